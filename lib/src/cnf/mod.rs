@@ -5,6 +5,33 @@ pub const MAX_CONCURRENT_TASKS: usize = 64;
 // Specifies how many subqueries will be processed recursively before the query fails.
 pub const MAX_RECURSIVE_QUERIES: usize = 16;
 
+// Specifies the maximum depth of a recursive graph traversal.
+pub const MAX_GRAPH_DEPTH: u32 = 256;
+
+// Specifies how many paths a recursive graph traversal will visit before the query fails.
+pub const MAX_GRAPH_PATHS: usize = 10_000;
+
+// Specifies how many times a conflicting statement transaction will be retried before the query fails.
+pub const MAX_TRANSACTION_RETRIES: u32 = 5;
+
+// Specifies the initial delay in milliseconds before a conflicting statement transaction is retried.
+pub const TRANSACTION_RETRY_BACKOFF: u64 = 10;
+
+// Specifies how many parsed queries are cached by each datastore.
+pub const QUERY_CACHE_SIZE: usize = 1000;
+
+// Specifies the maximum length of the query text which will be cached once parsed.
+pub const QUERY_CACHE_MAX_LENGTH: usize = 16 * 1024;
+
+// Specifies how many records are sent in each batch when streaming statement results.
+pub const STREAM_BATCH_SIZE: usize = 100;
+
+// Specifies how many records are indexed in each transaction when an index is built.
+pub const INDEX_BUILD_BATCH_SIZE: u32 = 1000;
+
+// Specifies how many records are processed in each transaction when a foreign table is built.
+pub const TABLE_BUILD_BATCH_SIZE: u32 = 1000;
+
 // The characters which are supported in server record IDs.
 pub const ID_CHARS: [char; 36] = [
 	'0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i',
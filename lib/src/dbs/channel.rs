@@ -6,11 +6,14 @@ use crate::dbs::Statement;
 use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::key::graph;
+use crate::key::history;
 use crate::key::thing;
+use crate::kvs::clock::nanos;
 use crate::sql::dir::Dir;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use channel::Sender;
+use std::collections::HashSet;
 
 impl Iterable {
 	pub(crate) async fn channel(
@@ -18,7 +21,7 @@ impl Iterable {
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		_stm: &Statement<'_>,
+		stm: &Statement<'_>,
 		chn: Sender<(Option<Thing>, Operable)>,
 	) -> Result<(), Error> {
 		if ctx.is_ok() {
@@ -33,8 +36,17 @@ impl Iterable {
 					// Check that the table exists
 					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 					// Fetch the data from the store
-					let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-					let val = txn.clone().lock().await.get(key).await?;
+					let val = match stm.version() {
+						Some(ver) => {
+							let vs = nanos(ver.0.timestamp_nanos());
+							let mut run = txn.lock().await;
+							run.get_version(opt.ns(), opt.db(), &v.tb, &v.id, vs).await?
+						}
+						None => {
+							let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
+							txn.clone().lock().await.get(key).await?
+						}
+					};
 					// Parse the data from the store
 					let val = Operable::Value(match val {
 						Some(v) => Value::from(v),
@@ -78,6 +90,13 @@ impl Iterable {
 				Iterable::Table(v) => {
 					// Check that the table exists
 					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v, opt.strict).await?;
+					// Process the record history at a point in time
+					if stm.version().is_some() {
+						let beg = history::prefix(opt.ns(), opt.db(), &v);
+						let end = history::suffix(opt.ns(), opt.db(), &v);
+						versions(ctx, txn, stm, &chn, beg, end).await?;
+						return Ok(());
+					}
 					// Prepare the start and end keys
 					let beg = thing::prefix(opt.ns(), opt.db(), &v);
 					let end = thing::suffix(opt.ns(), opt.db(), &v);
@@ -138,6 +157,13 @@ impl Iterable {
 				Iterable::Range(v) => {
 					// Check that the table exists
 					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
+					// Process the record history at a point in time
+					if stm.version().is_some() {
+						let beg = history::new(opt.ns(), opt.db(), &v.tb, &v.beg, u64::MAX);
+						let end = history::new(opt.ns(), opt.db(), &v.tb, &v.end, u64::MAX);
+						versions(ctx, txn, stm, &chn, beg.encode()?, end.encode()?).await?;
+						return Ok(());
+					}
 					// Prepare the start and end keys
 					let beg = thing::new(opt.ns(), opt.db(), &v.tb, &v.beg).encode().unwrap();
 					let end = thing::new(opt.ns(), opt.db(), &v.tb, &v.end).encode().unwrap();
@@ -195,6 +221,86 @@ impl Iterable {
 						break;
 					}
 				}
+				Iterable::Index(t, p) => {
+					// Check that the table exists
+					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &t, opt.strict).await?;
+					// Updated records can move to an index entry which is
+					// scanned later, so an UPDATE keeps track of the records
+					// it has processed, which other statements do not need
+					let mut seen: Option<HashSet<Vec<u8>>> =
+						match stm.is_select() || stm.is_delete() {
+							true => None,
+							false => Some(HashSet::new()),
+						};
+					// Loop over the index key ranges
+					for (r, (beg, end)) in p.rng.iter().enumerate() {
+						// Prepare the next holder key
+						let mut nxt: Option<Vec<u8>> = None;
+						// Loop until no more keys
+						loop {
+							// Check if the context is finished
+							if ctx.is_done() {
+								break;
+							}
+							// Get the next 1000 key-value entries
+							let res = match nxt {
+								None => {
+									let min = beg.clone();
+									let max = end.clone();
+									txn.clone().lock().await.scan(min..max, 1000).await?
+								}
+								Some(ref mut beg) => {
+									beg.push(0x00);
+									let min = beg.clone();
+									let max = end.clone();
+									txn.clone().lock().await.scan(min..max, 1000).await?
+								}
+							};
+							// If there are key-value entries then fetch them
+							if !res.is_empty() {
+								// Get total results
+								let n = res.len();
+								// Loop over results
+								for (i, (k, v)) in res.into_iter().enumerate() {
+									// Check the context
+									if ctx.is_done() {
+										break;
+									}
+									// Ready the next
+									if n == i + 1 {
+										nxt = Some(k.clone());
+									}
+									// Skip records which were already updated
+									if let Some(seen) = seen.as_mut() {
+										if !seen.insert(v.clone()) {
+											continue;
+										}
+									}
+									// Parse the record id from the index entry
+									let rid: Thing = (&v).into();
+									// Fetch the data from the store
+									let key = thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+									let val = txn.clone().lock().await.get(key).await?;
+									// Parse the data from the store
+									let val = match val {
+										Some(v) => Value::from(v),
+										None => Value::None,
+									};
+									// Skip records which were output at an earlier entry
+									if p.scanned(ctx, opt, txn, &rid, &val, (r, &k)).await? {
+										continue;
+									}
+									// Create a new operable value
+									let val = Operable::Value(val);
+									// Process the record
+									chn.send((Some(rid), val)).await?;
+								}
+								continue;
+							}
+							break;
+						}
+					}
+				}
 				Iterable::Edges(e) => {
 					// Pull out options
 					let ns = opt.ns();
@@ -309,8 +415,25 @@ impl Iterable {
 									// Parse the data from the store
 									let gra: crate::key::graph::Graph = (&k).into();
 									// Fetch the data from the store
-									let key = thing::new(opt.ns(), opt.db(), &gra.ft, &gra.fk);
-									let val = txn.clone().lock().await.get(key).await?;
+									let val = match stm.version() {
+										Some(ver) => {
+											let vs = nanos(ver.0.timestamp_nanos());
+											let mut run = txn.lock().await;
+											run.get_version(
+												opt.ns(),
+												opt.db(),
+												&gra.ft,
+												&gra.fk,
+												vs,
+											)
+											.await?
+										}
+										None => {
+											let key =
+												thing::new(opt.ns(), opt.db(), &gra.ft, &gra.fk);
+											txn.clone().lock().await.get(key).await?
+										}
+									};
 									let rid = Thing::from((gra.ft, gra.fk));
 									// Parse the data from the store
 									let val = Operable::Value(match val {
@@ -331,3 +454,85 @@ impl Iterable {
 		Ok(())
 	}
 }
+
+// Send the latest version of each record in a range
+// of the record history, as at the statement VERSION.
+// The versions of each record are ordered from newest
+// to oldest, so the first version at or before the
+// point in time is the version of the record.
+async fn versions(
+	ctx: &Context<'_>,
+	txn: &Transaction,
+	stm: &Statement<'_>,
+	chn: &Sender<(Option<Thing>, Operable)>,
+	beg: Vec<u8>,
+	end: Vec<u8>,
+) -> Result<(), Error> {
+	// Get the point in time to read at
+	let vs = match stm.version() {
+		Some(v) => nanos(v.0.timestamp_nanos()),
+		None => return Ok(()),
+	};
+	// Keep track of the last record which was seen
+	let mut cur: Option<Thing> = None;
+	// Prepare the next holder key
+	let mut nxt: Option<Vec<u8>> = None;
+	// Loop until no more keys
+	loop {
+		// Check if the context is finished
+		if ctx.is_done() {
+			return Ok(());
+		}
+		// Get the next 1000 key-value entries
+		let res = match nxt {
+			None => {
+				let min = beg.clone();
+				let max = end.clone();
+				txn.clone().lock().await.scan(min..max, 1000).await?
+			}
+			Some(ref mut beg) => {
+				beg.push(0x00);
+				let min = beg.clone();
+				let max = end.clone();
+				txn.clone().lock().await.scan(min..max, 1000).await?
+			}
+		};
+		// If there are key-value entries then fetch them
+		if !res.is_empty() {
+			// Get total results
+			let n = res.len();
+			// Loop over results
+			for (i, (k, v)) in res.into_iter().enumerate() {
+				// Check the context
+				if ctx.is_done() {
+					break;
+				}
+				// Ready the next
+				if n == i + 1 {
+					nxt = Some(k.clone());
+				}
+				// Parse the data from the store
+				let key: crate::key::history::History = (&k).into();
+				// Skip versions after the point in time
+				if key.vs() > vs {
+					continue;
+				}
+				let rid = Thing::from((key.tb, key.id));
+				// Skip the older versions of the record
+				if cur.as_ref() == Some(&rid) {
+					continue;
+				}
+				cur = Some(rid.clone());
+				// Send the latest version of the record
+				let val = Value::from(v);
+				if val.is_some() {
+					chn.send((Some(rid), Operable::Value(val))).await?;
+				}
+			}
+			continue;
+		}
+		break;
+	}
+	// Everything processed ok
+	Ok(())
+}
@@ -0,0 +1,122 @@
+use crate::dbs::Iterable;
+use crate::dbs::Statement;
+use crate::err::Error;
+use futures::lock::Mutex;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::sync::Arc;
+
+// A Next is passed to the Iterator of a SELECT statement, and
+// is used to collect the Cursor of the last record output when
+// the statement LIMIT was reached, so that a subsequent query
+// can resume scanning the table, range, or index from there.
+
+pub type Next = Arc<Mutex<Option<Cursor>>>;
+
+// A Cursor marks a position in the values iterated over by a
+// SELECT statement. It holds the index of the iterated value,
+// the index of the key range within that value, and the last
+// key which was scanned. Values which are not scanned, such as
+// individual records, are output in full, and have no key. The
+// cursor also holds a hash of the iterated values and the WHERE
+// clause of the query, so that a cursor is only accepted by the
+// query which it was output by.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Cursor {
+	// The hash of the iterated query
+	pub qid: u64,
+	// The index of the iterated value
+	pub pos: usize,
+	// The index of the scanned key range
+	pub rng: usize,
+	// The last key which was scanned
+	pub key: Vec<u8>,
+}
+
+impl Cursor {
+	// Compute the hash of the values iterated by a query
+	pub fn query(entries: &[Iterable], stm: &Statement<'_>) -> u64 {
+		let mut hash = Sha256::new();
+		for v in entries.iter() {
+			hash.update(v.explain().to_string());
+			hash.update([0]);
+		}
+		if let Some(v) = stm.conds() {
+			hash.update(v.to_string());
+		}
+		let out = hash.finalize();
+		u64::from_be_bytes([out[0], out[1], out[2], out[3], out[4], out[5], out[6], out[7]])
+	}
+	// Encode the cursor as an opaque string
+	pub fn encode(&self) -> String {
+		let mut out = String::with_capacity(32 + self.key.len() * 2);
+		let qid = self.qid.to_be_bytes();
+		let pos = (self.pos as u32).to_be_bytes();
+		let rng = (self.rng as u32).to_be_bytes();
+		for b in qid.iter().chain(pos.iter()).chain(rng.iter()).chain(self.key.iter()) {
+			let _ = write!(out, "{:02x}", b);
+		}
+		out
+	}
+	// Decode a cursor from an opaque string
+	pub fn decode(v: &str) -> Result<Cursor, Error> {
+		// Check the cursor string length
+		if v.len() < 32 || v.len() & 1 != 0 || !v.is_ascii() {
+			return Err(Error::InvalidCursor {
+				message: String::from("The cursor is malformed"),
+			});
+		}
+		// Decode the hexadecimal bytes
+		let mut out = Vec::with_capacity(v.len() / 2);
+		for i in (0..v.len()).step_by(2) {
+			match u8::from_str_radix(&v[i..i + 2], 16) {
+				Ok(b) => out.push(b),
+				Err(_) => {
+					return Err(Error::InvalidCursor {
+						message: String::from("The cursor is malformed"),
+					})
+				}
+			}
+		}
+		// Split out the cursor positions
+		let key = out.split_off(16);
+		let qid =
+			u64::from_be_bytes([out[0], out[1], out[2], out[3], out[4], out[5], out[6], out[7]]);
+		let pos = u32::from_be_bytes([out[8], out[9], out[10], out[11]]) as usize;
+		let rng = u32::from_be_bytes([out[12], out[13], out[14], out[15]]) as usize;
+		Ok(Cursor {
+			qid,
+			pos,
+			rng,
+			key,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn cursor_encode_decode() {
+		let cur = Cursor {
+			qid: 3,
+			pos: 1,
+			rng: 2,
+			key: b"/*test*test*person".to_vec(),
+		};
+		let enc = cur.encode();
+		assert!(enc.starts_with("00000000000000030000000100000002"));
+		assert_eq!(Cursor::decode(&enc).unwrap(), cur);
+	}
+
+	#[test]
+	fn cursor_decode_invalid() {
+		assert!(Cursor::decode("").is_err());
+		assert!(Cursor::decode("0000000100000002").is_err());
+		assert!(Cursor::decode("0000000000000003000000010000000x").is_err());
+		assert!(Cursor::decode("000000000000000300000001000000020").is_err());
+	}
+}
@@ -1,7 +1,13 @@
+use crate::cnf;
 use crate::ctx::Context;
+use crate::ctx::Reason;
+use crate::dbs::response::QueryType;
 use crate::dbs::response::Response;
 use crate::dbs::Auth;
+use crate::dbs::Chunk;
 use crate::dbs::Level;
+use crate::dbs::Next;
+use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::dbs::LOG;
@@ -12,25 +18,43 @@ use crate::sql::paths::NS;
 use crate::sql::query::Query;
 use crate::sql::statement::Statement;
 use crate::sql::value::Value;
+use channel::Receiver;
+use channel::Sender;
 use futures::lock::Mutex;
+use futures_timer::Delay;
+use rand::Rng;
 use std::sync::Arc;
+use std::time::Duration;
 use trice::Instant;
 
 pub struct Executor<'a> {
 	err: bool,
 	kvs: &'a Datastore,
 	txn: Option<Transaction>,
+	sender: Sender<Notification>,
+	receiver: Receiver<Notification>,
+	stream: Option<Sender<Chunk>>,
 }
 
 impl<'a> Executor<'a> {
 	pub fn new(kvs: &'a Datastore) -> Executor<'a> {
+		let (sender, receiver) = channel::unbounded();
 		Executor {
 			kvs,
 			txn: None,
 			err: false,
+			sender,
+			receiver,
+			stream: None,
 		}
 	}
 
+	// Stream the statement results to a channel
+	pub fn with_stream(mut self, chn: Sender<Chunk>) -> Executor<'a> {
+		self.stream = Some(chn);
+		self
+	}
+
 	fn txn(&self) -> Transaction {
 		match self.txn.as_ref() {
 			Some(txn) => txn.clone(),
@@ -54,7 +78,7 @@ impl<'a> Executor<'a> {
 		}
 	}
 
-	async fn commit(&mut self, local: bool) {
+	async fn commit(&mut self, local: bool) -> Result<(), Error> {
 		if local {
 			if let Some(txn) = self.txn.as_ref() {
 				match &self.err {
@@ -64,19 +88,27 @@ impl<'a> Executor<'a> {
 						if txn.cancel().await.is_err() {
 							self.err = true;
 						}
+						self.clear();
 						self.txn = None;
 					}
 					false => {
 						let txn = txn.clone();
 						let mut txn = txn.lock().await;
-						if txn.commit().await.is_err() {
-							self.err = true;
+						let res = txn.commit().await;
+						match &res {
+							Ok(_) => self.flush().await,
+							Err(_) => {
+								self.err = true;
+								self.clear();
+							}
 						}
 						self.txn = None;
+						return res;
 					}
 				}
 			}
 		}
+		Ok(())
 	}
 
 	async fn cancel(&mut self, local: bool) {
@@ -87,33 +119,75 @@ impl<'a> Executor<'a> {
 				if txn.cancel().await.is_err() {
 					self.err = true;
 				}
+				self.clear();
 				self.txn = None;
 			}
 		}
 	}
 
+	// Send any buffered notifications once committed
+	async fn flush(&self) {
+		if let Some(chn) = self.kvs.sender() {
+			while let Ok(v) = self.receiver.try_recv() {
+				let _ = chn.send(v).await;
+			}
+		}
+	}
+
+	// Discard any buffered notifications
+	fn clear(&self) {
+		while self.receiver.try_recv().is_ok() {}
+	}
+
+	// Output a statement response
+	async fn output(&self, out: &mut Vec<Response>, res: Response) {
+		match &self.stream {
+			Some(chn) => {
+				let _ = chn.send(Chunk::Response(res)).await;
+			}
+			None => out.push(res),
+		}
+	}
+
 	fn buf_cancel(&self, v: Response) -> Response {
 		Response {
 			sql: v.sql,
 			time: v.time,
 			result: Err(Error::QueryCancelled),
+			query_type: v.query_type,
+			next: None,
 		}
 	}
 
-	fn buf_commit(&self, v: Response) -> Response {
+	fn buf_commit(&self, v: Response, res: &Result<(), Error>) -> Response {
 		match &self.err {
 			true => Response {
 				sql: v.sql,
 				time: v.time,
 				result: match v.result {
-					Ok(_) => Err(Error::QueryNotExecuted),
+					Ok(_) => match res {
+						Err(Error::TxConflict) => Err(Error::TxConflict),
+						_ => Err(Error::QueryNotExecuted),
+					},
 					Err(e) => Err(e),
 				},
+				query_type: v.query_type,
+				next: None,
 			},
 			_ => v,
 		}
 	}
 
+	// Wait before retrying a conflicting transaction
+	async fn backoff(&self, attempt: u32) {
+		// Double the delay on each attempt
+		let max = cnf::TRANSACTION_RETRY_BACKOFF << attempt.min(10);
+		// Add jitter to spread out retries
+		let ms = rand::thread_rng().gen_range(max / 2..=max);
+		// Sleep for the delay
+		Delay::new(Duration::from_millis(ms)).await;
+	}
+
 	async fn set_ns(&self, ctx: &mut Context<'_>, opt: &mut Options, ns: &str) {
 		let mut session = ctx.value("session").unwrap_or(&Value::None).clone();
 		session.put(NS.as_ref(), ns.to_owned().into());
@@ -132,22 +206,37 @@ impl<'a> Executor<'a> {
 		&mut self,
 		mut ctx: Context<'_>,
 		mut opt: Options,
-		qry: Query,
+		qry: Arc<Query>,
 	) -> Result<Vec<Response>, Error> {
 		// Initialise buffer of responses
 		let mut buf: Vec<Response> = vec![];
 		// Initialise array of responses
 		let mut out: Vec<Response> = vec![];
+		// Buffer notifications until committed
+		if self.kvs.sender().is_some() {
+			opt.sender = Some(self.sender.clone());
+		}
+		// Register the query so that it can be killed
+		let kvs = self.kvs;
+		let run =
+			kvs.running.register(qry.clone(), opt.ns.clone(), opt.db.clone(), ctx.add_cancel());
+		opt.running = Some(kvs.running.clone());
+		// Continue index builds in the background if enabled
+		opt.builds = kvs.builds;
 		// Process all statements in query
-		for stm in qry.iter() {
+		for (i, stm) in qry.iter().enumerate() {
 			// Log the statement
 			debug!(target: LOG, "Executing: {}", stm);
+			// Mark the running statement
+			run.enter(i);
 			// Reset errors
 			if self.txn.is_none() {
 				self.err = false;
 			}
 			// Get the statement start time
 			let now = Instant::now();
+			// Collect the cursor of the next page
+			let next = Next::default();
 			// Process a single statement
 			let res = match stm {
 				// Specify runtime options
@@ -177,16 +266,18 @@ impl<'a> Executor<'a> {
 				// Cancel a running transaction
 				Statement::Cancel(_) => {
 					self.cancel(true).await;
-					buf = buf.into_iter().map(|v| self.buf_cancel(v)).collect();
-					out.append(&mut buf);
+					for v in buf.drain(..) {
+						self.output(&mut out, self.buf_cancel(v)).await;
+					}
 					self.txn = None;
 					continue;
 				}
 				// Commit a running transaction
 				Statement::Commit(_) => {
-					self.commit(true).await;
-					buf = buf.into_iter().map(|v| self.buf_commit(v)).collect();
-					out.append(&mut buf);
+					let res = self.commit(true).await;
+					for v in buf.drain(..) {
+						self.output(&mut out, self.buf_commit(v, &res)).await;
+					}
 					self.txn = None;
 					continue;
 				}
@@ -239,13 +330,14 @@ impl<'a> Executor<'a> {
 								}
 								_ => break,
 							}
-							// Cancel transaction
+							// Finalise transaction
 							match stm.writeable() {
-								true => self.commit(loc).await,
-								false => self.cancel(loc).await,
-							};
-							// Return nothing
-							Ok(Value::None)
+								true => self.commit(loc).await.map(|_| Value::None),
+								false => {
+									self.cancel(loc).await;
+									Ok(Value::None)
+								}
+							}
 						}
 					}
 				}
@@ -255,42 +347,63 @@ impl<'a> Executor<'a> {
 					true => Err(Error::QueryNotExecuted),
 					// Compute the statement normally
 					false => {
-						// Create a transaction
-						let loc = self.begin(stm.writeable()).await;
-						// Check the transaction
-						match self.err {
-							// We failed to create a transaction
-							true => Err(Error::TxFailure),
-							// The transaction began successfully
-							false => {
-								// Process the statement
-								let res = match stm.timeout() {
-									// There is a timeout clause
-									Some(timeout) => {
-										// Set statement timeout
-										let mut ctx = Context::new(&ctx);
+						// Count the conflicting attempts
+						let mut attempt = 0;
+						loop {
+							// Create a transaction
+							let loc = self.begin(stm.writeable()).await;
+							// Stream the results of read only statements
+							let opt = match loc && !stm.writeable() {
+								true => opt.stream(self.stream.clone()),
+								false => opt.stream(None),
+							};
+							// Output the cursor of paginated statements
+							let opt = opt.next(Some(next.clone()));
+							// Check the transaction
+							let res = match self.err {
+								// We failed to create a transaction
+								true => Err(Error::TxFailure),
+								// The transaction began successfully
+								false => {
+									// Set the statement timeout
+									let mut ctx = Context::new(&ctx);
+									if let Some(timeout) = stm.timeout().or(kvs.timeout) {
 										ctx.add_timeout(timeout);
-										// Process the statement
-										let res = stm.compute(&ctx, &opt, &self.txn(), None).await;
-										// Catch statement timeout
-										match ctx.is_timedout() {
-											true => Err(Error::QueryTimedout),
-											false => res,
+									}
+									// Process the statement
+									let res = stm.compute(&ctx, &opt, &self.txn(), None).await;
+									// Catch statement timeout or cancellation
+									let res = match ctx.done() {
+										Some(Reason::Timedout) => Err(Error::QueryTimedout),
+										Some(Reason::Canceled) => Err(Error::QueryKilled),
+										None => res,
+									};
+									// Finalise transaction
+									match res {
+										Ok(v) => match stm.writeable() {
+											true => self.commit(loc).await.map(|_| v),
+											false => {
+												self.cancel(loc).await;
+												Ok(v)
+											}
+										},
+										Err(e) => {
+											self.cancel(loc).await;
+											Err(e)
 										}
 									}
-									// There is no timeout clause
-									None => stm.compute(&ctx, &opt, &self.txn(), None).await,
-								};
-								// Finalise transaction
-								match &res {
-									Ok(_) => match stm.writeable() {
-										true => self.commit(loc).await,
-										false => self.cancel(loc).await,
-									},
-									Err(_) => self.cancel(loc).await,
-								};
+								}
+							};
+							// Retry implicit transactions which conflicted
+							match res {
+								Err(Error::TxConflict) if loc && attempt < opt.retries => {
+									debug!(target: LOG, "Retrying conflicting statement: {}", stm);
+									self.err = false;
+									self.backoff(attempt).await;
+									attempt += 1;
+								}
 								// Return the result
-								res
+								res => break res,
 							}
 						}
 					}
@@ -298,6 +411,12 @@ impl<'a> Executor<'a> {
 			};
 			// Get the statement end time
 			let dur = now.elapsed();
+			// Get the statement type
+			let query_type = match stm {
+				Statement::Live(_) => QueryType::Live,
+				Statement::Kill(_) => QueryType::Kill,
+				_ => QueryType::Other,
+			};
 			// Produce the response
 			let res = match res {
 				Ok(v) => Response {
@@ -307,6 +426,8 @@ impl<'a> Executor<'a> {
 					},
 					time: dur,
 					result: Ok(v),
+					query_type,
+					next: next.lock().await.take().map(|c| c.encode()),
 				},
 				Err(e) => {
 					// Produce the response
@@ -317,6 +438,8 @@ impl<'a> Executor<'a> {
 						},
 						time: dur,
 						result: Err(e),
+						query_type,
+						next: None,
 					};
 					// Mark the error
 					self.err = true;
@@ -333,7 +456,7 @@ impl<'a> Executor<'a> {
 					}
 					_ => buf.push(res),
 				},
-				None => out.push(res),
+				None => self.output(&mut out, res).await,
 			}
 		}
 		// Return responses
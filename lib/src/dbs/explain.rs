@@ -0,0 +1,158 @@
+use crate::dbs::Iterable;
+use crate::dbs::Statement;
+use crate::sql::object::Object;
+use crate::sql::value::Value;
+use futures::lock::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+// An Explain is passed to the Iterator of an EXPLAIN statement,
+// and is used to collect information about how the statement
+// is processed. When the statement is analyzed, the Iterator
+// records the time taken and the number of records produced by
+// each stage, otherwise the statement is planned but not run.
+
+pub type Explain = Arc<Mutex<Explanation>>;
+
+#[derive(Debug, Default)]
+pub struct Explanation {
+	// Whether the statement is run
+	pub analyze: bool,
+	// Whether the iterables are processed in parallel
+	pub parallel: bool,
+	// The iterables which are processed
+	pub iterables: Vec<Value>,
+	// The stages which process the results
+	pub stages: Vec<Stage>,
+	// The total processing time of the statement
+	pub time: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub struct Stage {
+	// The name of the stage
+	pub name: &'static str,
+	// The statement clause processed by the stage
+	pub detail: Option<String>,
+	// The time taken to process the stage
+	pub time: Option<Duration>,
+	// The number of records after the stage
+	pub records: Option<usize>,
+}
+
+impl Explanation {
+	// Create a new explanation for a statement
+	pub fn new(analyze: bool) -> Explain {
+		Arc::new(Mutex::new(Explanation {
+			analyze,
+			..Explanation::default()
+		}))
+	}
+	// Describe the iterables and stages of a statement
+	pub(crate) fn prepare(&mut self, entries: &[Iterable], stm: &Statement<'_>) {
+		self.parallel = stm.parallel();
+		self.iterables = entries.iter().map(Iterable::explain).collect();
+		self.stages = vec![Stage::new("ITERATE", None)];
+		if let Some(v) = stm.split() {
+			self.stages.push(Stage::new("SPLIT", Some(v.to_string())));
+		}
+		if let Some(v) = stm.group() {
+			self.stages.push(Stage::new("GROUP", Some(v.to_string())));
+		}
+		if let Some(v) = stm.expr().filter(|v| v.windowed()) {
+			let v = v.windows().map(|(f, w, _)| format!("{} {}", f, w)).collect::<Vec<_>>();
+			self.stages.push(Stage::new("WINDOW", Some(v.join(", "))));
+		}
+		if let Some(v) = stm.order() {
+			self.stages.push(Stage::new("ORDER", Some(v.to_string())));
+		}
+		if let Some(v) = stm.start() {
+			self.stages.push(Stage::new("START", Some(v.to_string())));
+		}
+		if let Some(v) = stm.limit() {
+			self.stages.push(Stage::new("LIMIT", Some(v.to_string())));
+		}
+		if let Some(v) = stm.join() {
+			self.stages.push(Stage::new("JOIN", Some(v.to_string())));
+		}
+		if let Some(v) = stm.fetch() {
+			self.stages.push(Stage::new("FETCH", Some(v.to_string())));
+		}
+	}
+	// Record the time taken and records output by a stage
+	pub(crate) fn record(&mut self, name: &str, time: Duration, records: usize) {
+		if let Some(s) = self.stages.iter_mut().find(|s| s.name == name) {
+			s.time = Some(time);
+			s.records = Some(records);
+		}
+	}
+}
+
+impl Stage {
+	fn new(name: &'static str, detail: Option<String>) -> Stage {
+		Stage {
+			name,
+			detail,
+			time: None,
+			records: None,
+		}
+	}
+}
+
+impl From<&Stage> for Value {
+	fn from(v: &Stage) -> Value {
+		let mut out = map! {
+			String::from("stage") => v.name.into(),
+		};
+		if let Some(detail) = &v.detail {
+			out.insert(String::from("detail"), detail.as_str().into());
+		}
+		if let Some(time) = v.time {
+			out.insert(String::from("time"), crate::sql::duration::Duration::from(time).into());
+		}
+		if let Some(records) = v.records {
+			out.insert(String::from("records"), records.into());
+		}
+		Value::Object(Object(out))
+	}
+}
+
+impl From<&Explanation> for Value {
+	fn from(v: &Explanation) -> Value {
+		let mut out = map! {
+			String::from("analyze") => v.analyze.into(),
+			String::from("parallel") => v.parallel.into(),
+			String::from("iterables") => v.iterables.clone().into(),
+			String::from("stages") => v.stages.iter().map(Value::from).collect::<Vec<_>>().into(),
+		};
+		if let Some(time) = v.time {
+			out.insert(String::from("time"), crate::sql::duration::Duration::from(time).into());
+		}
+		if let Some(records) = v.stages.last().and_then(|s| s.records) {
+			out.insert(String::from("records"), records.into());
+		}
+		Value::Object(Object(out))
+	}
+}
+
+impl Iterable {
+	// Describe how this iterable is processed
+	pub(super) fn explain(&self) -> Value {
+		let (kind, detail): (&str, Value) = match self {
+			Iterable::Value(v) => ("Value", v.clone()),
+			Iterable::Table(v) => ("Table", v.to_string().into()),
+			Iterable::Index(_, p) => ("Index", p.to_string().into()),
+			Iterable::Thing(v) => ("Thing", v.clone().into()),
+			Iterable::Range(v) => ("Range", v.to_string().into()),
+			Iterable::Edges(v) => ("Edges", v.to_string().into()),
+			Iterable::Mergeable(v, _) => ("Mergeable", v.clone().into()),
+			Iterable::Relatable(f, v, w) => {
+				("Relatable", format!("{} -> {} -> {}", f, v, w).into())
+			}
+		};
+		Value::Object(Object(map! {
+			String::from("type") => kind.into(),
+			String::from("detail") => detail,
+		}))
+	}
+}
@@ -7,10 +7,13 @@ use crate::dbs::Statement;
 use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::key::graph;
+use crate::key::history;
 use crate::key::thing;
+use crate::kvs::clock::nanos;
 use crate::sql::dir::Dir;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
+use std::collections::HashSet;
 
 impl Iterable {
 	pub(crate) async fn iterate(
@@ -33,8 +36,17 @@ impl Iterable {
 					// Check that the table exists
 					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 					// Fetch the data from the store
-					let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-					let val = txn.clone().lock().await.get(key).await?;
+					let val = match stm.version() {
+						Some(ver) => {
+							let vs = nanos(ver.0.timestamp_nanos());
+							let mut run = txn.lock().await;
+							run.get_version(opt.ns(), opt.db(), &v.tb, &v.id, vs).await?
+						}
+						None => {
+							let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
+							txn.clone().lock().await.get(key).await?
+						}
+					};
 					// Parse the data from the store
 					let val = Operable::Value(match val {
 						Some(v) => Value::from(v),
@@ -78,11 +90,18 @@ impl Iterable {
 				Iterable::Table(v) => {
 					// Check that the table exists
 					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v, opt.strict).await?;
+					// Process the record history at a point in time
+					if stm.version().is_some() {
+						let beg = history::prefix(opt.ns(), opt.db(), &v);
+						let end = history::suffix(opt.ns(), opt.db(), &v);
+						versions(ctx, opt, txn, stm, ite, beg, end).await?;
+						return Ok(());
+					}
 					// Prepare the start and end keys
 					let beg = thing::prefix(opt.ns(), opt.db(), &v);
 					let end = thing::suffix(opt.ns(), opt.db(), &v);
 					// Prepare the next holder key
-					let mut nxt: Option<Vec<u8>> = None;
+					let mut nxt = ite.resume(0, &beg, &end)?;
 					// Loop until no more keys
 					loop {
 						// Check if the context is finished
@@ -117,6 +136,8 @@ impl Iterable {
 								if n == i + 1 {
 									nxt = Some(k.clone());
 								}
+								// Mark the record position
+								ite.mark(0, &k);
 								// Parse the data from the store
 								let key: crate::key::thing::Thing = (&k).into();
 								let val: crate::sql::value::Value = (&v).into();
@@ -134,11 +155,18 @@ impl Iterable {
 				Iterable::Range(v) => {
 					// Check that the table exists
 					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
+					// Process the record history at a point in time
+					if stm.version().is_some() {
+						let beg = history::new(opt.ns(), opt.db(), &v.tb, &v.beg, u64::MAX);
+						let end = history::new(opt.ns(), opt.db(), &v.tb, &v.end, u64::MAX);
+						versions(ctx, opt, txn, stm, ite, beg.encode()?, end.encode()?).await?;
+						return Ok(());
+					}
 					// Prepare the start and end keys
 					let beg = thing::new(opt.ns(), opt.db(), &v.tb, &v.beg).encode().unwrap();
 					let end = thing::new(opt.ns(), opt.db(), &v.tb, &v.end).encode().unwrap();
 					// Prepare the next holder key
-					let mut nxt: Option<Vec<u8>> = None;
+					let mut nxt = ite.resume(0, &beg, &end)?;
 					// Loop until no more keys
 					loop {
 						// Check if the context is finished
@@ -173,6 +201,8 @@ impl Iterable {
 								if n == i + 1 {
 									nxt = Some(k.clone());
 								}
+								// Mark the record position
+								ite.mark(0, &k);
 								// Parse the data from the store
 								let key: crate::key::thing::Thing = (&k).into();
 								let val: crate::sql::value::Value = (&v).into();
@@ -187,6 +217,92 @@ impl Iterable {
 						break;
 					}
 				}
+				Iterable::Index(t, p) => {
+					// Check that the table exists
+					txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &t, opt.strict).await?;
+					// Updated records can move to an index entry which is
+					// scanned later, so an UPDATE keeps track of the records
+					// it has processed, which other statements do not need
+					let mut seen: Option<HashSet<Vec<u8>>> =
+						match stm.is_select() || stm.is_delete() {
+							true => None,
+							false => Some(HashSet::new()),
+						};
+					// Loop over the index key ranges
+					for (r, (beg, end)) in p.rng.iter().enumerate() {
+						// Skip ranges passed by the cursor
+						if ite.skip(r) {
+							continue;
+						}
+						// Prepare the next holder key
+						let mut nxt = ite.resume(r, beg, end)?;
+						// Loop until no more keys
+						loop {
+							// Check if the context is finished
+							if ctx.is_done() {
+								break;
+							}
+							// Get the next 1000 key-value entries
+							let res = match nxt {
+								None => {
+									let min = beg.clone();
+									let max = end.clone();
+									txn.clone().lock().await.scan(min..max, 1000).await?
+								}
+								Some(ref mut beg) => {
+									beg.push(0x00);
+									let min = beg.clone();
+									let max = end.clone();
+									txn.clone().lock().await.scan(min..max, 1000).await?
+								}
+							};
+							// If there are key-value entries then fetch them
+							if !res.is_empty() {
+								// Get total results
+								let n = res.len();
+								// Loop over results
+								for (i, (k, v)) in res.into_iter().enumerate() {
+									// Check the context
+									if ctx.is_done() {
+										break;
+									}
+									// Ready the next
+									if n == i + 1 {
+										nxt = Some(k.clone());
+									}
+									// Skip records which were already updated
+									if let Some(seen) = seen.as_mut() {
+										if !seen.insert(v.clone()) {
+											continue;
+										}
+									}
+									// Parse the record id from the index entry
+									let rid: Thing = (&v).into();
+									// Fetch the data from the store
+									let key = thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+									let val = txn.clone().lock().await.get(key).await?;
+									// Parse the data from the store
+									let val = match val {
+										Some(v) => Value::from(v),
+										None => Value::None,
+									};
+									// Skip records which were output at an earlier entry
+									if p.scanned(ctx, opt, txn, &rid, &val, (r, &k)).await? {
+										continue;
+									}
+									// Mark the record position
+									ite.mark(r, &k);
+									// Create a new operable value
+									let val = Operable::Value(val);
+									// Process the record
+									ite.process(ctx, opt, txn, stm, Some(rid), val).await;
+								}
+								continue;
+							}
+							break;
+						}
+					}
+				}
 				Iterable::Edges(e) => {
 					// Pull out options
 					let ns = opt.ns();
@@ -257,9 +373,13 @@ impl Iterable {
 						},
 					};
 					//
-					for (beg, end) in keys.iter() {
+					for (r, (beg, end)) in keys.iter().enumerate() {
+						// Skip ranges passed by the cursor
+						if ite.skip(r) {
+							continue;
+						}
 						// Prepare the next holder key
-						let mut nxt: Option<Vec<u8>> = None;
+						let mut nxt = ite.resume(r, beg, end)?;
 						// Loop until no more keys
 						loop {
 							// Check if the context is finished
@@ -298,11 +418,30 @@ impl Iterable {
 									if n == i + 1 {
 										nxt = Some(k.clone());
 									}
+									// Mark the record position
+									ite.mark(r, &k);
 									// Parse the data from the store
 									let gra: crate::key::graph::Graph = (&k).into();
 									// Fetch the data from the store
-									let key = thing::new(opt.ns(), opt.db(), &gra.ft, &gra.fk);
-									let val = txn.clone().lock().await.get(key).await?;
+									let val = match stm.version() {
+										Some(ver) => {
+											let vs = nanos(ver.0.timestamp_nanos());
+											let mut run = txn.lock().await;
+											run.get_version(
+												opt.ns(),
+												opt.db(),
+												&gra.ft,
+												&gra.fk,
+												vs,
+											)
+											.await?
+										}
+										None => {
+											let key =
+												thing::new(opt.ns(), opt.db(), &gra.ft, &gra.fk);
+											txn.clone().lock().await.get(key).await?
+										}
+									};
 									let rid = Thing::from((gra.ft, gra.fk));
 									// Parse the data from the store
 									let val = Operable::Value(match val {
@@ -323,3 +462,86 @@ impl Iterable {
 		Ok(())
 	}
 }
+
+// Process the latest version of each record in a range
+// of the record history, as at the statement VERSION.
+// The versions of each record are ordered from newest
+// to oldest, so the first version at or before the
+// point in time is the version of the record.
+async fn versions(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	stm: &Statement<'_>,
+	ite: &mut Iterator,
+	beg: Vec<u8>,
+	end: Vec<u8>,
+) -> Result<(), Error> {
+	// Get the point in time to read at
+	let vs = match stm.version() {
+		Some(v) => nanos(v.0.timestamp_nanos()),
+		None => return Ok(()),
+	};
+	// Keep track of the last record which was seen
+	let mut cur: Option<Thing> = None;
+	// Prepare the next holder key
+	let mut nxt: Option<Vec<u8>> = None;
+	// Loop until no more keys
+	loop {
+		// Check if the context is finished
+		if ctx.is_done() {
+			return Ok(());
+		}
+		// Get the next 1000 key-value entries
+		let res = match nxt {
+			None => {
+				let min = beg.clone();
+				let max = end.clone();
+				txn.clone().lock().await.scan(min..max, 1000).await?
+			}
+			Some(ref mut beg) => {
+				beg.push(0x00);
+				let min = beg.clone();
+				let max = end.clone();
+				txn.clone().lock().await.scan(min..max, 1000).await?
+			}
+		};
+		// If there are key-value entries then fetch them
+		if !res.is_empty() {
+			// Get total results
+			let n = res.len();
+			// Loop over results
+			for (i, (k, v)) in res.into_iter().enumerate() {
+				// Check the context
+				if ctx.is_done() {
+					break;
+				}
+				// Ready the next
+				if n == i + 1 {
+					nxt = Some(k.clone());
+				}
+				// Parse the data from the store
+				let key: crate::key::history::History = (&k).into();
+				// Skip versions after the point in time
+				if key.vs() > vs {
+					continue;
+				}
+				let rid = Thing::from((key.tb, key.id));
+				// Skip the older versions of the record
+				if cur.as_ref() == Some(&rid) {
+					continue;
+				}
+				cur = Some(rid.clone());
+				// Process the latest version of the record
+				let val = Value::from(v);
+				if val.is_some() {
+					ite.process(ctx, opt, txn, stm, Some(rid), Operable::Value(val)).await;
+				}
+			}
+			continue;
+		}
+		break;
+	}
+	// Everything processed ok
+	Ok(())
+}
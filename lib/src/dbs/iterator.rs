@@ -1,27 +1,36 @@
+use crate::cnf;
 use crate::ctx::Canceller;
 use crate::ctx::Context;
+use crate::dbs::Chunk;
+use crate::dbs::Cursor;
+use crate::dbs::Explain;
 use crate::dbs::Options;
+use crate::dbs::Plan;
 use crate::dbs::Statement;
 use crate::dbs::Transaction;
 use crate::dbs::LOG;
 use crate::doc::Document;
 use crate::err::Error;
+use crate::idx::search;
 use crate::sql::array::Array;
 use crate::sql::edges::Edges;
 use crate::sql::field::Field;
+use crate::sql::index::Index;
 use crate::sql::part::Part;
 use crate::sql::range::Range;
 use crate::sql::table::Table;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use async_recursion::async_recursion;
-use std::cmp::Ordering;
+use channel::Sender;
 use std::collections::BTreeMap;
 use std::mem;
+use trice::Instant;
 
 pub enum Iterable {
 	Value(Value),
 	Table(Table),
+	Index(Table, Plan),
 	Thing(Thing),
 	Range(Range),
 	Edges(Edges),
@@ -51,6 +60,26 @@ pub struct Iterator {
 	results: Vec<Value>,
 	// Iterator input values
 	entries: Vec<Iterable>,
+	// Iterator output stream
+	stream: Option<Sender<Chunk>>,
+	// Iterator accepted results
+	count: usize,
+	// Iterator skipped results
+	skipped: usize,
+	// Iterator streamed results
+	sent: usize,
+	// Iterator query hash
+	qid: u64,
+	// Iterator resume position
+	after: Option<Cursor>,
+	// Iterator current input value
+	pos: usize,
+	// Iterator current record position
+	cur: Option<Cursor>,
+	// Iterator last accepted record position
+	last: Option<Cursor>,
+	// Iterator record positions are tracked
+	paged: bool,
 }
 
 impl Iterator {
@@ -74,31 +103,307 @@ impl Iterator {
 	) -> Result<Value, Error> {
 		// Log the statement
 		trace!(target: LOG, "Iterating: {}", stm);
+		// Check if the statement is explained
+		let exp = opt.explain.clone();
+		// Check if the statement is streamed
+		let out = opt.stream.clone();
+		// Check if the statement is paginated
+		let nxt = opt.next.clone();
+		let opt = &opt.explain(None).stream(None).next(None);
 		// Enable context override
 		let mut ctx = Context::new(ctx);
-		self.run = ctx.add_cancel();
+		// Plan any index scans
+		self.prepare_plan(&ctx, opt, txn, stm).await?;
+		// Identify the iterated values for any cursors
+		if nxt.is_some() || stm.after().is_some() {
+			self.qid = Cursor::query(&self.entries, stm);
+		}
+		// Resume from any AFTER cursor
+		self.prepare_after(&ctx, opt, txn, stm).await?;
+		// Process an explained statement
+		if let Some(exp) = exp {
+			return self.explain(&mut ctx, opt, txn, stm, exp).await;
+		}
+		// Stream results which need no sorting or grouping
+		if stm.split().is_none()
+			&& stm.group().is_none()
+			&& stm.order().is_none()
+			&& !matches!(stm.expr(), Some(v) if v.windowed())
+		{
+			self.stream = out;
+		}
+		// Track record positions for limited scans
+		self.paged = nxt.is_some() && stm.limit().is_some() && self.pageable(stm);
+		// Run any full-text searches
+		self.prepare_search(&mut ctx, opt, txn).await?;
 		// Process prepared values
 		self.iterate(&ctx, opt, txn, stm).await?;
 		// Return any document errors
 		if let Some(e) = self.error.take() {
 			return Err(e);
 		}
+		// Output the cursor if the limit was reached
+		if let (Some(nxt), Some(l)) = (nxt, stm.limit()) {
+			if self.paged && self.count >= l.0 + stm.start().map_or(0, |s| s.0) {
+				*nxt.lock().await = self.last.take();
+			}
+		}
+		// Send any remaining streamed results
+		if self.stream.is_some() {
+			self.flush(&ctx, opt, txn, stm, true).await;
+			// Return any fetch errors
+			if let Some(e) = self.error.take() {
+				return Err(e);
+			}
+			// The results have been output
+			return Ok(Value::Array(Array::new()));
+		}
 		// Process any SPLIT clause
 		self.output_split(&ctx, opt, txn, stm).await?;
 		// Process any GROUP clause
 		self.output_group(&ctx, opt, txn, stm).await?;
+		// Process any window functions
+		self.output_window(&ctx, opt, txn, stm).await?;
 		// Process any ORDER clause
 		self.output_order(&ctx, opt, txn, stm).await?;
 		// Process any START clause
 		self.output_start(&ctx, opt, txn, stm).await?;
 		// Process any LIMIT clause
 		self.output_limit(&ctx, opt, txn, stm).await?;
+		// Process any JOIN clauses
+		self.output_join(&ctx, opt, txn, stm).await?;
 		// Process any FETCH clause
 		self.output_fetch(&ctx, opt, txn, stm).await?;
 		// Output the results
 		Ok(mem::take(&mut self.results).into())
 	}
 
+	// Process the records and explain each stage
+	async fn explain(
+		&mut self,
+		ctx: &mut Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		exp: Explain,
+	) -> Result<Value, Error> {
+		// Claim the explanation
+		let mut exp = exp.lock().await;
+		// Describe the statement stages
+		exp.prepare(&self.entries, stm);
+		// Check if the statement should be run
+		if !exp.analyze {
+			return Ok(Value::None);
+		}
+		// Get the statement start time
+		let beg = Instant::now();
+		// Process prepared values
+		let now = Instant::now();
+		self.prepare_search(ctx, opt, txn).await?;
+		self.iterate(ctx, opt, txn, stm).await?;
+		exp.record("ITERATE", now.elapsed(), self.results.len());
+		// Return any document errors
+		if let Some(e) = self.error.take() {
+			return Err(e);
+		}
+		// Process any SPLIT clause
+		let now = Instant::now();
+		self.output_split(ctx, opt, txn, stm).await?;
+		exp.record("SPLIT", now.elapsed(), self.results.len());
+		// Process any GROUP clause
+		let now = Instant::now();
+		self.output_group(ctx, opt, txn, stm).await?;
+		exp.record("GROUP", now.elapsed(), self.results.len());
+		// Process any window functions
+		let now = Instant::now();
+		self.output_window(ctx, opt, txn, stm).await?;
+		exp.record("WINDOW", now.elapsed(), self.results.len());
+		// Process any ORDER clause
+		let now = Instant::now();
+		self.output_order(ctx, opt, txn, stm).await?;
+		exp.record("ORDER", now.elapsed(), self.results.len());
+		// Process any START clause
+		let now = Instant::now();
+		self.output_start(ctx, opt, txn, stm).await?;
+		exp.record("START", now.elapsed(), self.results.len());
+		// Process any LIMIT clause
+		let now = Instant::now();
+		self.output_limit(ctx, opt, txn, stm).await?;
+		exp.record("LIMIT", now.elapsed(), self.results.len());
+		// Process any JOIN clauses
+		let now = Instant::now();
+		self.output_join(ctx, opt, txn, stm).await?;
+		exp.record("JOIN", now.elapsed(), self.results.len());
+		// Process any FETCH clause
+		let now = Instant::now();
+		self.output_fetch(ctx, opt, txn, stm).await?;
+		exp.record("FETCH", now.elapsed(), self.results.len());
+		// Record the total processing time
+		exp.time = Some(beg.elapsed());
+		// The results are not output
+		Ok(Value::None)
+	}
+
+	#[inline]
+	async fn prepare_plan(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Indexes only contain the latest record versions
+		if stm.version().is_some() {
+			return Ok(());
+		}
+		if let Some(cond) = stm.conds() {
+			// Loop over each prepared value
+			for v in self.entries.iter_mut() {
+				// Check if the table can be scanned using an index
+				if let Iterable::Table(tb) = v {
+					if let Some(plan) = Plan::new(ctx, opt, txn, tb, cond).await? {
+						*v = Iterable::Index(tb.clone(), plan);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	#[inline]
+	async fn prepare_search(
+		&mut self,
+		ctx: &mut Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<(), Error> {
+		// The search results of each table
+		let mut res = BTreeMap::new();
+		// Loop over each prepared value
+		for v in mem::take(&mut self.entries) {
+			match v {
+				// Look up the records which match a full-text query
+				Iterable::Index(tb, plan) if plan.qry.is_some() => {
+					if let (Index::Search(sc), Some(qry)) = (&plan.ix.index, &plan.qry) {
+						let run = txn.clone();
+						let mut run = run.lock().await;
+						let hits = search::search(&mut run, opt, &plan.ix, sc, qry).await?;
+						drop(run);
+						// Store the score of each record
+						let mut scores = BTreeMap::new();
+						for (rid, score) in hits {
+							scores.insert(rid.id.to_string(), Value::from(score));
+							self.entries.push(Iterable::Thing(rid));
+						}
+						// Store the details for the search functions
+						res.insert(
+							tb.to_string(),
+							Value::from(map! {
+								String::from("index") => Value::from(sc.to_string()),
+								String::from("terms") => Value::from(search::query(sc, qry).into_iter().map(Value::from).collect::<Vec<_>>()),
+								String::from("scores") => Value::from(scores),
+							}),
+						);
+					}
+				}
+				v => self.entries.push(v),
+			}
+		}
+		// Make the search results available to the documents
+		if !res.is_empty() {
+			ctx.add_value("search".into(), Value::from(res));
+		}
+		Ok(())
+	}
+
+	#[inline]
+	async fn prepare_after(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		if let Some(after) = stm.after() {
+			// Cursors follow the natural key order
+			if !self.pageable(stm) {
+				return Err(Error::InvalidCursor {
+					message: String::from(
+						"AFTER can not be used with SPLIT, GROUP, ORDER, VERSION, or PARALLEL clauses",
+					),
+				});
+			}
+			// Compute the cursor to resume from
+			self.after = after.compute(ctx, opt, txn).await?;
+			// Ensure the cursor was output by this query
+			if matches!(&self.after, Some(c) if c.qid != self.qid) {
+				return Err(Error::InvalidCursor {
+					message: String::from("The cursor does not belong to this query"),
+				});
+			}
+		}
+		Ok(())
+	}
+
+	// Check if the results follow the natural key order
+	fn pageable(&self, stm: &Statement<'_>) -> bool {
+		stm.split().is_none()
+			&& stm.group().is_none()
+			&& stm.order().is_none()
+			&& stm.version().is_none()
+			&& !stm.parallel()
+	}
+
+	// Prepare to iterate a value unless passed by the cursor
+	fn enter(&mut self, pos: usize) -> bool {
+		if let Some(c) = &self.after {
+			if pos < c.pos || (pos == c.pos && c.key.is_empty()) {
+				return false;
+			}
+		}
+		self.pos = pos;
+		self.cur = None;
+		true
+	}
+
+	// Check if a scanned key range was passed by the cursor
+	pub(crate) fn skip(&self, rng: usize) -> bool {
+		matches!(&self.after, Some(c) if c.pos == self.pos && rng < c.rng)
+	}
+
+	// Get the key to resume a scanned key range after
+	pub(crate) fn resume(
+		&self,
+		rng: usize,
+		beg: &[u8],
+		end: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		match &self.after {
+			Some(c) if c.pos == self.pos && c.rng == rng => {
+				// Ensure the key is within the key range
+				if c.key.as_slice() < beg || c.key.as_slice() >= end {
+					return Err(Error::InvalidCursor {
+						message: String::from("The cursor does not belong to this query"),
+					});
+				}
+				Ok(Some(c.key.clone()))
+			}
+			_ => Ok(None),
+		}
+	}
+
+	// Mark the position of the record being processed
+	pub(crate) fn mark(&mut self, rng: usize, key: &[u8]) {
+		if self.paged {
+			self.cur = Some(Cursor {
+				qid: self.qid,
+				pos: self.pos,
+				rng,
+				key: key.to_vec(),
+			});
+		}
+	}
+
 	#[inline]
 	async fn output_split(
 		&mut self,
@@ -153,6 +458,19 @@ impl Iterator {
 	) -> Result<(), Error> {
 		if let Some(fields) = stm.expr() {
 			if let Some(groups) = stm.group() {
+				// Check that window function inputs are grouped
+				for (f, _, _) in fields.windows() {
+					match f.args().first() {
+						None => (),
+						Some(Value::Function(g)) if g.is_aggregate() => (),
+						Some(Value::Idiom(v)) if groups.iter().any(|g| g.0 == *v) => (),
+						Some(v) => {
+							return Err(Error::InvalidWindow {
+								value: v.to_string(),
+							})
+						}
+					}
+				}
 				// Create the new grouped collection
 				let mut grp: BTreeMap<Array, Array> = BTreeMap::new();
 				// Get the query result
@@ -221,6 +539,19 @@ impl Iterator {
 							}
 						}
 					}
+					// Pass window function inputs through
+					for (f, _, i) in fields.windows() {
+						let x = match f.args().first() {
+							// Aggregate inputs are calculated over the group
+							Some(Value::Function(g)) if g.is_aggregate() => {
+								let x = vals.all().get(ctx, opt, txn, &i).await?;
+								g.aggregate(x).compute(ctx, opt, txn, None).await?
+							}
+							// Grouped inputs are the same for each record
+							_ => vals.first().pick(&i),
+						};
+						obj.set(ctx, opt, txn, &i, x).await?;
+					}
 					// Add the object to the results
 					self.results.push(obj);
 				}
@@ -229,6 +560,23 @@ impl Iterator {
 		Ok(())
 	}
 
+	#[inline]
+	async fn output_window(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		if let Some(fields) = stm.expr() {
+			// Loop over each window function
+			for (f, w, i) in fields.windows() {
+				w.compute(ctx, opt, txn, f, &i, &mut self.results).await?;
+			}
+		}
+		Ok(())
+	}
+
 	#[inline]
 	async fn output_order(
 		&mut self,
@@ -239,31 +587,7 @@ impl Iterator {
 	) -> Result<(), Error> {
 		if let Some(orders) = stm.order() {
 			// Sort the full result set
-			self.results.sort_by(|a, b| {
-				// Loop over each order clause
-				for order in orders.iter() {
-					// Reverse the ordering if DESC
-					let o = match order.random {
-						true => {
-							let a = rand::random::<f64>();
-							let b = rand::random::<f64>();
-							a.partial_cmp(&b)
-						}
-						false => match order.direction {
-							true => a.compare(b, order, order.collate, order.numeric),
-							false => b.compare(a, order, order.collate, order.numeric),
-						},
-					};
-					//
-					match o {
-						Some(Ordering::Greater) => return Ordering::Greater,
-						Some(Ordering::Equal) => continue,
-						Some(Ordering::Less) => return Ordering::Less,
-						None => continue,
-					}
-				}
-				Ordering::Equal
-			})
+			self.results.sort_by(|a, b| orders.compare(a, b))
 		}
 		Ok(())
 	}
@@ -296,6 +620,23 @@ impl Iterator {
 		Ok(())
 	}
 
+	#[inline]
+	async fn output_join(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		if let Some(joins) = stm.join() {
+			// Loop over each join clause
+			for join in joins.iter() {
+				join.compute(ctx, opt, txn, &mut self.results).await?;
+			}
+		}
+		Ok(())
+	}
+
 	#[inline]
 	async fn output_fetch(
 		&mut self,
@@ -335,6 +676,49 @@ impl Iterator {
 		Ok(())
 	}
 
+	// Send a batch of results to the output stream
+	async fn flush(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		all: bool,
+	) {
+		if let Some(chn) = self.stream.clone() {
+			if all || self.results.len() >= cnf::STREAM_BATCH_SIZE {
+				// Process any START clause
+				if let Some(v) = stm.start() {
+					let n = v.0.saturating_sub(self.skipped).min(self.results.len());
+					self.results.drain(..n);
+					self.skipped += n;
+				}
+				// Process any LIMIT clause
+				if let Some(v) = stm.limit() {
+					self.results.truncate(v.0.saturating_sub(self.sent));
+				}
+				// Process any JOIN clauses
+				if let Err(e) = self.output_join(ctx, opt, txn, stm).await {
+					self.error = Some(e);
+					self.run.cancel();
+					return;
+				}
+				// Process any FETCH clause
+				if let Err(e) = self.output_fetch(ctx, opt, txn, stm).await {
+					self.error = Some(e);
+					self.run.cancel();
+					return;
+				}
+				// Send the batch of results
+				let res = mem::take(&mut self.results);
+				self.sent += res.len();
+				if !res.is_empty() && chn.send(Chunk::Values(res)).await.is_err() {
+					self.run.cancel();
+				}
+			}
+		}
+	}
+
 	#[cfg(any(target_arch = "wasm32", not(feature = "parallel")))]
 	#[cfg_attr(feature = "parallel", async_recursion)]
 	#[cfg_attr(not(feature = "parallel"), async_recursion(?Send))]
@@ -345,9 +729,19 @@ impl Iterator {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
+		// Enable early exit once the limit is reached
+		let mut ctx = Context::new(ctx);
+		self.run = ctx.add_cancel();
+		let ctx = &ctx;
 		// Process all prepared values
-		for v in mem::take(&mut self.entries) {
-			v.iterate(ctx, opt, txn, stm, self).await?;
+		for (pos, v) in mem::take(&mut self.entries).into_iter().enumerate() {
+			// Check if the context is finished
+			if ctx.is_done() {
+				break;
+			}
+			if self.enter(pos) {
+				v.iterate(ctx, opt, txn, stm, self).await?;
+			}
 		}
 		// Everything processed ok
 		Ok(())
@@ -363,12 +757,22 @@ impl Iterator {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
+		// Enable early exit once the limit is reached
+		let mut ctx = Context::new(ctx);
+		self.run = ctx.add_cancel();
+		let ctx = &ctx;
 		match stm.parallel() {
 			// Run statements sequentially
 			false => {
 				// Process all prepared values
-				for v in mem::take(&mut self.entries) {
-					v.iterate(ctx, opt, txn, stm, self).await?;
+				for (pos, v) in mem::take(&mut self.entries).into_iter().enumerate() {
+					// Check if the context is finished
+					if ctx.is_done() {
+						break;
+					}
+					if self.enter(pos) {
+						v.iterate(ctx, opt, txn, stm, self).await?;
+					}
 				}
 				// Everything processed ok
 				Ok(())
@@ -412,6 +816,7 @@ impl Iterator {
 					// Process all processed values
 					while let Ok(r) = vals.recv().await {
 						self.result(r, stm);
+						self.flush(ctx, opt, txn, stm, false).await;
 					}
 					// Shutdown the executor
 					let _ = end.send(()).await;
@@ -458,9 +863,12 @@ impl Iterator {
 			Statement::Relate(_) => doc.relate(ctx, opt, txn, stm).await,
 			Statement::Delete(_) => doc.delete(ctx, opt, txn, stm).await,
 			Statement::Insert(_) => doc.insert(ctx, opt, txn, stm).await,
+			Statement::Upsert(_) => doc.upsert(ctx, opt, txn, stm).await,
 		};
 		// Process the result
 		self.result(res, stm);
+		// Stream the results
+		self.flush(ctx, opt, txn, stm, false).await;
 	}
 
 	// Accept a processed record result
@@ -470,21 +878,42 @@ impl Iterator {
 			Err(Error::Ignore) => {
 				return;
 			}
+			// Documents which were processed when the iteration
+			// stopped are skipped, as the statement is checked
+			// for a timeout or cancellation once it completes
+			Err(Error::QueryCancelled | Error::QueryTimedout) => {
+				self.run.cancel();
+				return;
+			}
 			Err(e) => {
 				self.error = Some(e);
 				self.run.cancel();
 				return;
 			}
-			Ok(v) => self.results.push(v),
+			Ok(v) => {
+				self.count += 1;
+				self.results.push(v);
+				// Track the last accepted record
+				if self.paged {
+					self.last = Some(self.cur.take().unwrap_or(Cursor {
+						qid: self.qid,
+						pos: self.pos,
+						..Cursor::default()
+					}));
+				}
+			}
 		}
 		// Check if we can exit
-		if stm.group().is_none() && stm.order().is_none() {
+		if stm.group().is_none()
+			&& stm.order().is_none()
+			&& !matches!(stm.expr(), Some(v) if v.windowed())
+		{
 			if let Some(l) = stm.limit() {
 				if let Some(s) = stm.start() {
-					if self.results.len() == l.0 + s.0 {
+					if self.count == l.0 + s.0 {
 						self.run.cancel()
 					}
-				} else if self.results.len() == l.0 {
+				} else if self.count == l.0 {
 					self.run.cancel()
 				}
 			}
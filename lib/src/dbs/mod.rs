@@ -1,19 +1,31 @@
 mod auth;
+mod cursor;
 mod executor;
+mod explain;
 mod iterate;
 mod iterator;
+mod notification;
 mod options;
+mod plan;
+mod prepared;
 mod response;
+mod running;
 mod session;
 mod statement;
 mod transaction;
 mod variables;
 
 pub use self::auth::*;
+pub use self::cursor::*;
 pub use self::executor::*;
+pub use self::explain::*;
 pub use self::iterator::*;
+pub use self::notification::*;
 pub use self::options::*;
+pub use self::plan::*;
+pub use self::prepared::*;
 pub use self::response::*;
+pub use self::running::*;
 pub use self::session::*;
 pub use self::statement::*;
 pub use self::transaction::*;
@@ -0,0 +1,51 @@
+use crate::sql::object::Object;
+use crate::sql::uuid::Uuid;
+use crate::sql::value::Value;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A change notification which is sent to the subscriber of a live query.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+	/// The id of the live query which this notification belongs to
+	pub id: Uuid,
+	/// The type of change which occured to the record
+	pub action: Action,
+	/// The projected record, or the record id when the record was deleted
+	pub result: Value,
+}
+
+impl fmt::Display for Notification {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} {} {}", self.id, self.action, self.result)
+	}
+}
+
+impl From<Notification> for Value {
+	fn from(v: Notification) -> Value {
+		Value::Object(Object(map! {
+			String::from("id") => v.id.into(),
+			String::from("action") => v.action.to_string().into(),
+			String::from("result") => v.result,
+		}))
+	}
+}
+
+/// The type of change which triggered a live query [`Notification`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Action {
+	Create,
+	Update,
+	Delete,
+}
+
+impl fmt::Display for Action {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Action::Create => write!(f, "CREATE"),
+			Action::Update => write!(f, "UPDATE"),
+			Action::Delete => write!(f, "DELETE"),
+		}
+	}
+}
@@ -1,7 +1,13 @@
 use crate::cnf;
 use crate::dbs::Auth;
+use crate::dbs::Chunk;
+use crate::dbs::Explain;
 use crate::dbs::Level;
+use crate::dbs::Next;
+use crate::dbs::Notification;
+use crate::dbs::Running;
 use crate::err::Error;
+use channel::Sender;
 use std::sync::Arc;
 
 // An Options is passed around when processing a set of query
@@ -11,7 +17,7 @@ use std::sync::Arc;
 // whether field/event/table queries should be processed (useful
 // when importing data, where these queries might fail).
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Options {
 	// Currently selected NS
 	pub ns: Option<Arc<String>>,
@@ -21,6 +27,8 @@ pub struct Options {
 	pub auth: Arc<Auth>,
 	// How many subqueries have we gone into?
 	pub dive: usize,
+	// How many times should conflicting transactions be retried?
+	pub retries: u32,
 	// Whether live queries are allowed?
 	pub live: bool,
 	// Should we debug query response SQL?
@@ -41,6 +49,18 @@ pub struct Options {
 	pub indexes: bool,
 	// Should we process function futures?
 	pub futures: bool,
+	// Are index builds continued in the background?
+	pub builds: bool,
+	// Channel for sending live query notifications
+	pub sender: Option<Sender<Notification>>,
+	// Channel for streaming statement results
+	pub stream: Option<Sender<Chunk>>,
+	// Collector for explaining how a statement is processed
+	pub explain: Option<Explain>,
+	// Collector for the cursor of the next page of results
+	pub next: Option<Next>,
+	// Registry of the queries running on the datastore
+	pub running: Option<Arc<Running>>,
 }
 
 impl Default for Options {
@@ -56,6 +76,7 @@ impl Options {
 			ns: None,
 			db: None,
 			dive: 0,
+			retries: cnf::MAX_TRANSACTION_RETRIES,
 			live: false,
 			perms: true,
 			debug: false,
@@ -66,6 +87,12 @@ impl Options {
 			tables: true,
 			indexes: true,
 			futures: false,
+			builds: false,
+			sender: None,
+			stream: None,
+			explain: None,
+			next: None,
+			running: None,
 			auth: Arc::new(auth),
 		}
 	}
@@ -87,6 +114,11 @@ impl Options {
 				auth: self.auth.clone(),
 				ns: self.ns.clone(),
 				db: self.db.clone(),
+				sender: self.sender.clone(),
+				running: self.running.clone(),
+				stream: None,
+				explain: None,
+				next: None,
 				dive: self.dive + 1,
 				..*self
 			})
@@ -101,6 +133,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			debug: v,
 			..*self
 		}
@@ -112,6 +149,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			force: v,
 			..*self
 		}
@@ -123,6 +165,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			perms: v,
 			..*self
 		}
@@ -134,6 +181,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			fields: v,
 			..*self
 		}
@@ -145,6 +197,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			events: v,
 			..*self
 		}
@@ -156,6 +213,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			tables: v,
 			..*self
 		}
@@ -167,6 +229,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			indexes: v,
 			..*self
 		}
@@ -178,6 +245,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			fields: !v,
 			events: !v,
 			tables: !v,
@@ -191,6 +263,11 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			strict: v,
 			..*self
 		}
@@ -202,11 +279,61 @@ impl Options {
 			auth: self.auth.clone(),
 			ns: self.ns.clone(),
 			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: self.next.clone(),
 			futures: v,
 			..*self
 		}
 	}
 
+	// Create a new Options object for an explained statement
+	pub fn explain(&self, v: Option<Explain>) -> Options {
+		Options {
+			auth: self.auth.clone(),
+			ns: self.ns.clone(),
+			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: v,
+			next: self.next.clone(),
+			..*self
+		}
+	}
+
+	// Create a new Options object for a streamed statement
+	pub fn stream(&self, v: Option<Sender<Chunk>>) -> Options {
+		Options {
+			auth: self.auth.clone(),
+			ns: self.ns.clone(),
+			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: v,
+			explain: self.explain.clone(),
+			next: self.next.clone(),
+			..*self
+		}
+	}
+
+	// Create a new Options object for a paginated statement
+	pub fn next(&self, v: Option<Next>) -> Options {
+		Options {
+			auth: self.auth.clone(),
+			ns: self.ns.clone(),
+			db: self.db.clone(),
+			sender: self.sender.clone(),
+			running: self.running.clone(),
+			stream: self.stream.clone(),
+			explain: self.explain.clone(),
+			next: v,
+			..*self
+		}
+	}
+
 	// Check whether realtime queries are supported
 	pub fn realtime(&self) -> Result<(), Error> {
 		if !self.live {
@@ -0,0 +1,601 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::idx::spatial;
+use crate::key;
+use crate::sql::array::Array;
+use crate::sql::cond::Cond;
+use crate::sql::expression::Expression;
+use crate::sql::function::Function;
+use crate::sql::geometry::Geometry;
+use crate::sql::idiom::Idiom;
+use crate::sql::index::Index;
+use crate::sql::number::Number;
+use crate::sql::operator::Operator;
+use crate::sql::statements::define::DefineIndexStatement;
+use crate::sql::table::Table;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use std::fmt;
+
+// The maximum number of key combinations which an index
+// plan will expand a set of IN conditions into, before
+// the planner falls back to scanning the whole table.
+const MAX_INDEX_KEYS: usize = 1000;
+
+// A Plan describes how the records of a table can be found by
+// range-scanning the entries of one of the table indexes, instead
+// of iterating over every record in the table. The key ranges are
+// a superset of the matching records, as each fetched record is
+// still checked against the WHERE clause of the statement. Index
+// lookups match values of the same type as the query value, with
+// numbers matching across integers, floats, and decimals, and `=`
+// lookups also match the values which the query value converts to.
+// Full-text search plans instead look up the records which match
+// the query terms, before the records are iterated over.
+// Geospatial plans scan the index cells which cover the
+// query geometry, or the area within a distance of a point.
+
+#[derive(Clone, Debug)]
+pub struct Plan {
+	// The index which is scanned
+	pub ix: DefineIndexStatement,
+	// The conditions which are answered by the index
+	pub exp: Vec<Expression>,
+	// The index key ranges which are scanned
+	pub rng: Vec<(Vec<u8>, Vec<u8>)>,
+	// The full-text query which is searched for
+	pub qry: Option<String>,
+}
+
+impl fmt::Display for Plan {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "INDEX {} ON {}", self.ix.name, self.ix.what)?;
+		if !self.exp.is_empty() {
+			let exp: Vec<String> = self.exp.iter().map(|e| e.to_string()).collect();
+			write!(f, " WHERE {}", exp.join(" AND "))?;
+		}
+		Ok(())
+	}
+}
+
+// A condition on a single field, which can be answered by an index
+struct Predicate {
+	// The field which is checked
+	field: Idiom,
+	// The type of check which is performed
+	check: Check,
+	// The original condition expression
+	exp: Expression,
+}
+
+enum Check {
+	// The field is equal to one of the values
+	Equal(Vec<Value>),
+	// The field is within a lower and upper bound
+	Range(Option<Value>, Option<Value>),
+	// The field matches a full-text query
+	Matches(String),
+	// The field intersects with a geometry
+	Spatial(geo::Geometry<f64>),
+}
+
+impl Plan {
+	// Attempt to plan an index scan for a table and a WHERE clause
+	pub async fn new(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		tb: &Table,
+		cond: &Cond,
+	) -> Result<Option<Plan>, Error> {
+		// Find the conditions which could use an index
+		let mut exps = Vec::new();
+		conjunctions(&cond.0, &mut exps);
+		let mut preds = Vec::new();
+		for e in exps {
+			if let Some(p) = Predicate::new(ctx, opt, txn, e).await? {
+				preds.push(p);
+			}
+		}
+		// There are no usable conditions
+		if preds.is_empty() {
+			return Ok(None);
+		}
+		// Get the table index definitions
+		let ixs = txn.clone().lock().await.all_ix(opt.ns(), opt.db(), tb).await?;
+		// Find the index which answers the most conditions
+		let mut best: Option<(usize, Plan)> = None;
+		for ix in ixs.iter() {
+			// Ignore indexes which are still being built
+			if ix.building {
+				continue;
+			}
+			if let Some((score, plan)) = Plan::index(opt, ix, &preds) {
+				let better = match &best {
+					Some((s, p)) => {
+						score > *s
+							|| (score == *s && ix.index == Index::Uniq && p.ix.index != Index::Uniq)
+					}
+					None => true,
+				};
+				if better {
+					best = Some((score, plan));
+				}
+			}
+		}
+		// Return the chosen index plan
+		Ok(best.map(|(_, plan)| plan))
+	}
+	// Check if a record has an index entry which is scanned before an
+	// entry within a key range. A record is only output at its first
+	// entry within the scanned key ranges, as geospatial indexes store
+	// several entries for each record. Other indexes store one entry
+	// for each record, and the key ranges of a plan never overlap.
+	pub(crate) async fn scanned(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		rid: &Thing,
+		val: &Value,
+		(rng, key): (usize, &[u8]),
+	) -> Result<bool, Error> {
+		// Only geospatial indexes store several entries
+		if self.ix.index != Index::Spatial {
+			return Ok(false);
+		}
+		// Get the index entries of the record
+		let mut o = Array::with_capacity(self.ix.cols.len());
+		for i in self.ix.cols.iter() {
+			o.push(i.compute(ctx, opt, txn, Some(val)).await?);
+		}
+		let keys = spatial::keys(opt, &self.ix, rid, &o);
+		// Check the key ranges which are scanned first
+		Ok(keys.iter().any(|k| {
+			self.rng.iter().take(rng + 1).enumerate().any(|(i, (beg, end))| match i < rng {
+				true => k >= beg && k < end,
+				false => k >= beg && k.as_slice() < key,
+			})
+		}))
+	}
+	// Attempt to plan a scan of a specific index
+	fn index(
+		opt: &Options,
+		ix: &DefineIndexStatement,
+		preds: &[Predicate],
+	) -> Option<(usize, Plan)> {
+		// Search indexes answer full-text conditions
+		if let Index::Search(_) = ix.index {
+			// Full-text searches are always preferred
+			return preds.iter().find_map(|p| match &p.check {
+				Check::Matches(q) if ix.cols.len() == 1 && p.field == ix.cols[0] => Some((
+					usize::MAX,
+					Plan {
+						ix: ix.clone(),
+						exp: vec![p.exp.clone()],
+						rng: vec![],
+						qry: Some(q.clone()),
+					},
+				)),
+				_ => None,
+			});
+		}
+		// Spatial indexes answer geometry conditions
+		if let Index::Spatial = ix.index {
+			return preds.iter().find_map(|p| match &p.check {
+				Check::Spatial(g) if ix.cols.len() == 1 && p.field == ix.cols[0] => Some((
+					2,
+					Plan {
+						ix: ix.clone(),
+						exp: vec![p.exp.clone()],
+						rng: merge(spatial::ranges(opt, ix, g)),
+						qry: None,
+					},
+				)),
+				_ => None,
+			});
+		}
+		// The equality values of the leading index columns
+		let mut keys: Vec<Vec<Value>> = vec![vec![]];
+		// The range bounds of the final index column
+		let mut last: Vec<(Option<Value>, Option<Value>)> = vec![];
+		// The conditions which are answered by the index
+		let mut exp = vec![];
+		// Loop over the index columns in order
+		for col in ix.cols.iter() {
+			// Find an equality condition for this column
+			let eq = preds.iter().find_map(|p| match &p.check {
+				Check::Equal(v) if &p.field == col => Some((v, p)),
+				_ => None,
+			});
+			match eq {
+				// Exact values can be part of the key prefix
+				Some((v, p)) if v.iter().all(exact) => {
+					// Expand the key prefix with each value
+					if keys.len() * v.len() > MAX_INDEX_KEYS {
+						break;
+					}
+					keys = keys
+						.into_iter()
+						.flat_map(|k| {
+							v.iter().map(move |v| {
+								let mut k = k.clone();
+								k.push(v.clone());
+								k
+							})
+						})
+						.collect();
+					exp.push(p.exp.clone());
+				}
+				// Numbers are scanned as a range on the final column
+				Some((v, p)) if v.iter().all(|v| exact(v) || v.is_number()) => {
+					if keys.len() * v.len() > MAX_INDEX_KEYS {
+						break;
+					}
+					last = v.iter().map(|v| (Some(v.clone()), Some(v.clone()))).collect();
+					exp.push(p.exp.clone());
+					break;
+				}
+				// Otherwise check for range conditions
+				_ => {
+					let mut beg: Option<Value> = None;
+					let mut end: Option<Value> = None;
+					for p in preds.iter().filter(|p| &p.field == col) {
+						if let Check::Range(b, e) = &p.check {
+							// Only use bounds of a matching type
+							if let Some(k) = beg.as_ref().or(end.as_ref()) {
+								if !b.iter().chain(e).all(|v| same(k, v)) {
+									continue;
+								}
+							}
+							if let Some(b) = b {
+								beg = Some(b.clone());
+							}
+							if let Some(e) = e {
+								end = Some(e.clone());
+							}
+							exp.push(p.exp.clone());
+						}
+					}
+					if beg.is_some() || end.is_some() {
+						last = vec![(beg, end)];
+					}
+					break;
+				}
+			}
+		}
+		// Score the index by the columns it answers
+		let cols = keys[0].len();
+		let score = cols * 2 + !last.is_empty() as usize;
+		if score == 0 {
+			return None;
+		}
+		// Calculate the index key ranges
+		let mut rng = vec![];
+		for k in keys.iter() {
+			match last.is_empty() {
+				// Scan all entries with exactly these column values
+				true if cols == ix.cols.len() => {
+					let fd = Array::from(k.clone());
+					let beg = key::index::prefix_ids(opt.ns(), opt.db(), &ix.what, &ix.name, &fd);
+					let end = key::index::suffix_ids(opt.ns(), opt.db(), &ix.what, &ix.name, &fd);
+					rng.push((beg, end));
+				}
+				// Scan all entries which start with the column values
+				true => {
+					let beg = key::index::prefix_fds(opt.ns(), opt.db(), &ix.what, &ix.name, k);
+					let end = key::index::suffix_fds(opt.ns(), opt.db(), &ix.what, &ix.name, k);
+					rng.push((beg, end));
+				}
+				// Scan all entries within the final column bounds
+				false => {
+					for (b, e) in last.iter() {
+						rng.append(&mut ranges(opt, ix, k, b, e));
+					}
+				}
+			}
+		}
+		Some((
+			score,
+			Plan {
+				ix: ix.clone(),
+				exp,
+				rng: merge(rng),
+				qry: None,
+			},
+		))
+	}
+}
+
+impl Predicate {
+	// Attempt to convert an expression into an index predicate
+	async fn new(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		exp: &Expression,
+	) -> Result<Option<Predicate>, Error> {
+		// Check if the expression limits a distance
+		if let Some(p) = Predicate::distance(ctx, opt, txn, exp).await? {
+			return Ok(Some(p));
+		}
+		// Ensure the expression compares a field with a value
+		let (field, o, v, rev) = match (&exp.l, &exp.r) {
+			(Value::Idiom(f), v) if fixed(v) => (f, exp.o.clone(), v, false),
+			(v, Value::Idiom(f)) if fixed(v) => match exp.o {
+				Operator::LessThan => (f, Operator::MoreThan, v, true),
+				Operator::LessThanOrEqual => (f, Operator::MoreThanOrEqual, v, true),
+				Operator::MoreThan => (f, Operator::LessThan, v, true),
+				Operator::MoreThanOrEqual => (f, Operator::LessThanOrEqual, v, true),
+				Operator::Equal | Operator::Exact => (f, exp.o.clone(), v, true),
+				Operator::Inside => (f, Operator::Contain, v, true),
+				Operator::Contain => (f, Operator::Inside, v, true),
+				Operator::Intersects => (f, Operator::Intersects, v, true),
+				_ => return Ok(None),
+			},
+			_ => return Ok(None),
+		};
+		// Compute the value which the field is compared with
+		let v = v.compute(ctx, opt, txn, None).await?;
+		// Check which type of comparison is made
+		let check = match o {
+			Operator::Exact => match v {
+				v if exact(&v) || v.is_number() => Check::Equal(vec![v]),
+				_ => return Ok(None),
+			},
+			// The value is converted to the type of the field
+			Operator::Equal if !rev => match v {
+				v if exact(&v) || v.is_number() => Check::Equal(coerced(v)),
+				_ => return Ok(None),
+			},
+			// The field is converted to the type of the value
+			Operator::Equal => match v {
+				v if strict(&v) => Check::Equal(vec![v]),
+				_ => return Ok(None),
+			},
+			// The field is converted to the type of each value
+			Operator::Inside => match v {
+				Value::Geometry(g) => Check::Spatial(g.into()),
+				Value::Array(a) if a.iter().all(strict) => {
+					let mut a = a.0;
+					a.sort();
+					a.dedup();
+					Check::Equal(a)
+				}
+				_ => return Ok(None),
+			},
+			Operator::MoreThan | Operator::MoreThanOrEqual if ranged(&v) => {
+				Check::Range(Some(v), None)
+			}
+			Operator::LessThan | Operator::LessThanOrEqual if ranged(&v) => {
+				Check::Range(None, Some(v))
+			}
+			Operator::Matches => match v {
+				Value::Strand(v) => Check::Matches(v.as_string()),
+				_ => return Ok(None),
+			},
+			Operator::Contain | Operator::Intersects => match v {
+				Value::Geometry(g) => Check::Spatial(g.into()),
+				_ => return Ok(None),
+			},
+			_ => return Ok(None),
+		};
+		// Return the index predicate
+		Ok(Some(Predicate {
+			field: field.clone(),
+			check,
+			exp: exp.clone(),
+		}))
+	}
+	// Attempt to convert a maximum distance from a point into an index predicate
+	async fn distance(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		exp: &Expression,
+	) -> Result<Option<Predicate>, Error> {
+		// Ensure the distance is compared with a maximum value
+		let (f, v) = match (&exp.l, &exp.o, &exp.r) {
+			(Value::Function(f), Operator::LessThan | Operator::LessThanOrEqual, v) if fixed(v) => {
+				(f, v)
+			}
+			(v, Operator::MoreThan | Operator::MoreThanOrEqual, Value::Function(f)) if fixed(v) => {
+				(f, v)
+			}
+			_ => return Ok(None),
+		};
+		// Ensure the distance is between a field and a point
+		let (field, p) = match f.as_ref() {
+			Function::Normal(name, args) if name == "geo::distance" && args.len() == 2 => {
+				match (&args[0], &args[1]) {
+					(Value::Idiom(f), p) if fixed(p) => (f, p),
+					(p, Value::Idiom(f)) if fixed(p) => (f, p),
+					_ => return Ok(None),
+				}
+			}
+			_ => return Ok(None),
+		};
+		// Compute the point and the maximum distance
+		let p = p.compute(ctx, opt, txn, None).await?;
+		let v = v.compute(ctx, opt, txn, None).await?;
+		let check = match (p, v) {
+			(Value::Geometry(Geometry::Point(p)), Value::Number(v)) => {
+				Check::Spatial(spatial::radius(p, v.as_float()))
+			}
+			_ => return Ok(None),
+		};
+		// Return the index predicate
+		Ok(Some(Predicate {
+			field: field.clone(),
+			check,
+			exp: exp.clone(),
+		}))
+	}
+}
+
+// Collect all of the conditions which are joined with AND
+fn conjunctions<'a>(v: &'a Value, out: &mut Vec<&'a Expression>) {
+	if let Value::Expression(e) = v {
+		match e.o {
+			Operator::And => {
+				conjunctions(&e.l, out);
+				conjunctions(&e.r, out);
+			}
+			_ => out.push(e),
+		}
+	}
+}
+
+// Check if a value does not depend on the current document
+fn fixed(v: &Value) -> bool {
+	match v {
+		Value::Param(_) | Value::Geometry(_) => true,
+		Value::Array(v) => v.iter().all(fixed),
+		v => exact(v) || v.is_number(),
+	}
+}
+
+// Check if a value is encoded exactly within an index key
+fn exact(v: &Value) -> bool {
+	matches!(
+		v,
+		Value::Null
+			| Value::True
+			| Value::False
+			| Value::Strand(_)
+			| Value::Duration(_)
+			| Value::Datetime(_)
+			| Value::Uuid(_)
+			| Value::Thing(_)
+	)
+}
+
+// Check if a value is only equal to values of the same type
+fn strict(v: &Value) -> bool {
+	matches!(v, Value::Uuid(_) | Value::Thing(_))
+}
+
+// Get the values which a field is equal to a value with, as any
+// field is compared as a string with a string value, and boolean,
+// number, duration, and datetime fields convert a string value
+fn coerced(v: Value) -> Vec<Value> {
+	let mut a = vec![Value::Strand(v.to_strand())];
+	if let Value::Null = v {
+		a.push(Value::None);
+	}
+	if let Value::Strand(_) = v {
+		if v.is_true() {
+			a.push(Value::True);
+		}
+		if v.is_false() {
+			a.push(Value::False);
+		}
+		a.push(Value::Number(v.to_number()));
+		a.push(Value::Duration(v.to_duration()));
+		a.push(Value::Datetime(v.to_datetime()));
+	}
+	a.push(v);
+	a.sort();
+	a.dedup();
+	a
+}
+
+// Check if a value can be used as a range bound within an index
+fn ranged(v: &Value) -> bool {
+	matches!(v, Value::Number(_) | Value::Strand(_))
+}
+
+// Check if two range bounds are of the same type
+fn same(a: &Value, b: &Value) -> bool {
+	matches!((a, b), (Value::Number(_), Value::Number(_)) | (Value::Strand(_), Value::Strand(_)))
+}
+
+// Calculate the index key ranges for the bounds of the final column
+fn ranges(
+	opt: &Options,
+	ix: &DefineIndexStatement,
+	fd: &[Value],
+	beg: &Option<Value>,
+	end: &Option<Value>,
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+	// The start and end of the scanned column
+	let min = key::index::prefix_fds(opt.ns(), opt.db(), &ix.what, &ix.name, fd);
+	let max = key::index::suffix_fds(opt.ns(), opt.db(), &ix.what, &ix.name, fd);
+	// The start of the entries with the final column value
+	let key = |v: Value| {
+		let mut fd = fd.to_vec();
+		fd.push(v);
+		key::index::prefix_fds(opt.ns(), opt.db(), &ix.what, &ix.name, &fd)
+	};
+	// The end of the entries with the final column value
+	let after = |v: Value| {
+		let mut fd = fd.to_vec();
+		fd.push(v);
+		key::index::suffix_fds(opt.ns(), opt.db(), &ix.what, &ix.name, &fd)
+	};
+	match beg.as_ref().or(end.as_ref()) {
+		// Numbers are stored as integers, floats, or decimals
+		Some(Value::Number(_)) => {
+			let b = beg.as_ref().map(|v| v.clone().as_float());
+			let e = end.as_ref().map(|v| v.clone().as_float());
+			// Integer keys within the bounds
+			let int = (
+				match b {
+					Some(b) => key(Number::Int(b.floor() as i64).into()),
+					None => min.clone(),
+				},
+				match e {
+					Some(e) => after(Number::Int(e.ceil() as i64).into()),
+					None => after(Number::Int(i64::MAX).into()),
+				},
+			);
+			// Float keys within the bounds
+			let float = (
+				key(Number::Float(b.unwrap_or(f64::NEG_INFINITY)).into()),
+				after(Number::Float(e.unwrap_or(f64::INFINITY)).into()),
+			);
+			// Decimal keys are scanned in full
+			let decimal = (
+				key::index::prefix_decimals(opt.ns(), opt.db(), &ix.what, &ix.name, fd),
+				match e {
+					Some(_) => {
+						key::index::suffix_decimals(opt.ns(), opt.db(), &ix.what, &ix.name, fd)
+					}
+					None => max,
+				},
+			);
+			vec![int, float, decimal]
+		}
+		// Strings are compared byte by byte
+		_ => vec![(
+			match beg {
+				Some(b) => key(b.clone()),
+				None => min,
+			},
+			match end {
+				Some(e) => after(e.clone()),
+				None => max,
+			},
+		)],
+	}
+}
+
+// Merge the overlapping index key ranges, so
+// that each index entry is only scanned once
+fn merge(mut rng: Vec<(Vec<u8>, Vec<u8>)>) -> Vec<(Vec<u8>, Vec<u8>)> {
+	rng.sort();
+	let mut out: Vec<(Vec<u8>, Vec<u8>)> = Vec::with_capacity(rng.len());
+	for (beg, end) in rng {
+		match out.last_mut() {
+			Some((_, e)) if beg <= *e => {
+				if end > *e {
+					*e = end;
+				}
+			}
+			_ => out.push((beg, end)),
+		}
+	}
+	out
+}
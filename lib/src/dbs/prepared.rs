@@ -0,0 +1,88 @@
+use crate::cnf;
+use crate::err::Error;
+use crate::sql;
+use crate::sql::query::Query;
+use lru::LruCache;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// A parsed SQL query which can be executed repeatedly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreparedQuery {
+	pub(crate) ast: Arc<Query>,
+}
+
+impl PreparedQuery {
+	/// Retrieve the parsed statements of the query
+	pub fn query(&self) -> &Query {
+		&self.ast
+	}
+}
+
+impl fmt::Display for PreparedQuery {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.ast)
+	}
+}
+
+/// A least recently used cache of parsed queries, keyed by the query text.
+pub(crate) struct QueryCache(Mutex<LruCache<String, Arc<Query>>>);
+
+impl Default for QueryCache {
+	fn default() -> Self {
+		QueryCache::new(cnf::QUERY_CACHE_SIZE)
+	}
+}
+
+impl QueryCache {
+	// Create a new cache holding a number of queries
+	pub fn new(size: usize) -> Self {
+		let size = NonZeroUsize::new(size.max(1)).unwrap();
+		QueryCache(Mutex::new(LruCache::new(size)))
+	}
+	// Parse a query, reusing a previously parsed query if cached
+	pub fn parse(&self, txt: &str) -> Result<Arc<Query>, Error> {
+		// Large queries are parsed without being cached
+		if txt.len() > cnf::QUERY_CACHE_MAX_LENGTH {
+			return sql::parse(txt).map(Arc::new);
+		}
+		// Check if the query has been parsed already
+		if let Some(v) = self.0.lock().unwrap().get(txt) {
+			return Ok(v.clone());
+		}
+		// Parse the SQL query text
+		let ast = Arc::new(sql::parse(txt)?);
+		// Store the parsed query
+		self.0.lock().unwrap().put(txt.to_owned(), ast.clone());
+		// Return the parsed query
+		Ok(ast)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn cache_reuses_parsed_queries() {
+		let cache = QueryCache::new(2);
+		let one = cache.parse("SELECT * FROM person").unwrap();
+		let two = cache.parse("SELECT * FROM person").unwrap();
+		assert!(Arc::ptr_eq(&one, &two));
+		assert!(cache.parse("SELECT * FROM").is_err());
+	}
+
+	#[test]
+	fn cache_evicts_least_recently_used() {
+		let cache = QueryCache::new(2);
+		let one = cache.parse("SELECT * FROM one").unwrap();
+		let two = cache.parse("SELECT * FROM two").unwrap();
+		assert!(Arc::ptr_eq(&one, &cache.parse("SELECT * FROM one").unwrap()));
+		cache.parse("SELECT * FROM three").unwrap();
+		assert!(Arc::ptr_eq(&one, &cache.parse("SELECT * FROM one").unwrap()));
+		assert!(!Arc::ptr_eq(&two, &cache.parse("SELECT * FROM two").unwrap()));
+	}
+}
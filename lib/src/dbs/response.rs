@@ -5,12 +5,40 @@ use serde::ser::SerializeStruct;
 use serde::Serialize;
 use std::time::Duration;
 
+/// The type of statement which produced a [`Response`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QueryType {
+	/// A `LIVE SELECT` statement which registered a live query
+	Live,
+	/// A `KILL` statement which removed a live query
+	Kill,
+	/// Any other statement
+	Other,
+}
+
+/// A part of the output when running a query set as a stream.
+///
+/// The records output by a read-only statement are sent in batches
+/// as they are processed, followed by the [`Response`] for the
+/// statement, the result of which contains any remaining records.
+#[derive(Debug)]
+pub enum Chunk {
+	/// A batch of records output by the current statement
+	Values(Vec<Value>),
+	/// The response once the current statement has completed
+	Response(Response),
+}
+
 /// The return value when running a query set on the database.
 #[derive(Debug)]
 pub struct Response {
 	pub sql: Option<String>,
 	pub time: Duration,
 	pub result: Result<Value, Error>,
+	pub query_type: QueryType,
+	/// The cursor to pass to `AFTER` to fetch the next page of a
+	/// `SELECT` statement, if its `LIMIT` was reached
+	pub next: Option<String>,
 }
 
 impl Response {
@@ -34,34 +62,20 @@ impl From<Response> for Value {
 		// Get the response status
 		let status = v.output().map_or_else(|_| "ERR", |_| "OK");
 		// Convert the response
+		let mut out = Object::default();
+		if let Some(sql) = v.sql {
+			out.insert(String::from("sql"), sql.into());
+		}
+		out.insert(String::from("time"), time.into());
+		out.insert(String::from("status"), status.into());
 		match v.result {
-			Ok(val) => match v.sql {
-				Some(sql) => Value::Object(Object(map! {
-					String::from("sql") => sql.into(),
-					String::from("time") => time.into(),
-					String::from("status") => status.into(),
-					String::from("result") => val,
-				})),
-				None => Value::Object(Object(map! {
-					String::from("time") => time.into(),
-					String::from("status") => status.into(),
-					String::from("result") => val,
-				})),
-			},
-			Err(err) => match v.sql {
-				Some(sql) => Value::Object(Object(map! {
-					String::from("sql") => sql.into(),
-					String::from("time") => time.into(),
-					String::from("status") => status.into(),
-					String::from("detail") => err.to_string().into(),
-				})),
-				None => Value::Object(Object(map! {
-					String::from("time") => time.into(),
-					String::from("status") => status.into(),
-					String::from("detail") => err.to_string().into(),
-				})),
-			},
+			Ok(val) => out.insert(String::from("result"), val),
+			Err(err) => out.insert(String::from("detail"), err.to_string().into()),
+		};
+		if let Some(next) = v.next {
+			out.insert(String::from("next"), next.into());
 		}
+		Value::Object(out)
 	}
 }
 
@@ -70,41 +84,26 @@ impl Serialize for Response {
 	where
 		S: serde::Serializer,
 	{
+		// Count the output fields
+		let len = 3 + self.sql.is_some() as usize + self.next.is_some() as usize;
+		let mut val = serializer.serialize_struct("Response", len)?;
+		if let Some(s) = &self.sql {
+			val.serialize_field("sql", s.as_str())?;
+		}
+		val.serialize_field("time", self.speed().as_str())?;
 		match &self.result {
-			Ok(v) => match &self.sql {
-				Some(s) => {
-					let mut val = serializer.serialize_struct("Response", 4)?;
-					val.serialize_field("sql", s.as_str())?;
-					val.serialize_field("time", self.speed().as_str())?;
-					val.serialize_field("status", "OK")?;
-					val.serialize_field("result", v)?;
-					val.end()
-				}
-				None => {
-					let mut val = serializer.serialize_struct("Response", 3)?;
-					val.serialize_field("time", self.speed().as_str())?;
-					val.serialize_field("status", "OK")?;
-					val.serialize_field("result", v)?;
-					val.end()
-				}
-			},
-			Err(e) => match &self.sql {
-				Some(s) => {
-					let mut val = serializer.serialize_struct("Response", 4)?;
-					val.serialize_field("sql", s.as_str())?;
-					val.serialize_field("time", self.speed().as_str())?;
-					val.serialize_field("status", "ERR")?;
-					val.serialize_field("detail", e)?;
-					val.end()
-				}
-				None => {
-					let mut val = serializer.serialize_struct("Response", 3)?;
-					val.serialize_field("time", self.speed().as_str())?;
-					val.serialize_field("status", "ERR")?;
-					val.serialize_field("detail", e)?;
-					val.end()
-				}
-			},
+			Ok(v) => {
+				val.serialize_field("status", "OK")?;
+				val.serialize_field("result", v)?;
+			}
+			Err(e) => {
+				val.serialize_field("status", "ERR")?;
+				val.serialize_field("detail", e)?;
+			}
+		}
+		if let Some(n) = &self.next {
+			val.serialize_field("next", n.as_str())?;
 		}
+		val.end()
 	}
 }
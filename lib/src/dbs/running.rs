@@ -0,0 +1,117 @@
+use crate::ctx::Canceller;
+use crate::sql::duration::Duration;
+use crate::sql::object::Object;
+use crate::sql::query::Query;
+use crate::sql::uuid::Uuid;
+use crate::sql::value::Value;
+use crate::sql::Datetime;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex;
+use trice::Instant;
+
+// A Running keeps track of the queries which are currently being
+// executed on a datastore, so that they can be listed using the
+// INFO FOR RUNNING statement, and cancelled from another session
+// using the KILL QUERY statement.
+
+#[derive(Debug, Default)]
+pub struct Running(Mutex<HashMap<Uuid, Arc<RunningQuery>>>);
+
+#[derive(Debug)]
+pub struct RunningQuery {
+	// The query which is being executed
+	query: Arc<Query>,
+	// The namespace the query was started in
+	ns: Option<Arc<String>>,
+	// The database the query was started in
+	db: Option<Arc<String>>,
+	// The time at which the query was started
+	started: Datetime,
+	// Measures how long the query has been running
+	instant: Instant,
+	// The index of the statement being executed
+	current: AtomicUsize,
+	// Cancels the execution of the query
+	cancel: Canceller,
+}
+
+// A Registration removes a query from the running queries once
+// the query has finished executing, or has been dropped.
+
+pub struct Registration<'a> {
+	running: &'a Running,
+	query: Arc<RunningQuery>,
+	id: Uuid,
+}
+
+impl<'a> Registration<'a> {
+	// Mark the statement which is being executed
+	pub fn enter(&self, index: usize) {
+		self.query.current.store(index, Ordering::Relaxed);
+	}
+}
+
+impl<'a> Drop for Registration<'a> {
+	fn drop(&mut self) {
+		self.running.0.lock().unwrap().remove(&self.id);
+	}
+}
+
+impl Running {
+	// Add a query to the running queries
+	pub fn register(
+		&self,
+		query: Arc<Query>,
+		ns: Option<Arc<String>>,
+		db: Option<Arc<String>>,
+		cancel: Canceller,
+	) -> Registration<'_> {
+		let id = Uuid::new();
+		let query = Arc::new(RunningQuery {
+			query,
+			ns,
+			db,
+			started: Datetime::default(),
+			instant: Instant::now(),
+			current: AtomicUsize::new(0),
+			cancel,
+		});
+		self.0.lock().unwrap().insert(id.clone(), query.clone());
+		Registration {
+			running: self,
+			query,
+			id,
+		}
+	}
+	// Cancel a running query, returning whether it was found
+	pub fn kill(&self, id: &Uuid) -> bool {
+		match self.0.lock().unwrap().get(id) {
+			Some(v) => {
+				v.cancel.cancel();
+				true
+			}
+			None => false,
+		}
+	}
+	// Describe each of the running queries
+	pub fn list(&self) -> Value {
+		let mut res = Object::default();
+		for (id, v) in self.0.lock().unwrap().iter() {
+			let current = v.current.load(Ordering::Relaxed);
+			let statement = v.query.get(current).map(|s| s.to_string());
+			res.insert(
+				id.to_raw(),
+				Value::from(map! {
+					String::from("ns") => v.ns.as_ref().map(|v| v.to_string()).into(),
+					String::from("db") => v.db.as_ref().map(|v| v.to_string()).into(),
+					String::from("statement") => statement.into(),
+					String::from("started") => v.started.clone().into(),
+					String::from("duration") => Duration::from(v.instant.elapsed()).into(),
+				}),
+			);
+		}
+		res.into()
+	}
+}
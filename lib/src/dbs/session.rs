@@ -1,3 +1,4 @@
+use crate::cnf;
 use crate::ctx::Context;
 use crate::dbs::Auth;
 use crate::sql::value::Value;
@@ -24,6 +25,8 @@ pub struct Session {
 	pub sc: Option<String>,
 	/// The current scope authentication data
 	pub sd: Option<Value>,
+	/// The number of times to retry conflicting statement transactions
+	pub tr: Option<u32>,
 }
 
 impl Session {
@@ -80,6 +83,11 @@ impl Session {
 		self.db = Some(db.to_owned());
 		self
 	}
+	/// Set the number of times to retry conflicting statement transactions
+	pub fn with_retries(mut self, tr: u32) -> Session {
+		self.tr = Some(tr);
+		self
+	}
 	/// Retrieves the selected namespace
 	pub(crate) fn ns(&self) -> Option<Arc<String>> {
 		self.ns.to_owned().map(Arc::new)
@@ -88,6 +96,10 @@ impl Session {
 	pub(crate) fn db(&self) -> Option<Arc<String>> {
 		self.db.to_owned().map(Arc::new)
 	}
+	/// Retrieves the number of transaction retries
+	pub(crate) fn retries(&self) -> u32 {
+		self.tr.unwrap_or(cnf::MAX_TRANSACTION_RETRIES)
+	}
 	/// Convert a session into a runtime
 	pub(crate) fn context<'a>(&self, mut ctx: Context<'a>) -> Context<'a> {
 		// Add scope value
@@ -1,8 +1,10 @@
+use crate::sql::after::After;
 use crate::sql::cond::Cond;
 use crate::sql::data::Data;
 use crate::sql::fetch::Fetchs;
 use crate::sql::field::Fields;
 use crate::sql::group::Groups;
+use crate::sql::join::Joins;
 use crate::sql::limit::Limit;
 use crate::sql::order::Orders;
 use crate::sql::output::Output;
@@ -14,6 +16,7 @@ use crate::sql::statements::insert::InsertStatement;
 use crate::sql::statements::relate::RelateStatement;
 use crate::sql::statements::select::SelectStatement;
 use crate::sql::statements::update::UpdateStatement;
+use crate::sql::statements::upsert::UpsertStatement;
 use crate::sql::version::Version;
 use std::fmt;
 
@@ -25,6 +28,7 @@ pub enum Statement<'a> {
 	Relate(&'a RelateStatement),
 	Delete(&'a DeleteStatement),
 	Insert(&'a InsertStatement),
+	Upsert(&'a UpsertStatement),
 }
 
 impl<'a> From<&'a SelectStatement> for Statement<'a> {
@@ -63,6 +67,12 @@ impl<'a> From<&'a InsertStatement> for Statement<'a> {
 	}
 }
 
+impl<'a> From<&'a UpsertStatement> for Statement<'a> {
+	fn from(v: &'a UpsertStatement) -> Self {
+		Statement::Upsert(v)
+	}
+}
+
 impl<'a> fmt::Display for Statement<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -72,6 +82,7 @@ impl<'a> fmt::Display for Statement<'a> {
 			Statement::Relate(v) => write!(f, "{}", v),
 			Statement::Delete(v) => write!(f, "{}", v),
 			Statement::Insert(v) => write!(f, "{}", v),
+			Statement::Upsert(v) => write!(f, "{}", v),
 		}
 	}
 }
@@ -103,6 +114,7 @@ impl<'a> Statement<'a> {
 			Statement::Update(v) => v.data.as_ref(),
 			Statement::Relate(v) => v.data.as_ref(),
 			Statement::Insert(v) => v.update.as_ref(),
+			Statement::Upsert(v) => v.data.as_ref(),
 			_ => None,
 		}
 	}
@@ -140,6 +152,14 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	// Returns any JOIN clauses if specified
+	#[inline]
+	pub fn join(&self) -> Option<&Joins> {
+		match self {
+			Statement::Select(v) => v.join.as_ref(),
+			_ => None,
+		}
+	}
 	// Returns any FETCH clause if specified
 	#[inline]
 	pub fn fetch(&self) -> Option<&Fetchs> {
@@ -164,6 +184,14 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	// Returns any AFTER clause if specified
+	#[inline]
+	pub fn after(&self) -> Option<&After> {
+		match self {
+			Statement::Select(v) => v.after.as_ref(),
+			_ => None,
+		}
+	}
 	// Returns any VERSION clause if specified
 	#[inline]
 	pub fn version(&self) -> Option<&Version> {
@@ -181,6 +209,7 @@ impl<'a> Statement<'a> {
 			Statement::Relate(v) => v.output.as_ref(),
 			Statement::Delete(v) => v.output.as_ref(),
 			Statement::Insert(v) => v.output.as_ref(),
+			Statement::Upsert(v) => v.output.as_ref(),
 			_ => None,
 		}
 	}
@@ -194,6 +223,7 @@ impl<'a> Statement<'a> {
 			Statement::Relate(v) => v.parallel,
 			Statement::Delete(v) => v.parallel,
 			Statement::Insert(v) => v.parallel,
+			Statement::Upsert(v) => v.parallel,
 		}
 	}
 }
@@ -0,0 +1,48 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Statement;
+use crate::dbs::Transaction;
+use crate::doc::Document;
+use crate::err::Error;
+use crate::sql::value::Value;
+
+impl<'a> Document<'a> {
+	pub async fn changefeed(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check if forced
+		if !opt.force && !self.changed() {
+			return Ok(());
+		}
+		// Check if the table has a change feed
+		if self.tb(opt, txn).await?.changefeed.is_none() {
+			return Ok(());
+		}
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Get the change which was made
+		let (act, doc) = match stm.is_delete() {
+			true => {
+				("delete", Value::from(map! { String::from("id") => Value::from(rid.clone()) }))
+			}
+			false => ("update", self.current.as_ref().clone()),
+		};
+		// Create the change entry
+		let val = Value::from(map! {
+			String::from(act) => doc,
+		});
+		// Store the change entry at the versionstamp of the transaction
+		let (ns, db, tb) = (opt.ns().to_owned(), opt.db().to_owned(), rid.tb.clone());
+		run.stamp(move |vs, nr| crate::key::cf::new(&ns, &db, &tb, vs, nr).into(), val);
+		// Carry on
+		Ok(())
+	}
+}
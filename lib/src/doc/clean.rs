@@ -5,6 +5,7 @@ use crate::dbs::Transaction;
 use crate::doc::Document;
 use crate::err::Error;
 use crate::sql::idiom::Idiom;
+use crate::sql::part::Part;
 
 impl<'a> Document<'a> {
 	pub async fn clean(
@@ -19,17 +20,23 @@ impl<'a> Document<'a> {
 		// This table is schemafull
 		if tb.full {
 			// Create a vector to store the keys
-			let mut keys: Vec<Idiom> = vec![];
+			let mut keys: Vec<(Idiom, bool)> = vec![];
 			// Loop through all field statements
 			for fd in self.fd(opt, txn).await?.iter() {
+				// Object schemas have already removed undeclared fields
+				let schema = matches!(&fd.kind, Some(k) if k.is_schema());
 				// Loop over this field in the document
 				for k in self.current.each(&fd.name).into_iter() {
-					keys.push(k);
+					keys.push((k, schema));
 				}
 			}
 			// Loop over every field in the document
 			for fd in self.current.every().iter() {
-				if !keys.contains(fd) {
+				// Array elements and schema fields are kept with their field
+				if !keys.iter().any(|(k, schema)| {
+					fd.starts_with(k)
+						&& (*schema || fd[k.len()..].iter().all(|p| matches!(p, Part::Index(_))))
+				}) {
 					match fd {
 						fd if fd.is_id() => continue,
 						fd if fd.is_in() => continue,
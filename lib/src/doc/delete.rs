@@ -22,8 +22,12 @@ impl<'a> Document<'a> {
 		self.allow(ctx, opt, txn, stm).await?;
 		// Purge index data
 		self.index(ctx, opt, txn, stm).await?;
+		// Remove joining records from views
+		self.unlink(ctx, opt, txn, stm).await?;
 		// Purge record data
 		self.purge(ctx, opt, txn, stm).await?;
+		// Store record changes
+		self.changefeed(ctx, opt, txn, stm).await?;
 		// Run table queries
 		self.table(ctx, opt, txn, stm).await?;
 		// Run lives queries
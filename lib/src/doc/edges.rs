@@ -43,6 +43,9 @@ impl<'a> Document<'a> {
 			// Store the right pointer edge
 			let key = crate::key::graph::new(opt.ns(), opt.db(), &r.tb, &r.id, i, rid);
 			run.set(key, vec![]).await?;
+			// Store the edge between the pair of records
+			let key = crate::key::re::new(opt.ns(), opt.db(), &rid.tb, l, r, &rid.id);
+			run.set(key, vec![]).await?;
 			// Store the edges on the record
 			self.current.to_mut().set(ctx, opt, txn, &*IN, l.clone().into()).await?;
 			self.current.to_mut().set(ctx, opt, txn, &*OUT, r.clone().into()).await?;
@@ -4,6 +4,7 @@ use crate::dbs::Statement;
 use crate::dbs::Transaction;
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::part::Part;
 use crate::sql::permission::Permission;
 use crate::sql::value::Value;
 
@@ -27,6 +28,18 @@ impl<'a> Document<'a> {
 			for (k, mut val) in self.current.walk(&fd.name).into_iter() {
 				// Get the initial value
 				let old = self.initial.pick(&k);
+				// Check for a DEFAULT clause
+				if let Some(expr) =
+					fd.default.as_ref().filter(|_| val.is_none() && !orphan(&fd.name, &k, &val))
+				{
+					// Configure the context
+					let mut ctx = Context::new(ctx);
+					ctx.add_value("value".into(), &val);
+					ctx.add_value("after".into(), &val);
+					ctx.add_value("before".into(), &old);
+					// Process the DEFAULT clause
+					val = expr.compute(&ctx, opt, txn, Some(&self.current)).await?;
+				}
 				// Check for a VALUE clause
 				if let Some(expr) = &fd.value {
 					// Configure the context
@@ -42,8 +55,29 @@ impl<'a> Document<'a> {
 					val = match val {
 						Value::None => val,
 						Value::Null => val,
-						_ => val.convert_to(kind),
-					}
+						_ => {
+							// Convert the value to the field type
+							let out = val.clone().convert_to(kind);
+							// Check the converted value is of the field type
+							if !out.is_type(kind) {
+								return Err(Error::FieldCheck {
+									thing: rid.to_string(),
+									value: val.to_string(),
+									field: fd.name.clone(),
+									check: kind.to_string(),
+								});
+							}
+							out
+						}
+					};
+				}
+				// Check for a READONLY clause
+				if fd.readonly && !self.is_new() && val != old {
+					return Err(Error::FieldReadonly {
+						thing: rid.to_string(),
+						value: val.to_string(),
+						field: fd.name.clone(),
+					});
 				}
 				// Check for a ASSERT clause
 				if let Some(expr) = &fd.assert {
@@ -100,3 +134,8 @@ impl<'a> Document<'a> {
 		Ok(())
 	}
 }
+
+// Check if a nested field is missing because its parent is missing
+fn orphan(name: &[Part], k: &[Part], val: &Value) -> bool {
+	val.is_none() && k.len() < name.len()
+}
@@ -5,6 +5,8 @@ use crate::dbs::Transaction;
 use crate::doc::Document;
 use crate::err::Error;
 use crate::sql::array::Array;
+use crate::sql::index::Index;
+use crate::sql::statements::define::DefineIndexStatement;
 
 impl<'a> Document<'a> {
 	pub async fn index(
@@ -12,7 +14,7 @@ impl<'a> Document<'a> {
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		_stm: &Statement<'_>,
+		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		// Check events
 		if !opt.indexes {
@@ -26,77 +28,106 @@ impl<'a> Document<'a> {
 		if self.tb(opt, txn).await?.drop {
 			return Ok(());
 		}
-		// Get the record id
-		let rid = self.id.as_ref().unwrap();
 		// Loop through all index statements
 		for ix in self.ix(opt, txn).await?.iter() {
-			// Calculate old values
-			let mut o = Array::with_capacity(ix.cols.len());
-			for i in ix.cols.iter() {
-				let v = i.compute(ctx, opt, txn, Some(&self.initial)).await?;
-				o.push(v);
-			}
-			// Calculate new values
-			let mut n = Array::with_capacity(ix.cols.len());
-			for i in ix.cols.iter() {
-				let v = i.compute(ctx, opt, txn, Some(&self.current)).await?;
-				n.push(v);
-			}
-			// Clone transaction
-			let run = txn.clone();
-			// Claim transaction
-			let mut run = run.lock().await;
-			// Update the index entries
-			if opt.force || o != n {
-				match ix.uniq {
-					true => {
-						// Delete the old index data
-						if self.initial.is_some() {
-							#[rustfmt::skip]
-							let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &o, None);
-							let _ = run.delc(key, Some(rid)).await; // Ignore this error
-						}
-						// Create the new index data
-						if self.current.is_some() {
-							#[rustfmt::skip]
-							let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &n, None);
-							if run.putc(key, rid, None).await.is_err() {
-								return Err(Error::IndexExists {
-									thing: rid.to_string(),
-									index: ix.name.to_string(),
-									value: match n.len() {
-										1 => n.first().unwrap().to_string(),
-										_ => n.to_string(),
-									},
-								});
-							}
-						}
+			self.index_one(ctx, opt, txn, stm, ix).await?;
+		}
+		// Carry on
+		Ok(())
+	}
+	// Update the entries of a single index for this document
+	pub(crate) async fn index_one(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Calculate old values
+		let mut o = Array::with_capacity(ix.cols.len());
+		for i in ix.cols.iter() {
+			let v = i.compute(ctx, opt, txn, Some(&self.initial)).await?;
+			o.push(v);
+		}
+		// Calculate new values
+		let mut n = Array::with_capacity(ix.cols.len());
+		for i in ix.cols.iter() {
+			let v = i.compute(ctx, opt, txn, Some(&self.current)).await?;
+			n.push(v);
+		}
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Update the index entries
+		if opt.force || o != n || self.is_new() || stm.is_delete() {
+			match &ix.index {
+				Index::Uniq => {
+					// Delete the old index data
+					if self.initial.is_some() {
+						#[rustfmt::skip]
+						let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &o, None);
+						let _ = run.delc(key, Some(rid)).await; // Ignore this error
 					}
-					false => {
-						// Delete the old index data
-						if self.initial.is_some() {
-							#[rustfmt::skip]
-							let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &o, Some(&rid.id));
-							let _ = run.delc(key, Some(rid)).await; // Ignore this error
+					// Create the new index data
+					if self.current.is_some() && !stm.is_delete() {
+						#[rustfmt::skip]
+						let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &n, None);
+						if run.putc(key, rid, None).await.is_err() {
+							return Err(Error::IndexExists {
+								thing: rid.to_string(),
+								index: ix.name.to_string(),
+								value: match n.len() {
+									1 => n.first().unwrap().to_string(),
+									_ => n.to_string(),
+								},
+							});
 						}
-						// Create the new index data
-						if self.current.is_some() {
-							#[rustfmt::skip]
-							let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &n, Some(&rid.id));
-							if run.putc(key, rid, None).await.is_err() {
-								return Err(Error::IndexExists {
-									thing: rid.to_string(),
-									index: ix.name.to_string(),
-									value: match n.len() {
-										1 => n.first().unwrap().to_string(),
-										_ => n.to_string(),
-									},
-								});
-							}
+					}
+				}
+				Index::Idx => {
+					// Delete the old index data
+					if self.initial.is_some() {
+						#[rustfmt::skip]
+						let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &o, Some(&rid.id));
+						let _ = run.delc(key, Some(rid)).await; // Ignore this error
+					}
+					// Create the new index data
+					if self.current.is_some() && !stm.is_delete() {
+						#[rustfmt::skip]
+						let key = crate::key::index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &n, Some(&rid.id));
+						if run.putc(key, rid, None).await.is_err() {
+							return Err(Error::IndexExists {
+								thing: rid.to_string(),
+								index: ix.name.to_string(),
+								value: match n.len() {
+									1 => n.first().unwrap().to_string(),
+									_ => n.to_string(),
+								},
+							});
 						}
 					}
-				};
-			}
+				}
+				Index::Search(sc) => {
+					// Update the search index data
+					let new = match self.current.is_some() && !stm.is_delete() {
+						true => Some(&n),
+						false => None,
+					};
+					crate::idx::search::index(&mut run, opt, ix, sc, rid, &o, new).await?;
+				}
+				Index::Spatial => {
+					// Update the geospatial index data
+					let new = match self.current.is_some() && !stm.is_delete() {
+						true => Some(&n),
+						false => None,
+					};
+					crate::idx::spatial::index(&mut run, opt, ix, rid, &o, new).await?;
+				}
+			};
 		}
 		// Carry on
 		Ok(())
@@ -30,8 +30,12 @@ impl<'a> Document<'a> {
 				self.allow(ctx, opt, txn, stm).await?;
 				// Store index data
 				self.index(ctx, opt, txn, stm).await?;
+				// Remove joining records from views
+				self.unlink(ctx, opt, txn, stm).await?;
 				// Store record data
 				self.store(ctx, opt, txn, stm).await?;
+				// Store record changes
+				self.changefeed(ctx, opt, txn, stm).await?;
 				// Run table queries
 				self.table(ctx, opt, txn, stm).await?;
 				// Run lives queries
@@ -55,8 +59,12 @@ impl<'a> Document<'a> {
 				self.allow(ctx, opt, txn, stm).await?;
 				// Store index data
 				self.index(ctx, opt, txn, stm).await?;
+				// Remove joining records from views
+				self.unlink(ctx, opt, txn, stm).await?;
 				// Store record data
 				self.store(ctx, opt, txn, stm).await?;
+				// Store record changes
+				self.changefeed(ctx, opt, txn, stm).await?;
 				// Run table queries
 				self.table(ctx, opt, txn, stm).await?;
 				// Run lives queries
@@ -1,18 +1,158 @@
 use crate::ctx::Context;
+use crate::dbs::Action;
+use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::Statement;
 use crate::dbs::Transaction;
+use crate::dbs::LOG;
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::paths::META;
+use crate::sql::paths::SC;
+use crate::sql::paths::SD;
+use crate::sql::permission::Permission;
+use crate::sql::statements::live::LiveStatement;
+use crate::sql::value::Value;
+use std::sync::Arc;
 
 impl<'a> Document<'a> {
 	pub async fn lives(
 		&self,
-		_ctx: &Context<'_>,
-		_opt: &Options,
-		_txn: &Transaction,
-		_stm: &Statement<'_>,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
 	) -> Result<(), Error> {
+		// Check if forced
+		if !opt.force && !self.changed() {
+			return Ok(());
+		}
+		// Check if notifications are enabled
+		let chn = match opt.sender.as_ref() {
+			Some(chn) => chn,
+			None => return Ok(()),
+		};
+		// Get the notification action
+		let met = if stm.is_delete() {
+			Action::Delete
+		} else if self.is_new() {
+			Action::Create
+		} else {
+			Action::Update
+		};
+		// Loop through all live query statements
+		for lv in self.lv(opt, txn).await?.iter() {
+			// Process the live query for the subscriber
+			match self.live(ctx, opt, txn, lv, met).await {
+				// The record matches the live query
+				Ok(Some(result)) => {
+					// Send the notification
+					let _ = chn
+						.send(Notification {
+							id: lv.id.clone(),
+							action: met,
+							result,
+						})
+						.await;
+				}
+				// The record does not match the live query
+				Ok(None) => continue,
+				// The live query could not be processed
+				Err(e) => {
+					warn!(target: LOG, "Unable to process live query {}: {}", lv.id, e);
+					continue;
+				}
+			}
+		}
+		// Carry on
 		Ok(())
 	}
+	// Process a live query as the subscriber which registered it
+	async fn live(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		lv: &LiveStatement,
+		met: Action,
+	) -> Result<Option<Value>, Error> {
+		// Deleted records are checked against their last state
+		let doc = match met {
+			Action::Delete => self.initial.as_ref(),
+			_ => self.current.as_ref(),
+		};
+		// Configure the subscriber context
+		let mut ctx = Context::new(ctx);
+		if let Some(session) = &lv.session {
+			ctx.add_value("session".into(), session);
+			ctx.add_value("scope".into(), session.pick(SC.as_ref()));
+			ctx.add_value("auth".into(), session.pick(SD.as_ref()));
+		}
+		// Configure the subscriber options
+		let mut opt = opt.perms(true).futures(true);
+		if let Some(auth) = &lv.auth {
+			opt.auth = Arc::new(auth.clone());
+		}
+		let opt = &opt;
+		// Check the table permissions of the subscriber
+		if opt.auth.perms() {
+			// Get the table
+			let tb = self.tb(opt, txn).await?;
+			// Match the permission clause
+			match &tb.permissions.select {
+				Permission::None => return Ok(None),
+				Permission::Full => (),
+				Permission::Specific(e) => {
+					// Ensure permissions are disabled
+					let opt = &opt.perms(false);
+					// Process the PERMISSION clause
+					if !e.compute(&ctx, opt, txn, Some(doc)).await?.is_truthy() {
+						return Ok(None);
+					}
+				}
+			}
+		}
+		// Check the live query WHERE clause
+		if let Some(cond) = &lv.cond {
+			if !cond.compute(&ctx, opt, txn, Some(doc)).await?.is_truthy() {
+				return Ok(None);
+			}
+		}
+		// Deleted records only send the record id
+		if met == Action::Delete {
+			return Ok(self.id.clone().map(Value::from));
+		}
+		// Compute the live query fields
+		let mut out = lv.expr.compute(&ctx, opt, txn, Some(doc), false).await?;
+		// Process the field permissions of the subscriber
+		if opt.auth.perms() {
+			// Loop through all field statements
+			for fd in self.fd(opt, txn).await?.iter() {
+				// Loop over each field in document
+				for k in out.each(&fd.name).iter() {
+					// Process field permissions
+					match &fd.permissions.select {
+						Permission::Full => (),
+						Permission::None => out.del(&ctx, opt, txn, k).await?,
+						Permission::Specific(e) => {
+							// Get the current value
+							let val = doc.pick(k);
+							// Configure the context
+							let mut ctx = Context::new(&ctx);
+							ctx.add_value("value".into(), &val);
+							// Process the PERMISSION clause
+							let opt = &opt.perms(false);
+							if !e.compute(&ctx, opt, txn, Some(doc)).await?.is_truthy() {
+								out.del(&ctx, opt, txn, k).await?
+							}
+						}
+					}
+				}
+			}
+		}
+		// Remove metadata fields on output
+		out.del(&ctx, opt, txn, &*META).await?;
+		// Output result
+		Ok(Some(out))
+	}
 }
@@ -5,6 +5,7 @@ mod compute;
 
 mod allow;
 mod alter;
+mod changefeed;
 mod check;
 mod clean;
 mod create;
@@ -27,3 +28,4 @@ mod select;
 mod store;
 mod table;
 mod update;
+mod upsert;
@@ -46,6 +46,9 @@ impl<'a> Document<'a> {
 				Statement::Insert(_) => {
 					self.current.compute(ctx, opt, txn, Some(&self.current)).await
 				}
+				Statement::Upsert(_) => {
+					self.current.compute(ctx, opt, txn, Some(&self.current)).await
+				}
 				_ => Err(Error::Ignore),
 			},
 		}?;
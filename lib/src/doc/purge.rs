@@ -24,8 +24,10 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
 		// Check if the table is a view
-		if self.tb(opt, txn).await?.drop {
+		if tb.drop {
 			return Ok(());
 		}
 		// Clone transaction
@@ -37,6 +39,21 @@ impl<'a> Document<'a> {
 		// Purge the record data
 		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
 		run.del(key).await?;
+		// Mark the record as deleted in the record history
+		if tb.history.is_some() {
+			let (ns, db, tb, id) =
+				(opt.ns().to_owned(), opt.db().to_owned(), rid.tb.clone(), rid.id.clone());
+			run.stamp(
+				move |vs, _| crate::key::history::new(&ns, &db, &tb, &id, vs).into(),
+				Value::None,
+			);
+		}
+		// Purge the aggregate values of a view record
+		if tb.view.is_some() {
+			let beg = crate::key::va::prefix_id(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			let end = crate::key::va::suffix_id(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			run.delr(beg..end, u32::MAX).await?;
+		}
 		// Purge the record edges
 		match (self.initial.pick(&*IN), self.initial.pick(&*OUT)) {
 			(Value::Thing(ref l), Value::Thing(ref r)) => {
@@ -54,6 +71,9 @@ impl<'a> Document<'a> {
 				// Purge the right pointer edge
 				let key = crate::key::graph::new(opt.ns(), opt.db(), &r.tb, &r.id, i, rid);
 				run.del(key).await?;
+				// Purge the edge between the pair of records
+				let key = crate::key::re::new(opt.ns(), opt.db(), &rid.tb, l, r, &rid.id);
+				run.del(key).await?;
 			}
 			_ => {
 				// Release the transaction
@@ -17,8 +17,10 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
 		// Check if the table is a view
-		if self.tb(opt, txn).await?.drop {
+		if tb.drop {
 			return Ok(());
 		}
 		// Clone transaction
@@ -30,6 +32,12 @@ impl<'a> Document<'a> {
 		// Store the record data
 		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
 		run.set(key, self).await?;
+		// Store the record history
+		if tb.history.is_some() {
+			let (ns, db, tb, id) =
+				(opt.ns().to_owned(), opt.db().to_owned(), rid.tb.clone(), rid.id.clone());
+			run.stamp(move |vs, _| crate::key::history::new(&ns, &db, &tb, &id, vs).into(), self);
+		}
 		// Carry on
 		Ok(())
 	}
@@ -2,22 +2,27 @@ use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::dbs::Statement;
 use crate::dbs::Transaction;
+use crate::dbs::Workable;
 use crate::doc::Document;
 use crate::err::Error;
+use crate::idx::build::Build;
+use crate::sql::array::Array;
 use crate::sql::data::Data;
 use crate::sql::expression::Expression;
 use crate::sql::field::{Field, Fields};
 use crate::sql::idiom::Idiom;
+use crate::sql::number::Number;
 use crate::sql::operator::Operator;
 use crate::sql::part::Part;
-use crate::sql::statement::Statement as Query;
+use crate::sql::statements::define::DefineTableStatement;
 use crate::sql::statements::delete::DeleteStatement;
-use crate::sql::statements::ifelse::IfelseStatement;
 use crate::sql::statements::update::UpdateStatement;
 use crate::sql::subquery::Subquery;
 use crate::sql::thing::Thing;
 use crate::sql::value::{Value, Values};
+use async_recursion::async_recursion;
 use futures::future::try_join_all;
+use std::borrow::Cow;
 
 type Ops = Vec<(Idiom, Operator, Value)>;
 
@@ -44,6 +49,195 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Loop through all foreign table statements
+		for ft in self.ft(opt, txn).await?.iter() {
+			// Records which a view build has not reached are processed by the build
+			if self.built(opt, txn, ft).await? {
+				self.table_one(ctx, opt, txn, stm, ft).await?;
+				self.link_one(ctx, opt, txn, stm, ft).await?;
+			}
+		}
+		// Add the records which join fields from this record back to their views
+		self.linked(ctx, opt, txn, false).await?;
+		// Carry on
+		Ok(())
+	}
+	// Remove the records which join fields from this record from their views,
+	// while the previous version of this record is still in the datastore
+	pub async fn unlink(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check events
+		if !opt.tables {
+			return Ok(());
+		}
+		// Check if forced
+		if !opt.force && !self.changed() {
+			return Ok(());
+		}
+		// Remove the joining records from their views
+		self.linked(ctx, opt, txn, true).await
+	}
+	// Remove or add the records which join fields from this record
+	#[cfg_attr(feature = "parallel", async_recursion)]
+	#[cfg_attr(not(feature = "parallel"), async_recursion(?Send))]
+	async fn linked(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		del: bool,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		let (ns, db) = (opt.ns(), opt.db());
+		// Joining records are removed from or added to their views
+		let opt = &opt.force(false);
+		let dst = DeleteStatement::default();
+		let ust = UpdateStatement::default();
+		let stm = match del {
+			true => Statement::from(&dst),
+			false => Statement::from(&ust),
+		};
+		// Loop over the joining records in batches
+		let mut beg = crate::key::vl::prefix(ns, db, &rid.tb, &rid.id);
+		let end = crate::key::vl::suffix(ns, db, &rid.tb, &rid.id);
+		loop {
+			// Get the next batch of key-value entries
+			let res = txn.clone().lock().await.scan(beg.clone()..end.clone(), 1000).await?;
+			// Check if this is the last batch
+			let done = res.len() < 1000;
+			// Loop over results
+			for (k, _) in res.into_iter() {
+				// Continue after this record
+				beg = k.clone();
+				beg.push(0x00);
+				// Parse the joining record
+				let key: crate::key::vl::Vl = (&k).into();
+				let fk = Thing::from((key.ft, key.fk));
+				// Records which join their own fields are processed as usual
+				if &fk == rid {
+					continue;
+				}
+				// Fetch the view definition and the joining record
+				let fts = txn.clone().lock().await.all_ft(ns, db, &fk.tb).await?;
+				let ft = fts.iter().find(|v| v.name.0 == key.vw);
+				let val = crate::key::thing::new(ns, db, &fk.tb, &fk.id);
+				let val = txn.clone().lock().await.get(val).await?;
+				// Remove the link if the view or the record was removed
+				let (ft, val) = match (ft, val) {
+					(Some(ft), Some(v)) => (ft, Value::from(v)),
+					_ => {
+						txn.clone().lock().await.del(k).await?;
+						continue;
+					}
+				};
+				// Remove the link if the record no longer joins this record
+				if del && !links(ctx, opt, txn, ft, &val).await?.contains(rid) {
+					txn.clone().lock().await.del(k).await?;
+					continue;
+				}
+				// Process the joining record as if it was removed or created
+				let mut doc = Document::new(Some(fk), &val, Workable::Normal);
+				if !del {
+					doc.initial = Cow::Owned(Value::None);
+				}
+				// Records which a view build has not reached are processed by the build
+				if doc.built(opt, txn, ft).await? {
+					doc.table_one(ctx, opt, txn, &stm, ft).await?;
+					doc.link_one(ctx, opt, txn, &stm, ft).await?;
+				}
+			}
+			// All joining records are processed
+			if done {
+				break;
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+	// Track the records which a view joins fields from for this document
+	pub(crate) async fn link_one(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		ft: &DefineTableStatement,
+	) -> Result<(), Error> {
+		// Joining records are not removed from the view before
+		// they are added back, so their links are kept as they are
+		if stm.is_delete() && self.initial == self.current {
+			return Ok(());
+		}
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		let (ns, db) = (opt.ns(), opt.db());
+		// Get the records which are joined before and after the change
+		let old = match self.is_new() {
+			true => vec![],
+			false => links(ctx, opt, txn, ft, &self.initial).await?,
+		};
+		let new = match stm.is_delete() {
+			true => vec![],
+			false => links(ctx, opt, txn, ft, &self.current).await?,
+		};
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Remove the records which are no longer joined
+		for v in old.iter().filter(|v| !new.contains(v)) {
+			run.del(crate::key::vl::new(ns, db, &v.tb, &v.id, &ft.name, rid)).await?;
+		}
+		// Store the records which are joined
+		for v in new.iter() {
+			run.set(crate::key::vl::new(ns, db, &v.tb, &v.id, &ft.name, rid), vec![]).await?;
+		}
+		// Carry on
+		Ok(())
+	}
+	// Check if a view build in progress has processed this record
+	async fn built(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		ft: &DefineTableStatement,
+	) -> Result<bool, Error> {
+		// Fetch the view build progress
+		let key = crate::key::vb::new(opt.ns(), opt.db(), &ft.name);
+		let bld: Build = match txn.lock().await.get(key).await? {
+			Some(v) => (&v).into(),
+			None => return Ok(true),
+		};
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// The source tables are processed in order
+		let what = ft.view.as_ref().map(|v| v.what.0.iter().position(|v| v.0 == rid.tb));
+		match what.flatten() {
+			Some(i) if i < bld.what => Ok(true),
+			Some(i) if i > bld.what => Ok(false),
+			Some(_) => {
+				let key: Vec<u8> =
+					crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id).into();
+				Ok(matches!(bld.pos, Some(pos) if key <= pos))
+			}
+			None => Ok(true),
+		}
+	}
+	// Update the data of a single foreign table for this document
+	pub(crate) async fn table_one(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		ft: &DefineTableStatement,
+	) -> Result<(), Error> {
 		// Get the record id
 		let rid = self.id.as_ref().unwrap();
 		// Get the query action
@@ -54,189 +248,151 @@ impl<'a> Document<'a> {
 		} else {
 			Action::Update
 		};
-		// Loop through all foreign table statements
-		for ft in self.ft(opt, txn).await?.iter() {
-			// Get the table definition
-			let tb = ft.view.as_ref().unwrap();
-			// Check if there is a GROUP BY clause
-			match &tb.group {
-				// There is a GROUP BY clause specified
-				Some(group) => {
-					// Set the previous record id
-					let old = Thing {
-						tb: ft.name.to_raw(),
-						id: try_join_all(
-							group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.initial))),
-						)
-						.await?
-						.iter()
-						.map(|v| format!("{}", v))
-						.collect::<Vec<_>>()
-						.join(" ")
-						.into(),
-					};
-					// Set the current record id
-					let rid = Thing {
-						tb: ft.name.to_raw(),
-						id: try_join_all(
-							group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.current))),
-						)
-						.await?
-						.iter()
-						.map(|v| format!("{}", v))
-						.collect::<Vec<_>>()
-						.join(" ")
-						.into(),
-					};
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
-								v if v.is_truthy() => {
-									if !opt.force && act != Action::Create {
-										// Delete the old value
-										let act = Action::Delete;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-									if act != Action::Delete {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
-								_ => {
-									if !opt.force && act != Action::Create {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
-							}
-						}
-						// No WHERE clause is specified
-						None => {
-							if !opt.force && act != Action::Create {
-								// Delete the old value
-								let act = Action::Delete;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(old)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
-								};
-								// Execute the statement
-								stm.compute(ctx, opt, txn, None).await?;
-							}
-							if act != Action::Delete {
-								// Update the new value
-								let act = Action::Update;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(rid)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
-								};
-								// Execute the statement
-								stm.compute(ctx, opt, txn, None).await?;
-							}
-						}
+		// Get the table definition
+		let tb = ft.view.as_ref().unwrap();
+		// Check if there is a GROUP BY clause
+		match &tb.group {
+			// There is a GROUP BY clause specified
+			Some(group) => {
+				// Set the previous record id
+				let old = Thing {
+					tb: ft.name.to_raw(),
+					id: try_join_all(
+						group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.initial))),
+					)
+					.await?
+					.iter()
+					.map(|v| format!("{}", v))
+					.collect::<Vec<_>>()
+					.join(" ")
+					.into(),
+				};
+				// Set the current record id
+				let rid = Thing {
+					tb: ft.name.to_raw(),
+					id: try_join_all(
+						group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.current))),
+					)
+					.await?
+					.iter()
+					.map(|v| format!("{}", v))
+					.collect::<Vec<_>>()
+					.join(" ")
+					.into(),
+				};
+				// Check if the previous record was in the view
+				let was = match &tb.cond {
+					_ if self.is_new() => false,
+					Some(cond) => {
+						cond.compute(ctx, opt, txn, Some(&self.initial)).await?.is_truthy()
+					}
+					None => true,
+				};
+				// Check if the current record is in the view
+				let is = match &tb.cond {
+					_ if act == Action::Delete => false,
+					Some(cond) => {
+						cond.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy()
 					}
+					None => true,
+				};
+				if !opt.force && was {
+					// Delete the old value
+					let act = Action::Delete;
+					// Modify the value in the table
+					let stm = UpdateStatement {
+						what: Values(vec![Value::from(old.clone())]),
+						data: Some(self.data(ctx, opt, txn, act, &tb.expr, &old).await?),
+						..UpdateStatement::default()
+					};
+					// Execute the statement
+					stm.compute(ctx, opt, txn, None).await?;
 				}
-				// No GROUP BY clause is specified
-				None => {
-					// Set the current record id
-					let rid = Thing {
-						tb: ft.name.to_raw(),
-						id: rid.id.clone(),
+				if is {
+					// Update the new value
+					let act = Action::Update;
+					// Modify the value in the table
+					let stm = UpdateStatement {
+						what: Values(vec![Value::from(rid.clone())]),
+						data: Some(self.data(ctx, opt, txn, act, &tb.expr, &rid).await?),
+						..UpdateStatement::default()
 					};
-					// Use the current record data
-					let doc = Some(self.current.as_ref());
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, doc).await? {
-								v if v.is_truthy() => {
-									// Define the statement
-									let stm = match act {
-										// Delete the value in the table
-										Action::Delete => Query::Delete(DeleteStatement {
-											what: Values(vec![Value::from(rid)]),
-											..DeleteStatement::default()
-										}),
-										// Update the value in the table
-										_ => Query::Update(UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(Data::ReplaceExpression(
-												tb.expr.compute(ctx, opt, txn, doc, false).await?,
-											)),
-											..UpdateStatement::default()
-										}),
+					// Execute the statement
+					stm.compute(ctx, opt, txn, None).await?;
+				}
+			}
+			// No GROUP BY clause is specified
+			None => {
+				// Set the current record id
+				let rid = Thing {
+					tb: ft.name.to_raw(),
+					id: rid.id.clone(),
+				};
+				// Use the current record data
+				let doc = Some(self.current.as_ref());
+				// Check if a WHERE clause is specified
+				match &tb.cond {
+					// There is a WHERE clause specified
+					Some(cond) => {
+						match cond.compute(ctx, opt, txn, doc).await? {
+							v if v.is_truthy() => match act {
+								// Delete the value in the table
+								Action::Delete => {
+									let stm = DeleteStatement {
+										what: Values(vec![Value::from(rid)]),
+										..DeleteStatement::default()
 									};
 									// Execute the statement
 									stm.compute(ctx, opt, txn, None).await?;
 								}
+								// Update the value in the table
 								_ => {
-									// Delete the value in the table
-									let stm = DeleteStatement {
+									let stm = UpdateStatement {
 										what: Values(vec![Value::from(rid)]),
-										..DeleteStatement::default()
+										data: Some(Data::ReplaceExpression(
+											tb.expr.compute(ctx, opt, txn, doc, false).await?,
+										)),
+										..UpdateStatement::default()
 									};
 									// Execute the statement
 									stm.compute(ctx, opt, txn, None).await?;
 								}
-							}
-						}
-						// No WHERE clause is specified
-						None => {
-							// Define the statement
-							let stm = match act {
+							},
+							_ => {
 								// Delete the value in the table
-								Action::Delete => Query::Delete(DeleteStatement {
+								let stm = DeleteStatement {
 									what: Values(vec![Value::from(rid)]),
 									..DeleteStatement::default()
-								}),
-								// Update the value in the table
-								_ => Query::Update(UpdateStatement {
-									what: Values(vec![Value::from(rid)]),
-									data: Some(Data::ReplaceExpression(
-										tb.expr.compute(ctx, opt, txn, doc, false).await?,
-									)),
-									..UpdateStatement::default()
-								}),
+								};
+								// Execute the statement
+								stm.compute(ctx, opt, txn, None).await?;
+							}
+						}
+					}
+					// No WHERE clause is specified
+					None => match act {
+						// Delete the value in the table
+						Action::Delete => {
+							let stm = DeleteStatement {
+								what: Values(vec![Value::from(rid)]),
+								..DeleteStatement::default()
 							};
 							// Execute the statement
 							stm.compute(ctx, opt, txn, None).await?;
 						}
-					}
+						// Update the value in the table
+						_ => {
+							let stm = UpdateStatement {
+								what: Values(vec![Value::from(rid)]),
+								data: Some(Data::ReplaceExpression(
+									tb.expr.compute(ctx, opt, txn, doc, false).await?,
+								)),
+								..UpdateStatement::default()
+							};
+							// Execute the statement
+							stm.compute(ctx, opt, txn, None).await?;
+						}
+					},
 				}
 			}
 		}
@@ -251,6 +407,7 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		act: Action,
 		exp: &Fields,
+		tid: &Thing,
 	) -> Result<Data, Error> {
 		//
 		let mut ops: Ops = vec![];
@@ -274,13 +431,18 @@ impl<'a> Document<'a> {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
 							self.chg(&mut ops, &act, v.to_idiom(), val);
 						}
-						"math::min" => {
+						"math::min" | "math::max" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.min(&mut ops, &act, v.to_idiom(), val);
+							let max = f.name() == "math::max";
+							let val =
+								self.ext(opt, txn, &act, tid, &v.to_idiom(), max, val).await?;
+							self.set(&mut ops, v.to_idiom(), val);
 						}
-						"math::max" => {
+						"math::stddev" | "math::variance" | "array::distinct" | "array::group" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.max(&mut ops, &act, v.to_idiom(), val);
+							let val = self.all(opt, txn, &act, tid, &v.to_idiom(), val).await?;
+							let val = f.aggregate(val).compute(ctx, opt, txn, None).await?;
+							self.set(&mut ops, v.to_idiom(), val);
 						}
 						"math::mean" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
@@ -306,13 +468,18 @@ impl<'a> Document<'a> {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
 							self.chg(&mut ops, &act, i.to_owned(), val);
 						}
-						"math::min" => {
+						"math::min" | "math::max" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.min(&mut ops, &act, i.to_owned(), val);
+							let max = f.name() == "math::max";
+							let val =
+								self.ext(opt, txn, &act, tid, &i.to_owned(), max, val).await?;
+							self.set(&mut ops, i.to_owned(), val);
 						}
-						"math::max" => {
+						"math::stddev" | "math::variance" | "array::distinct" | "array::group" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.max(&mut ops, &act, i.to_owned(), val);
+							let val = self.all(opt, txn, &act, tid, &i.to_owned(), val).await?;
+							let val = f.aggregate(val).compute(ctx, opt, txn, None).await?;
+							self.set(&mut ops, i.to_owned(), val);
 						}
 						"math::mean" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
@@ -346,45 +513,140 @@ impl<'a> Document<'a> {
 			val,
 		));
 	}
-	// Set the new minimum value for the field in the foreign table
-	fn min(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
-		if act == &Action::Update {
-			ops.push((
-				key.clone(),
-				Operator::Equal,
-				Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
-					exprs: vec![(
-						Value::Expression(Box::new(Expression {
-							l: Value::Idiom(key.clone()),
-							o: Operator::MoreThan,
-							r: val.clone(),
-						})),
-						val,
-					)],
-					close: Some(Value::Idiom(key)),
-				}))),
-			));
+	// Update the number of times a value is counted for the field in the foreign table
+	async fn cnt(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		tid: &Thing,
+		fd: &str,
+		val: &Value,
+	) -> Result<(), Error> {
+		// Each distinct value is counted under its own key
+		let key = crate::key::va::new(opt.ns(), opt.db(), &tid.tb, &tid.id, fd, val);
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Update the number of times the value is counted
+		let cnt = match run.get(key.clone()).await? {
+			Some(v) => Value::from(&v).as_int(),
+			None => 0,
+		};
+		match act {
+			Action::Delete if cnt > 1 => run.set(key, Value::from(cnt - 1)).await?,
+			Action::Delete => run.del(key).await?,
+			Action::Update => run.set(key, Value::from(cnt + 1)).await?,
+			_ => unreachable!(),
+		};
+		// Carry on
+		Ok(())
+	}
+	// Add or remove the value from the values of the field in the foreign table
+	async fn all(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		tid: &Thing,
+		key: &Idiom,
+		val: Value,
+	) -> Result<Value, Error> {
+		// Update the number of times the value is counted
+		let fd = key.to_hash();
+		self.cnt(opt, txn, act, tid, &fd, &val).await?;
+		// Collect the counted values in batches
+		let (ns, db) = (opt.ns(), opt.db());
+		let mut all = Array::new();
+		let mut beg = crate::key::va::prefix_fd(ns, db, &tid.tb, &tid.id, &fd);
+		let end = crate::key::va::suffix_fd(ns, db, &tid.tb, &tid.id, &fd);
+		loop {
+			// Get the next batch of key-value entries
+			let res = txn.clone().lock().await.scan(beg.clone()..end.clone(), 1000).await?;
+			// Check if this is the last batch
+			let done = res.len() < 1000;
+			// Loop over results
+			for (k, v) in res.into_iter() {
+				let key: crate::key::va::Va = (&k).into();
+				for _ in 0..Value::from(&v).as_int() {
+					all.push(key.val.clone());
+				}
+				// Continue after this value
+				beg = k;
+				beg.push(0x00);
+			}
+			// All values are collected
+			if done {
+				break;
+			}
 		}
+		//
+		Ok(Value::from(all))
 	}
-	// Set the new maximum value for the field in the foreign table
-	fn max(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
-		if act == &Action::Update {
-			ops.push((
-				key.clone(),
-				Operator::Equal,
-				Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
-					exprs: vec![(
-						Value::Expression(Box::new(Expression {
-							l: Value::Idiom(key.clone()),
-							o: Operator::LessThan,
-							r: val.clone(),
-						})),
-						val,
-					)],
-					close: Some(Value::Idiom(key)),
-				}))),
-			));
+	// Add or remove the value from the ordered values of the field in the
+	// foreign table, and get the lowest or highest value which remains
+	#[allow(clippy::too_many_arguments)]
+	async fn ext(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		tid: &Thing,
+		key: &Idiom,
+		max: bool,
+		val: Value,
+	) -> Result<Value, Error> {
+		// Integers, floats, and decimals are not encoded in numeric order,
+		// so each number is counted after its floating point value, which
+		// is negated for the highest value to be the first counted value
+		let num = val.as_number();
+		let pos = match max {
+			true => -num.to_float(),
+			false => num.to_float(),
+		};
+		let val = Value::from(vec![Value::from(pos), Value::from(num)]);
+		// Update the number of times the value is counted
+		let fd = key.to_hash();
+		self.cnt(opt, txn, act, tid, &fd, &val).await?;
+		// Find the first counted value, and any with the same float value
+		let (ns, db) = (opt.ns(), opt.db());
+		let mut out: Option<(Value, Number)> = None;
+		let mut beg = crate::key::va::prefix_fd(ns, db, &tid.tb, &tid.id, &fd);
+		let end = crate::key::va::suffix_fd(ns, db, &tid.tb, &tid.id, &fd);
+		'scan: loop {
+			// Get the next batch of key-value entries
+			let res = txn.clone().lock().await.scan(beg.clone()..end.clone(), 10).await?;
+			// Check if this is the last batch
+			let done = res.len() < 10;
+			// Loop over results
+			for (k, _) in res.into_iter() {
+				let key: crate::key::va::Va = (&k).into();
+				let (pos, num) = match key.val {
+					Value::Array(mut v) if v.len() == 2 => (v.remove(0), v.remove(0).as_number()),
+					_ => continue,
+				};
+				out = match out {
+					// Neighbouring numbers can share a float value
+					Some((p, n)) if p == pos => match max {
+						true => Some((p, n.max(num))),
+						false => Some((p, n.min(num))),
+					},
+					// Values after the first float value are not needed
+					Some(_) => break 'scan,
+					None => Some((pos, num)),
+				};
+				// Continue after this value
+				beg = k;
+				beg.push(0x00);
+			}
+			// All values are checked
+			if done {
+				break;
+			}
 		}
+		//
+		Ok(out.map_or(Value::None, |(_, n)| n.into()))
 	}
 	// Set the new average value for the field in the foreign table
 	fn mean(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
@@ -440,3 +702,66 @@ impl<'a> Document<'a> {
 		));
 	}
 }
+
+// Get the records which a view joins fields from for a source record,
+// by fetching every record link which the view fields are read through
+async fn links(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ft: &DefineTableStatement,
+	doc: &Value,
+) -> Result<Vec<Thing>, Error> {
+	let mut out: Vec<Thing> = vec![];
+	// Get the view definition
+	let view = match &ft.view {
+		Some(v) => v,
+		None => return Ok(out),
+	};
+	// Find the fields which are read by the view
+	let mut all = vec![];
+	for v in view.expr.iter() {
+		if let Field::Alone(v) | Field::Alias(v, _) = v {
+			idioms(v, &mut all);
+		}
+	}
+	if let Some(v) = &view.cond {
+		idioms(&v.0, &mut all);
+	}
+	if let Some(v) = &view.group {
+		all.extend(v.iter().map(|v| &v.0));
+	}
+	// Fetch the value at each part of the fields
+	for i in all {
+		for n in 1..i.len() {
+			let v = Idiom::from(i[..n].to_vec()).compute(ctx, opt, txn, Some(doc)).await?;
+			let v = match v {
+				Value::Array(v) => v.0,
+				v => vec![v],
+			};
+			for v in v {
+				if let Value::Thing(v) = v {
+					if !out.contains(&v) {
+						out.push(v);
+					}
+				}
+			}
+		}
+	}
+	Ok(out)
+}
+
+// Collect the fields which are read by a view expression
+fn idioms<'a>(v: &'a Value, out: &mut Vec<&'a Idiom>) {
+	match v {
+		Value::Idiom(v) => out.push(v),
+		Value::Function(v) => v.args().iter().for_each(|v| idioms(v, out)),
+		Value::Expression(v) => {
+			idioms(&v.l, out);
+			idioms(&v.r, out);
+		}
+		Value::Array(v) => v.iter().for_each(|v| idioms(v, out)),
+		Value::Object(v) => v.values().for_each(|v| idioms(v, out)),
+		_ => (),
+	}
+}
@@ -41,6 +41,10 @@ pub enum Error {
 	#[error("The key being inserted already exists")]
 	TxKeyAlreadyExists,
 
+	/// The transaction conflicted with another concurrent transaction
+	#[error("Failed to commit transaction due to a read or write conflict. This transaction can be retried")]
+	TxConflict,
+
 	/// No namespace has been selected
 	#[error("Specify a namespace to use")]
 	NsEmpty,
@@ -88,6 +92,10 @@ pub enum Error {
 	#[error("The query was not executed because it exceeded the timeout")]
 	QueryTimedout,
 
+	/// The query was cancelled using a KILL QUERY statement
+	#[error("The query was not executed because it was killed")]
+	QueryKilled,
+
 	/// The query did not execute, because the transaction was cancelled
 	#[error("The query was not executed due to a cancelled transaction")]
 	QueryCancelled,
@@ -148,6 +156,16 @@ pub enum Error {
 	#[error("The table does not exist")]
 	TbNotFound,
 
+	/// The requested index does not exist
+	#[error("The index does not exist")]
+	IxNotFound,
+
+	/// The requested function does not exist
+	#[error("The function 'fn::{value}' does not exist")]
+	FcNotFound {
+		value: String,
+	},
+
 	/// Unable to perform the realtime query
 	#[error("Unable to perform the realtime query")]
 	RealtimeDisabled,
@@ -156,6 +174,18 @@ pub enum Error {
 	#[error("Too many recursive subqueries have been processed")]
 	TooManySubqueries,
 
+	/// Too many paths have been visited by a recursive graph traversal
+	#[error("The graph traversal visited more than {limit} paths")]
+	TooManyGraphPaths {
+		limit: usize,
+	},
+
+	/// The input of a window function is not grouped
+	#[error("The window function input '{value}' must be in the GROUP BY clause or an aggregate function")]
+	InvalidWindow {
+		value: String,
+	},
+
 	/// Can not execute CREATE query using the specified value
 	#[error("Can not execute CREATE query using value '{value}'")]
 	CreateStatement {
@@ -180,6 +210,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// Can not execute UPSERT query using the specified value
+	#[error("Can not execute UPSERT query using value '{value}'")]
+	UpsertStatement {
+		value: String,
+	},
+
 	/// Can not execute INSERT query using the specified value
 	#[error("Can not execute INSERT query using value '{value}'")]
 	InsertStatement {
@@ -204,12 +240,26 @@ pub enum Error {
 		table: String,
 	},
 
+	/// The permissions do not allow this function to be run
+	#[error("You don't have permission to run the `fn::{name}` function")]
+	FunctionPermissions {
+		name: String,
+	},
+
 	/// The specified table can not be written as it is setup as a foreign table view
 	#[error("Unable to write to the `{table}` table while setup as a view")]
 	TableIsView {
 		table: String,
 	},
 
+	/// The specified table can not be setup as a view which is ordered or limited
+	#[error(
+		"Unable to setup the `{table}` table as a view with ORDER BY, LIMIT, or START clauses"
+	)]
+	TableViewLimited {
+		table: String,
+	},
+
 	/// A database entry for the specified record already exists
 	#[error("Database record `{thing}` already exists")]
 	RecordExists {
@@ -224,6 +274,23 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The specified field did not conform to the field TYPE clause
+	#[error("Found {value} for field `{field}`, with record `{thing}`, but field must be of type {check}")]
+	FieldCheck {
+		thing: String,
+		value: String,
+		field: Idiom,
+		check: String,
+	},
+
+	/// The specified field is readonly and can not be changed
+	#[error("Found {value} for field `{field}`, with record `{thing}`, but field is readonly")]
+	FieldReadonly {
+		thing: String,
+		value: String,
+		field: Idiom,
+	},
+
 	/// The specified field did not conform to the field ASSERT clause
 	#[error("Found {value} for field `{field}`, with record `{thing}`, but field must conform to: {check}")]
 	FieldValue {
@@ -247,6 +314,22 @@ pub enum Error {
 	#[error("There was an error processing a value in parallel")]
 	Channel(String),
 
+	/// The cursor used to resume a query is not valid
+	#[error("Invalid cursor. {message}")]
+	InvalidCursor {
+		message: String,
+	},
+
+	/// An incremental backup was requested without the change log
+	#[error("Unable to take an incremental backup, as the change log is not enabled")]
+	ChangeLogDisabled,
+
+	/// The backup data is invalid or could not be verified
+	#[error("Unable to restore the backup. {message}")]
+	InvalidBackup {
+		message: String,
+	},
+
 	/// Represents an underlying error with Serde encoding / decoding
 	#[error("Serde error: {0}")]
 	Serde(#[from] SerdeError),
@@ -291,6 +374,7 @@ impl From<tikv::Error> for Error {
 	fn from(e: tikv::Error) -> Error {
 		match e {
 			tikv::Error::DuplicateKeyInsertion => Error::TxKeyAlreadyExists,
+			tikv::Error::KeyError(e) if e.conflict.is_some() => Error::TxConflict,
 			_ => Error::Tx(e.to_string()),
 		}
 	}
@@ -299,7 +383,11 @@ impl From<tikv::Error> for Error {
 #[cfg(feature = "kv-rocksdb")]
 impl From<rocksdb::Error> for Error {
 	fn from(e: rocksdb::Error) -> Error {
-		Error::Tx(e.to_string())
+		match e.kind() {
+			rocksdb::ErrorKind::Busy => Error::TxConflict,
+			rocksdb::ErrorKind::TryAgain => Error::TxConflict,
+			_ => Error::Tx(e.to_string()),
+		}
 	}
 }
 
@@ -3,6 +3,7 @@ use crate::err::Error;
 use crate::sql::array::Combine;
 use crate::sql::array::Concat;
 use crate::sql::array::Difference;
+use crate::sql::array::Flatten;
 use crate::sql::array::Intersect;
 use crate::sql::array::Union;
 use crate::sql::array::Uniq;
@@ -45,6 +46,13 @@ pub fn distinct(_: &Context, mut args: Vec<Value>) -> Result<Value, Error> {
 	}
 }
 
+pub fn group(_: &Context, mut args: Vec<Value>) -> Result<Value, Error> {
+	match args.remove(0) {
+		Value::Array(v) => Ok(v.flatten().uniq().into()),
+		_ => Ok(Value::None),
+	}
+}
+
 pub fn intersect(_: &Context, mut args: Vec<Value>) -> Result<Value, Error> {
 	match args.remove(0) {
 		Value::Array(v) => match args.remove(0) {
@@ -0,0 +1,465 @@
+use crate::ctx::Context;
+use crate::dbs::Level;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::key::graph;
+use crate::key::thing;
+use crate::kvs::Key;
+use crate::sql::dir::Dir;
+use crate::sql::number::Number;
+use crate::sql::object::Object;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+// The damping factor used when computing the pagerank
+const DAMPING: f64 = 0.85;
+// The maximum number of pagerank iterations
+const ITERATIONS: usize = 100;
+// The total change at which the pagerank has converged
+const TOLERANCE: f64 = 1e-6;
+
+// The graph functions read the record adjacency keys directly,
+// without fetching the edge or node records. As table and field
+// permissions are not applied, these functions can only be run
+// by database users, namespace users, or root users.
+
+struct Walker<'a> {
+	ctx: &'a Context<'a>,
+	opt: &'a Options,
+	txn: &'a Transaction,
+	edges: Vec<String>,
+	dir: Dir,
+}
+
+impl<'a> Walker<'a> {
+	// Fetch the edges of a record in a single direction
+	async fn edges(&self, rid: &Thing, dir: &Dir) -> Result<Vec<Thing>, Error> {
+		let (ns, db) = (self.opt.ns(), self.opt.db());
+		// Get the key ranges to scan
+		let rng = match self.edges.is_empty() {
+			true => vec![(
+				graph::egprefix(ns, db, &rid.tb, &rid.id, dir),
+				graph::egsuffix(ns, db, &rid.tb, &rid.id, dir),
+			)],
+			false => self
+				.edges
+				.iter()
+				.map(|ft| {
+					(
+						graph::ftprefix(ns, db, &rid.tb, &rid.id, dir, ft),
+						graph::ftsuffix(ns, db, &rid.tb, &rid.id, dir, ft),
+					)
+				})
+				.collect(),
+		};
+		// Scan the adjacency keys
+		let mut out = Vec::new();
+		for (beg, end) in rng.into_iter() {
+			for k in keys(self.ctx, self.txn, beg, end).await?.into_iter() {
+				let gra: graph::Graph = (&k).into();
+				out.push(Thing::from((gra.ft, gra.fk)));
+			}
+		}
+		Ok(out)
+	}
+	// Fetch the edges and records adjacent to a record
+	async fn adjacent(&self, rid: &Thing) -> Result<Vec<(Thing, Thing)>, Error> {
+		let dirs = match self.dir {
+			Dir::Both => vec![Dir::Out, Dir::In],
+			_ => vec![self.dir.clone()],
+		};
+		let mut out = Vec::new();
+		for dir in dirs.iter() {
+			for e in self.edges(rid, dir).await? {
+				// Edges point onwards in the same direction
+				for n in self.all(&e, dir).await? {
+					out.push((e.clone(), n));
+				}
+			}
+		}
+		Ok(out)
+	}
+	// Fetch all of the pointers of a record in a single direction
+	async fn all(&self, rid: &Thing, dir: &Dir) -> Result<Vec<Thing>, Error> {
+		let (ns, db) = (self.opt.ns(), self.opt.db());
+		let beg = graph::egprefix(ns, db, &rid.tb, &rid.id, dir);
+		let end = graph::egsuffix(ns, db, &rid.tb, &rid.id, dir);
+		let res = keys(self.ctx, self.txn, beg, end).await?;
+		Ok(res
+			.into_iter()
+			.map(|k| {
+				let gra: graph::Graph = (&k).into();
+				Thing::from((gra.ft, gra.fk))
+			})
+			.collect())
+	}
+	// Fetch the distinct records adjacent to a record
+	async fn neighbours(&self, rid: &Thing) -> Result<Vec<Thing>, Error> {
+		let mut seen = HashSet::new();
+		let mut out = Vec::new();
+		for (_, n) in self.adjacent(rid).await? {
+			if seen.insert(n.to_raw()) {
+				out.push(n);
+			}
+		}
+		Ok(out)
+	}
+}
+
+pub async fn neighbours(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	let name = "graph::neighbours";
+	let mut args = check(opt, name, args, 1, 3)?;
+	let rid = record(name, args.remove(0), "first")?;
+	// Neighbours are found in both directions by default
+	let walk = walker(ctx, opt, txn, name, args, Dir::Both)?;
+	Ok(walk.neighbours(&rid).await?.into_iter().map(Value::from).collect::<Vec<_>>().into())
+}
+
+pub async fn degree(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	let name = "graph::degree";
+	let mut args = check(opt, name, args, 1, 3)?;
+	let rid = record(name, args.remove(0), "first")?;
+	// Edges are counted in both directions by default
+	let walk = walker(ctx, opt, txn, name, args, Dir::Both)?;
+	let mut count = 0;
+	for dir in [Dir::Out, Dir::In].iter() {
+		if walk.dir == Dir::Both || &walk.dir == dir {
+			count += walk.edges(&rid, dir).await?.len();
+		}
+	}
+	Ok(count.into())
+}
+
+pub async fn shortest_path(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	let name = "graph::shortest_path";
+	let mut args = check(opt, name, args, 2, 4)?;
+	let beg = record(name, args.remove(0), "first")?;
+	let end = record(name, args.remove(0), "second")?;
+	// Paths follow the edge direction by default
+	let walk = walker(ctx, opt, txn, name, args, Dir::Out)?;
+	// A record has a path to itself
+	if beg == end {
+		return Ok(vec![Value::from(beg)].into());
+	}
+	// Walk the graph in breadth-first order
+	let mut prev: HashMap<String, (Thing, Thing)> = HashMap::new();
+	let mut seen = HashSet::new();
+	seen.insert(beg.to_raw());
+	let mut queue = VecDeque::from([beg.clone()]);
+	while let Some(v) = queue.pop_front() {
+		// Check if the context is finished
+		ctx.check()?;
+		for (e, n) in walk.adjacent(&v).await? {
+			if !seen.insert(n.to_raw()) {
+				continue;
+			}
+			prev.insert(n.to_raw(), (v.clone(), e));
+			// Rebuild the path once the record is reached
+			if n == end {
+				let mut path = vec![Value::from(n)];
+				let mut cur = end.to_raw();
+				while let Some((v, e)) = prev.get(&cur) {
+					path.push(Value::from(e.clone()));
+					path.push(Value::from(v.clone()));
+					cur = v.to_raw();
+				}
+				path.reverse();
+				return Ok(path.into());
+			}
+			queue.push_back(n);
+		}
+	}
+	// The record could not be reached
+	Ok(Value::None)
+}
+
+pub async fn connected_components(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	let name = "graph::connected_components";
+	let mut args = check(opt, name, args, 1, 2)?;
+	let all = records(ctx, opt, txn, name, args.remove(0)).await?;
+	// Components ignore the edge direction
+	let walk = walker(ctx, opt, txn, name, args, Dir::Both)?;
+	let set: HashSet<String> = all.iter().map(|v| v.to_raw()).collect();
+	let mut seen = HashSet::new();
+	let mut out = Vec::new();
+	for rid in all.into_iter() {
+		// Skip records which are already in a component
+		if !seen.insert(rid.to_raw()) {
+			continue;
+		}
+		// Find every record connected to this one
+		let mut component = vec![];
+		let mut queue = VecDeque::from([rid]);
+		while let Some(v) = queue.pop_front() {
+			// Check if the context is finished
+			ctx.check()?;
+			for n in walk.neighbours(&v).await? {
+				if set.contains(&n.to_raw()) && seen.insert(n.to_raw()) {
+					queue.push_back(n);
+				}
+			}
+			component.push(Value::from(v));
+		}
+		// Order the component for a stable output
+		component.sort();
+		out.push(Value::from(component));
+	}
+	Ok(out.into())
+}
+
+pub async fn pagerank(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	let name = "graph::pagerank";
+	let mut args = check(opt, name, args, 1, 2)?;
+	let all = records(ctx, opt, txn, name, args.remove(0)).await?;
+	// Rank flows in the edge direction
+	let walk = walker(ctx, opt, txn, name, args, Dir::Out)?;
+	// Index each of the records
+	let len = all.len();
+	if len == 0 {
+		return Ok(Value::from(Vec::<Value>::new()));
+	}
+	let idx: HashMap<String, usize> =
+		all.iter().enumerate().map(|(i, v)| (v.to_raw(), i)).collect();
+	// Fetch the outgoing links of each record
+	let mut links = Vec::with_capacity(len);
+	for rid in all.iter() {
+		let mut out = Vec::new();
+		for (_, n) in walk.adjacent(rid).await? {
+			if let Some(i) = idx.get(&n.to_raw()) {
+				out.push(*i);
+			}
+		}
+		links.push(out);
+	}
+	// Iterate until the ranks converge
+	let mut rank = vec![1.0 / len as f64; len];
+	for _ in 0..ITERATIONS {
+		// Check if the context is finished
+		ctx.check()?;
+		// Records without links share their rank with all records
+		let dangling: f64 = links
+			.iter()
+			.zip(rank.iter())
+			.filter(|(l, _)| l.is_empty())
+			.map(|(_, r)| r)
+			.sum::<f64>();
+		let base = (1.0 - DAMPING) / len as f64 + DAMPING * dangling / len as f64;
+		let mut next = vec![base; len];
+		for (i, l) in links.iter().enumerate() {
+			for j in l.iter() {
+				next[*j] += DAMPING * rank[i] / l.len() as f64;
+			}
+		}
+		let diff: f64 = next.iter().zip(rank.iter()).map(|(a, b)| (a - b).abs()).sum();
+		rank = next;
+		if diff < TOLERANCE {
+			break;
+		}
+	}
+	// Output the ranks in descending order
+	let mut out: Vec<(Thing, f64)> = all.into_iter().zip(rank).collect();
+	out.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+	Ok(out
+		.into_iter()
+		.map(|(id, rank)| {
+			let mut v = Object::default();
+			v.insert(String::from("id"), Value::from(id));
+			v.insert(String::from("rank"), Value::from(Number::from(rank)));
+			Value::from(v)
+		})
+		.collect::<Vec<_>>()
+		.into())
+}
+
+// Check the function permissions and arguments
+fn check(
+	opt: &Options,
+	name: &str,
+	args: Vec<Value>,
+	min: usize,
+	max: usize,
+) -> Result<Vec<Value>, Error> {
+	// Selected DB?
+	opt.needs(Level::Db)?;
+	// Allowed to run?
+	opt.check(Level::Db)?;
+	// Check the number of arguments
+	if args.len() < min || args.len() > max {
+		return Err(Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("The function expects {} to {} arguments.", min, max),
+		});
+	}
+	Ok(args)
+}
+
+// Parse a record argument
+fn record(name: &str, val: Value, pos: &str) -> Result<Thing, Error> {
+	match val {
+		Value::Thing(v) => Ok(v),
+		Value::Object(v) => match v.rid() {
+			Some(v) => Ok(v),
+			None => Err(Error::InvalidArguments {
+				name: name.to_owned(),
+				message: format!("The {} argument should be a record.", pos),
+			}),
+		},
+		_ => Err(Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("The {} argument should be a record.", pos),
+		}),
+	}
+}
+
+// Parse a set of records, or all of the records in a table
+async fn records(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	name: &str,
+	val: Value,
+) -> Result<Vec<Thing>, Error> {
+	let tb = match val {
+		Value::Array(v) => {
+			return v.into_iter().map(|v| record(name, v, "first")).collect();
+		}
+		Value::Strand(v) => v.0,
+		Value::Table(v) => v.0,
+		_ => {
+			return Err(Error::InvalidArguments {
+				name: name.to_owned(),
+				message: String::from(
+					"The first argument should be a table or an array of records.",
+				),
+			})
+		}
+	};
+	let beg = thing::prefix(opt.ns(), opt.db(), &tb);
+	let end = thing::suffix(opt.ns(), opt.db(), &tb);
+	let res = keys(ctx, txn, beg, end).await?;
+	Ok(res
+		.into_iter()
+		.map(|k| {
+			let key: thing::Thing = (&k).into();
+			Thing::from((key.tb, key.id))
+		})
+		.collect())
+}
+
+// Scan the keys in a range in batches
+async fn keys(ctx: &Context<'_>, txn: &Transaction, beg: Key, end: Key) -> Result<Vec<Key>, Error> {
+	let mut nxt: Option<Key> = None;
+	let mut out = Vec::new();
+	// Loop until no more keys
+	loop {
+		// Check if the context is finished
+		ctx.check()?;
+		// Get the next 1000 key-value entries
+		let res = match nxt {
+			None => {
+				let min = beg.clone();
+				let max = end.clone();
+				txn.clone().lock().await.scan(min..max, 1000).await?
+			}
+			Some(ref mut beg) => {
+				beg.push(0x00);
+				let min = beg.clone();
+				let max = end.clone();
+				txn.clone().lock().await.scan(min..max, 1000).await?
+			}
+		};
+		// Stop when there are no more entries
+		if res.is_empty() {
+			break;
+		}
+		// Keep only the keys of the entries
+		nxt = res.last().map(|(k, _)| k.clone());
+		out.extend(res.into_iter().map(|(k, _)| k));
+	}
+	Ok(out)
+}
+
+// Parse the optional edge tables and direction arguments
+fn walker<'a>(
+	ctx: &'a Context<'a>,
+	opt: &'a Options,
+	txn: &'a Transaction,
+	name: &str,
+	mut args: Vec<Value>,
+	dir: Dir,
+) -> Result<Walker<'a>, Error> {
+	let edges = match args.is_empty() {
+		true => vec![],
+		false => match args.remove(0) {
+			Value::None | Value::Null => vec![],
+			Value::Strand(v) => vec![v.0],
+			Value::Table(v) => vec![v.0],
+			Value::Array(v) => v.into_iter().map(|v| v.as_string()).collect(),
+			_ => {
+				return Err(Error::InvalidArguments {
+					name: name.to_owned(),
+					message: String::from("The edge tables should be a string or an array."),
+				})
+			}
+		},
+	};
+	let dir = match args.is_empty() {
+		true => dir,
+		false => match args.remove(0) {
+			Value::None | Value::Null => dir,
+			Value::Strand(v) => match v.to_lowercase().as_str() {
+				"in" => Dir::In,
+				"out" => Dir::Out,
+				"both" => Dir::Both,
+				_ => {
+					return Err(Error::InvalidArguments {
+						name: name.to_owned(),
+						message: String::from("The direction should be 'in', 'out', or 'both'."),
+					})
+				}
+			},
+			_ => {
+				return Err(Error::InvalidArguments {
+					name: name.to_owned(),
+					message: String::from("The direction should be 'in', 'out', or 'both'."),
+				})
+			}
+		},
+	};
+	Ok(Walker {
+		ctx,
+		opt,
+		txn,
+		edges,
+		dir,
+	})
+}
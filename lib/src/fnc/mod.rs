@@ -1,4 +1,6 @@
 use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::fnc::args::Args;
 use crate::sql::value::Value;
@@ -10,6 +12,7 @@ pub mod count;
 pub mod crypto;
 pub mod future;
 pub mod geo;
+pub mod graph;
 pub mod http;
 pub mod is;
 pub mod math;
@@ -17,6 +20,7 @@ pub mod operate;
 pub mod parse;
 pub mod rand;
 pub mod script;
+pub mod search;
 pub mod session;
 pub mod string;
 pub mod time;
@@ -24,12 +28,27 @@ pub mod r#type;
 pub mod util;
 
 // Attempts to run any function
-pub async fn run(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Value, Error> {
+pub async fn run(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	doc: Option<&Value>,
+	name: &str,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
 	match name {
+		v if v.starts_with("graph") => {
+			// Graph functions read from the transaction
+			transactional(ctx, opt, txn, name, args).await
+		}
 		v if v.starts_with("http") => {
 			// HTTP functions are asynchronous
 			asynchronous(ctx, name, args).await
 		}
+		v if v.starts_with("search") => {
+			// Search functions use the current document
+			contextual(ctx, doc, name, args)
+		}
 		_ => {
 			// Other functions are synchronous
 			synchronous(ctx, name, args)
@@ -45,6 +64,7 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"array::concat" => args::check(ctx, name, args, Args::Two, array::concat),
 		"array::difference" => args::check(ctx, name, args, Args::Two, array::difference),
 		"array::distinct" => args::check(ctx, name, args, Args::One, array::distinct),
+		"array::group" => args::check(ctx, name, args, Args::One, array::group),
 		"array::intersect" => args::check(ctx, name, args, Args::Two, array::intersect),
 		"array::len" => args::check(ctx, name, args, Args::One, array::len),
 		"array::sort" => args::check(ctx, name, args, Args::OneTwo, array::sort),
@@ -184,6 +204,47 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 	}
 }
 
+// Attempts to run a document function
+pub fn contextual(
+	ctx: &Context<'_>,
+	doc: Option<&Value>,
+	name: &str,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	// Add the current document to the context
+	let mut ctx = Context::new(ctx);
+	if let Some(doc) = doc {
+		ctx.add_value("document".into(), doc);
+	}
+	match name {
+		//
+		"search::highlight" => args::check(&ctx, name, args, Args::Three, search::highlight),
+		"search::score" => args::check(&ctx, name, args, Args::None, search::score),
+		//
+		_ => unreachable!(),
+	}
+}
+
+// Attempts to run a function which reads from the transaction
+pub async fn transactional(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	name: &str,
+	args: Vec<Value>,
+) -> Result<Value, Error> {
+	match name {
+		//
+		"graph::connected_components" => graph::connected_components(ctx, opt, txn, args).await,
+		"graph::degree" => graph::degree(ctx, opt, txn, args).await,
+		"graph::neighbours" => graph::neighbours(ctx, opt, txn, args).await,
+		"graph::pagerank" => graph::pagerank(ctx, opt, txn, args).await,
+		"graph::shortest_path" => graph::shortest_path(ctx, opt, txn, args).await,
+		//
+		_ => unreachable!(),
+	}
+}
+
 // Attempts to run an asynchronous function
 pub async fn asynchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Value, Error> {
 	match name {
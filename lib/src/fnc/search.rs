@@ -0,0 +1,65 @@
+use crate::ctx::Context;
+use crate::err::Error;
+use crate::idx;
+use crate::sql::index;
+use crate::sql::paths::ID;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+
+// Fetch the full-text search results for the current document
+fn results<'a>(ctx: &'a Context) -> Option<(&'a Value, Thing)> {
+	let rid = match ctx.value("document")?.pick(&*ID) {
+		Value::Thing(v) => v,
+		_ => return None,
+	};
+	match ctx.value("search")? {
+		Value::Object(v) => v.get(&rid.tb).map(|v| (v, rid)),
+		_ => None,
+	}
+}
+
+// Fetch a field of the full-text search results
+fn field<'a>(res: &'a Value, name: &str) -> Option<&'a Value> {
+	match res {
+		Value::Object(v) => v.get(name),
+		_ => None,
+	}
+}
+
+pub fn score(ctx: &Context, _: Vec<Value>) -> Result<Value, Error> {
+	let res = match results(ctx) {
+		Some(v) => v,
+		None => return Ok(Value::None),
+	};
+	match field(res.0, "scores") {
+		Some(Value::Object(v)) => Ok(v.get(&res.1.id.to_string()).cloned().unwrap_or(Value::None)),
+		_ => Ok(Value::None),
+	}
+}
+
+pub fn highlight(ctx: &Context, mut args: Vec<Value>) -> Result<Value, Error> {
+	let prefix = args.remove(0).as_string();
+	let suffix = args.remove(0).as_string();
+	let text = match args.remove(0) {
+		Value::Strand(v) => v,
+		v => return Ok(v),
+	};
+	// Fetch the search index analyzer and query terms
+	let res = match results(ctx) {
+		Some(v) => v,
+		None => return Ok(text.into()),
+	};
+	let sc = match field(res.0, "index") {
+		Some(Value::Strand(v)) => match index::search(v.as_str()) {
+			Ok((_, v)) => v,
+			Err(_) => return Ok(text.into()),
+		},
+		_ => return Ok(text.into()),
+	};
+	let terms: Vec<String> = match field(res.0, "terms") {
+		Some(Value::Array(v)) => v.iter().map(|v| v.clone().as_string()).collect(),
+		_ => vec![],
+	};
+	// Wrap the matching terms of the text
+	Ok(idx::search::highlight(&sc, &terms, &prefix, &suffix, text.as_str()).into())
+}
@@ -1,3 +1,4 @@
+use crate::fnc::util::math::variance::Variance;
 use crate::sql::number::Number;
 
 pub trait Deviation {
@@ -6,6 +7,6 @@ pub trait Deviation {
 
 impl Deviation for Vec<Number> {
 	fn deviation(self) -> Number {
-		todo!()
+		self.variance().sqrt()
 	}
 }
@@ -1,3 +1,4 @@
+use crate::fnc::util::math::mean::Mean;
 use crate::sql::number::Number;
 
 pub trait Variance {
@@ -6,6 +7,13 @@ pub trait Variance {
 
 impl Variance for Vec<Number> {
 	fn variance(self) -> Number {
-		todo!()
+		// The sample variance needs at least two values
+		if self.len() < 2 {
+			return Number::from(0);
+		}
+		let mean = self.mean();
+		let len = Number::from(self.len() - 1);
+		let sum = self.iter().map(|v| (v - &mean) * (v - &mean)).sum::<Number>();
+		sum / len
 	}
 }
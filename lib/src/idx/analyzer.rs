@@ -0,0 +1,164 @@
+use crate::idx::stemmer;
+use crate::sql::index::{Filter, Tokenizer};
+
+// A term which was found within a piece of text
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+	// The byte offset where the token starts
+	pub beg: usize,
+	// The byte offset where the token ends
+	pub end: usize,
+	// The analyzed term of the token
+	pub term: String,
+}
+
+// An Analyzer splits text into tokens using a set of
+// tokenizers, and then passes each token through a chain
+// of filters, to produce the terms which are indexed or
+// searched for. Tokens which do not contain any letters or
+// digits are always discarded.
+pub struct Analyzer<'a> {
+	tokenizers: &'a [Tokenizer],
+	filters: &'a [Filter],
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+	Letter,
+	Digit,
+	Other,
+}
+
+impl From<char> for Class {
+	fn from(c: char) -> Class {
+		match c {
+			c if c.is_alphabetic() => Class::Letter,
+			c if c.is_numeric() => Class::Digit,
+			_ => Class::Other,
+		}
+	}
+}
+
+impl<'a> Analyzer<'a> {
+	pub fn new(tokenizers: &'a [Tokenizer], filters: &'a [Filter]) -> Analyzer<'a> {
+		Analyzer {
+			tokenizers,
+			filters,
+		}
+	}
+	// Split a piece of text into analyzed tokens
+	pub fn analyze(&self, text: &str) -> Vec<Token> {
+		let mut out = Vec::new();
+		let mut beg: Option<usize> = None;
+		let mut prev: Option<char> = None;
+		for (i, c) in text.char_indices() {
+			// Check if this character separates tokens
+			if self.separator(c) {
+				if let Some(b) = beg.take() {
+					self.push(&mut out, text, b, i);
+				}
+				prev = None;
+				continue;
+			}
+			// Check if a new token starts at this character
+			if let (Some(b), Some(p)) = (beg, prev) {
+				if self.boundary(p, c) {
+					self.push(&mut out, text, b, i);
+					beg = None;
+				}
+			}
+			if beg.is_none() {
+				beg = Some(i);
+			}
+			prev = Some(c);
+		}
+		if let Some(b) = beg {
+			self.push(&mut out, text, b, text.len());
+		}
+		out
+	}
+	// Check if a character is discarded by a tokenizer
+	fn separator(&self, c: char) -> bool {
+		self.tokenizers.iter().any(|t| match t {
+			Tokenizer::Blank => c.is_whitespace(),
+			Tokenizer::Punct => !c.is_alphanumeric() && !c.is_whitespace(),
+			_ => false,
+		})
+	}
+	// Check if a tokenizer splits two adjacent characters
+	fn boundary(&self, p: char, c: char) -> bool {
+		self.tokenizers.iter().any(|t| match t {
+			Tokenizer::Camel => p.is_lowercase() && c.is_uppercase(),
+			Tokenizer::Class => Class::from(p) != Class::from(c),
+			_ => false,
+		})
+	}
+	// Filter a token and add it to the output
+	fn push(&self, out: &mut Vec<Token>, text: &str, beg: usize, end: usize) {
+		let mut term = text[beg..end].to_owned();
+		// Ignore tokens without any letters or digits
+		if !term.chars().any(char::is_alphanumeric) {
+			return;
+		}
+		// Pass the token through each filter
+		for f in self.filters {
+			term = match f {
+				Filter::Ascii => deunicode::deunicode(&term),
+				Filter::Lowercase => term.to_lowercase(),
+				Filter::Stem => stemmer::stem(&term),
+				Filter::Uppercase => term.to_uppercase(),
+			};
+		}
+		// Ignore tokens which were filtered away
+		if term.is_empty() {
+			return;
+		}
+		out.push(Token {
+			beg,
+			end,
+			term,
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	fn terms(tokenizers: &[Tokenizer], filters: &[Filter], text: &str) -> Vec<String> {
+		Analyzer::new(tokenizers, filters).analyze(text).into_iter().map(|t| t.term).collect()
+	}
+
+	#[test]
+	fn analyze_blank_class() {
+		let tks = [Tokenizer::Blank, Tokenizer::Class];
+		let out = terms(&tks, &[Filter::Lowercase], "Red shoes, size 42EU!");
+		assert_eq!(out, vec!["red", "shoes", "size", "42", "eu"]);
+	}
+
+	#[test]
+	fn analyze_camel_punct() {
+		let tks = [Tokenizer::Camel, Tokenizer::Punct];
+		let out = terms(&tks, &[], "getUserName/setUserName");
+		assert_eq!(out, vec!["get", "User", "Name", "set", "User", "Name"]);
+	}
+
+	#[test]
+	fn analyze_filters() {
+		let tks = [Tokenizer::Blank];
+		let fts = [Filter::Ascii, Filter::Lowercase, Filter::Stem];
+		let out = terms(&tks, &fts, "Crème Brûlée RUNNING connections");
+		assert_eq!(out, vec!["creme", "brule", "run", "connect"]);
+	}
+
+	#[test]
+	fn analyze_offsets() {
+		let tks = [Tokenizer::Blank, Tokenizer::Class];
+		let out = Analyzer::new(&tks, &[]).analyze("héllo, world");
+		assert_eq!(out[0].beg, 0);
+		assert_eq!(out[0].end, 6);
+		assert_eq!(out[1].beg, 8);
+		assert_eq!(out[1].end, 13);
+	}
+}
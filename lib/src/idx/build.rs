@@ -0,0 +1,367 @@
+use crate::cnf::INDEX_BUILD_BATCH_SIZE;
+use crate::cnf::MAX_TRANSACTION_RETRIES;
+use crate::cnf::TABLE_BUILD_BATCH_SIZE;
+use crate::ctx::Context;
+use crate::dbs::Auth;
+use crate::dbs::Options;
+use crate::dbs::Statement;
+use crate::dbs::Transaction;
+use crate::dbs::Workable;
+use crate::dbs::LOG;
+use crate::doc::Document;
+use crate::err::Error;
+use crate::key::ib;
+use crate::key::thing;
+use crate::key::vb;
+use crate::kvs::Datastore;
+use crate::kvs::Key;
+use crate::sql::statements::define::{DefineIndexStatement, DefineTableStatement};
+use crate::sql::statements::update::UpdateStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use derive::Store;
+use futures::lock::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+// An index is built by indexing the records of its table in
+// batches, with each batch committed in its own transaction.
+// The first batch is indexed when the index is defined, and
+// if more records remain, the progress of the build is stored
+// in the keyspace so that the remaining batches are indexed
+// in the background, resuming from the last indexed record
+// after a restart. Until the build is complete, the index is
+// kept up to date by writes, but is marked as building in its
+// definition, so that it is not used by the planner. Without
+// background builds, all of the batches are indexed when the
+// index is defined. A foreign table is built in the same way,
+// processing the records of each of its source tables in turn,
+// and writes to the records which the build has not yet reached
+// are left for the build to process.
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Store)]
+pub struct Build {
+	// The key of the last indexed record
+	pub pos: Option<Vec<u8>>,
+	// The number of indexed records
+	pub count: u64,
+	// The error which stopped the build
+	pub error: Option<String>,
+	// The source table which is being processed
+	#[serde(default)]
+	pub what: usize,
+}
+
+impl From<&Build> for Value {
+	fn from(v: &Build) -> Value {
+		let mut out = map! {
+			String::from("status") => match v.error {
+				Some(_) => "failed".into(),
+				None => "building".into(),
+			},
+			String::from("records") => v.count.into(),
+		};
+		if let Some(error) = &v.error {
+			out.insert(String::from("error"), error.as_str().into());
+		}
+		Value::from(out)
+	}
+}
+
+impl Build {
+	// Index the next batch of records, returning true once all records are indexed
+	pub(crate) async fn batch(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		ix: &DefineIndexStatement,
+	) -> Result<bool, Error> {
+		// Prepare the start and end keys
+		let beg = match &self.pos {
+			Some(v) => {
+				let mut v = v.clone();
+				v.push(0x00);
+				v
+			}
+			None => thing::prefix(opt.ns(), opt.db(), &ix.what),
+		};
+		let end = thing::suffix(opt.ns(), opt.db(), &ix.what);
+		// Get the next batch of key-value entries
+		let res = txn.clone().lock().await.scan(beg..end, INDEX_BUILD_BATCH_SIZE).await?;
+		// Check if this is the last batch
+		let done = res.len() < INDEX_BUILD_BATCH_SIZE as usize;
+		// Force the index entries to be written
+		let opt = &opt.force(true);
+		// Index the records as if they were updated
+		let stm = UpdateStatement::default();
+		let stm = Statement::from(&stm);
+		// Loop over results
+		for (k, v) in res.into_iter() {
+			// Parse the data from the store
+			let key: thing::Thing = (&k).into();
+			let val: Value = (&v).into();
+			let rid = Thing::from((key.tb, key.id));
+			// Index the record
+			let doc = Document::new(Some(rid), &val, Workable::Normal);
+			doc.index_one(ctx, opt, txn, &stm, ix).await?;
+			// Mark the record position
+			self.pos = Some(k);
+			self.count += 1;
+		}
+		// Carry on
+		Ok(done)
+	}
+	// Process the next batch of records of a foreign table, returning true once all records are processed
+	pub(crate) async fn table(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		tb: &DefineTableStatement,
+	) -> Result<bool, Error> {
+		// Check if table is a view
+		let view = match &tb.view {
+			Some(v) => v,
+			None => return Ok(true),
+		};
+		// Get the source table which is being processed
+		let ft = match view.what.0.get(self.what) {
+			Some(v) => v,
+			None => return Ok(true),
+		};
+		// Prepare the start and end keys
+		let beg = match &self.pos {
+			Some(v) => {
+				let mut v = v.clone();
+				v.push(0x00);
+				v
+			}
+			None => thing::prefix(opt.ns(), opt.db(), ft),
+		};
+		let end = thing::suffix(opt.ns(), opt.db(), ft);
+		// Get the next batch of key-value entries
+		let res = txn.clone().lock().await.scan(beg..end, TABLE_BUILD_BATCH_SIZE).await?;
+		// Check if this is the last batch of the source table
+		let last = res.len() < TABLE_BUILD_BATCH_SIZE as usize;
+		// Force the view data to be written
+		let opt = &opt.force(true);
+		// Don't process field queries
+		let opt = &opt.fields(false);
+		// Don't process event queries
+		let opt = &opt.events(false);
+		// Process the records as if they were updated
+		let stm = UpdateStatement::default();
+		let stm = Statement::from(&stm);
+		// Loop over results
+		for (k, v) in res.into_iter() {
+			// Parse the data from the store
+			let key: thing::Thing = (&k).into();
+			let val: Value = (&v).into();
+			let rid = Thing::from((key.tb, key.id));
+			// Process the view data
+			let doc = Document::new(Some(rid), &val, Workable::Normal);
+			doc.table_one(ctx, opt, txn, &stm, tb).await?;
+			doc.link_one(ctx, opt, txn, &stm, tb).await?;
+			// Mark the record position
+			self.pos = Some(k);
+			self.count += 1;
+		}
+		// Continue with the next source table
+		if last {
+			self.what += 1;
+			self.pos = None;
+		}
+		// Carry on
+		Ok(self.what >= view.what.0.len())
+	}
+}
+
+// An index or foreign table which is being built
+enum Job {
+	Index(ib::Ib),
+	Table(vb::Vb),
+}
+
+impl Job {
+	// The key of the build progress
+	fn key(&self) -> Key {
+		match self {
+			Job::Index(v) => v.clone().into(),
+			Job::Table(v) => v.clone().into(),
+		}
+	}
+}
+
+// Continue all of the index and foreign table builds which are in progress
+pub(crate) async fn resume(ds: &Datastore) -> Result<(), Error> {
+	// Fetch the index and foreign table builds
+	let mut txn = ds.transaction(false, false).await?;
+	let ibs = txn.scan(ib::prefix()..ib::suffix(), u32::MAX).await;
+	let vbs = txn.scan(vb::prefix()..vb::suffix(), u32::MAX).await;
+	txn.cancel().await?;
+	// Continue each of the index builds
+	for (k, v) in ibs? {
+		let key: ib::Ib = (&k).into();
+		let bld: Build = (&v).into();
+		// Skip builds which have failed
+		if bld.error.is_none() {
+			// A conflicting build is continued next time
+			if let Err(e) = build(ds, &Job::Index(key.clone())).await {
+				warn!(target: LOG, "Unable to continue the build of index {}: {}", key.ix, e);
+			}
+		}
+	}
+	// Continue each of the foreign table builds
+	for (k, v) in vbs? {
+		let key: vb::Vb = (&k).into();
+		let bld: Build = (&v).into();
+		// Skip builds which have failed
+		if bld.error.is_none() {
+			// A conflicting build is continued next time
+			if let Err(e) = build(ds, &Job::Table(key.clone())).await {
+				warn!(target: LOG, "Unable to continue the build of table {}: {}", key.tb, e);
+			}
+		}
+	}
+	// Carry on
+	Ok(())
+}
+
+// Process the remaining batches of records of a build
+async fn build(ds: &Datastore, job: &Job) -> Result<(), Error> {
+	// Get the namespace and database of the build
+	let (ns, db) = match job {
+		Job::Index(v) => (&v.ns, &v.db),
+		Job::Table(v) => (&v.ns, &v.db),
+	};
+	// Create a new query options
+	let mut opt = Options::new(Auth::Kv);
+	opt.ns = Some(Arc::new(ns.to_owned()));
+	opt.db = Some(Arc::new(db.to_owned()));
+	// Create a default context
+	let ctx = Context::default();
+	// Process each batch in a separate transaction
+	let mut retries = 0;
+	loop {
+		// Start a new write transaction
+		let txn = Arc::new(Mutex::new(ds.transaction(true, false).await?));
+		// Process the next batch of records
+		let res = match job {
+			Job::Index(key) => batch(&ctx, &opt, &txn, key).await,
+			Job::Table(key) => batch_table(&ctx, &opt, &txn, key).await,
+		};
+		// Store the build progress
+		let res = match res {
+			Ok(true) => txn.lock().await.commit().await.map(|_| true),
+			Ok(false) => txn.lock().await.commit().await.map(|_| false),
+			Err(e) => {
+				txn.lock().await.cancel().await?;
+				Err(e)
+			}
+		};
+		match res {
+			// The build is complete
+			Ok(true) => return Ok(()),
+			// More records remain to be processed
+			Ok(false) => retries = 0,
+			// Retry the batch on a transaction conflict
+			Err(Error::TxConflict) if retries < MAX_TRANSACTION_RETRIES => retries += 1,
+			// Another build or query conflicted with this batch
+			Err(Error::TxConflict) => return Err(Error::TxConflict),
+			// The records could not be processed
+			Err(e) => return fail(ds, job, e).await,
+		}
+	}
+}
+
+// Index a single batch of records, returning true once the build is complete
+async fn batch(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	key: &ib::Ib,
+) -> Result<bool, Error> {
+	// Fetch the build progress
+	let mut bld: Build = match txn.lock().await.get(key.clone()).await? {
+		Some(v) => (&v).into(),
+		// The build was completed or removed
+		None => return Ok(true),
+	};
+	// Fetch the index definition
+	let ix = crate::key::ix::new(&key.ns, &key.db, &key.tb, &key.ix);
+	let ix: DefineIndexStatement = match txn.lock().await.get(ix).await? {
+		Some(v) => (&v).into(),
+		// The index was removed with its table
+		None => {
+			txn.lock().await.del(key.clone()).await?;
+			return Ok(true);
+		}
+	};
+	// Index the next batch of records
+	let done = bld.batch(ctx, opt, txn, &ix).await?;
+	// Store the build progress
+	match done {
+		true => txn.lock().await.del(key.clone()).await?,
+		false => txn.lock().await.set(key.clone(), bld).await?,
+	};
+	// The index can be used once it is built
+	if done {
+		let ix = DefineIndexStatement {
+			building: false,
+			..ix
+		};
+		let key = crate::key::ix::new(&key.ns, &key.db, &key.tb, &key.ix);
+		txn.lock().await.set(key, ix).await?;
+	}
+	// Carry on
+	Ok(done)
+}
+
+// Process a single batch of records of a foreign table, returning true once the build is complete
+async fn batch_table(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	key: &vb::Vb,
+) -> Result<bool, Error> {
+	// Fetch the build progress
+	let mut bld: Build = match txn.lock().await.get(key.clone()).await? {
+		Some(v) => (&v).into(),
+		// The build was completed or removed
+		None => return Ok(true),
+	};
+	// Fetch the table definition
+	let tb = crate::key::tb::new(&key.ns, &key.db, &key.tb);
+	let tb: DefineTableStatement = match txn.lock().await.get(tb).await? {
+		Some(v) => (&v).into(),
+		// The table was removed
+		None => {
+			txn.lock().await.del(key.clone()).await?;
+			return Ok(true);
+		}
+	};
+	// Process the next batch of records
+	let done = bld.table(ctx, opt, txn, &tb).await?;
+	// Store the build progress
+	match done {
+		true => txn.lock().await.del(key.clone()).await?,
+		false => txn.lock().await.set(key.clone(), bld).await?,
+	};
+	// Carry on
+	Ok(done)
+}
+
+// Record the error which stopped a build
+async fn fail(ds: &Datastore, job: &Job, err: Error) -> Result<(), Error> {
+	// Start a new write transaction
+	let mut txn = ds.transaction(true, false).await?;
+	// Store the build error
+	if let Some(v) = txn.get(job.key()).await? {
+		let mut bld: Build = (&v).into();
+		bld.error = Some(err.to_string());
+		txn.set(job.key(), bld).await?;
+	}
+	// Commit the build error
+	txn.commit().await
+}
@@ -0,0 +1,5 @@
+pub mod analyzer;
+pub mod build;
+pub mod search;
+pub mod spatial;
+mod stemmer;
@@ -0,0 +1,224 @@
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::idx::analyzer::Analyzer;
+use crate::kvs;
+use crate::sql::array::Array;
+use crate::sql::id::Id;
+use crate::sql::index::Search;
+use crate::sql::statements::define::DefineIndexStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use derive::Store;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// A full-text search index stores a posting entry for each
+// term within each document, holding the number of times the
+// term appears within the document. Alongside the postings,
+// the index stores the number of terms within each document,
+// and the total number of documents and terms in the index,
+// which are used to rank the matching documents using BM25.
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Store)]
+struct Stats {
+	// The number of indexed documents
+	docs: u64,
+	// The total number of terms in all indexed documents
+	terms: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Store)]
+struct Count(u64);
+
+// The term frequencies and inverse document frequencies of each matching document
+type Hits = BTreeMap<String, (Id, Vec<(f64, f64)>)>;
+
+// Update the search index entries for a record
+pub async fn index(
+	run: &mut kvs::Transaction,
+	opt: &Options,
+	ix: &DefineIndexStatement,
+	sc: &Search,
+	rid: &Thing,
+	old: &Array,
+	new: Option<&Array>,
+) -> Result<(), Error> {
+	// Prepare the index analyzer
+	let az = Analyzer::new(&sc.tokenizers, &sc.filters);
+	// Fetch the index statistics
+	let key = crate::key::bs::new(opt.ns(), opt.db(), &ix.what, &ix.name);
+	let mut stats: Stats = match run.get(key.clone()).await? {
+		Some(v) => v.into(),
+		None => Stats::default(),
+	};
+	// Remove the previously indexed terms
+	let bl = crate::key::bl::new(opt.ns(), opt.db(), &ix.what, &ix.name, &rid.id);
+	if let Some(len) = run.get(bl.clone()).await? {
+		let len: Count = len.into();
+		let (tfs, _) = terms(&az, old);
+		for tk in tfs.keys() {
+			let key = crate::key::bt::new(opt.ns(), opt.db(), &ix.what, &ix.name, tk, &rid.id);
+			run.del(key).await?;
+		}
+		run.del(bl.clone()).await?;
+		stats.docs = stats.docs.saturating_sub(1);
+		stats.terms = stats.terms.saturating_sub(len.0);
+	}
+	// Add the currently indexed terms
+	if let Some(new) = new {
+		let (tfs, len) = terms(&az, new);
+		if len > 0 {
+			for (tk, tf) in tfs {
+				let key = crate::key::bt::new(opt.ns(), opt.db(), &ix.what, &ix.name, &tk, &rid.id);
+				run.set(key, Count(tf)).await?;
+			}
+			run.set(bl, Count(len)).await?;
+			stats.docs += 1;
+			stats.terms += len;
+		}
+	}
+	// Store the index statistics
+	run.set(key, stats).await?;
+	// Carry on
+	Ok(())
+}
+
+// Find the records which contain every term of a query, ranked by relevance
+pub async fn search(
+	run: &mut kvs::Transaction,
+	opt: &Options,
+	ix: &DefineIndexStatement,
+	sc: &Search,
+	query: &str,
+) -> Result<Vec<(Thing, f64)>, Error> {
+	// Analyze the query terms
+	let tks = self::query(sc, query);
+	if tks.is_empty() {
+		return Ok(vec![]);
+	}
+	// Fetch the index statistics
+	let key = crate::key::bs::new(opt.ns(), opt.db(), &ix.what, &ix.name);
+	let stats: Stats = match run.get(key).await? {
+		Some(v) => v.into(),
+		None => return Ok(vec![]),
+	};
+	if stats.docs == 0 {
+		return Ok(vec![]);
+	}
+	let docs = stats.docs as f64;
+	// Find the documents which contain every term
+	let mut hits: Option<Hits> = None;
+	for tk in tks.iter() {
+		let beg = crate::key::bt::prefix(opt.ns(), opt.db(), &ix.what, &ix.name, tk);
+		let end = crate::key::bt::suffix(opt.ns(), opt.db(), &ix.what, &ix.name, tk);
+		let res = run.getr(beg..end, u32::MAX).await?;
+		// Calculate the inverse document frequency of the term
+		let df = res.len() as f64;
+		let idf = ((docs - df + 0.5) / (df + 0.5) + 1.0).ln();
+		// Collect the term frequency within each document
+		let mut next = BTreeMap::new();
+		for (k, v) in res {
+			let id = crate::key::bt::Bt::from(k).id;
+			let tf = Count::from(v).0 as f64;
+			let key = id.to_string();
+			match hits {
+				Some(ref mut hits) => {
+					if let Some((id, mut tfs)) = hits.remove(&key) {
+						tfs.push((idf, tf));
+						next.insert(key, (id, tfs));
+					}
+				}
+				None => {
+					next.insert(key, (id, vec![(idf, tf)]));
+				}
+			}
+		}
+		// Stop when no documents contain all terms
+		if next.is_empty() {
+			return Ok(vec![]);
+		}
+		hits = Some(next);
+	}
+	// Rank the matching documents using BM25
+	let k1 = sc.k1.clone().as_float();
+	let b = sc.b.clone().as_float();
+	let avg = stats.terms as f64 / docs;
+	let mut out = Vec::new();
+	for (_, (id, tfs)) in hits.unwrap_or_default() {
+		let key = crate::key::bl::new(opt.ns(), opt.db(), &ix.what, &ix.name, &id);
+		let len = match run.get(key).await? {
+			Some(v) => Count::from(v).0 as f64,
+			None => avg,
+		};
+		let score = tfs
+			.iter()
+			.map(|(idf, tf)| idf * (tf * (k1 + 1.0)) / (tf + k1 * (1.0 - b + b * len / avg)))
+			.sum::<f64>();
+		out.push((Thing::from((ix.what.to_string(), id)), score));
+	}
+	// Output the most relevant documents first
+	out.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+	Ok(out)
+}
+
+// Analyze the distinct terms of a query
+pub fn query(sc: &Search, query: &str) -> Vec<String> {
+	let az = Analyzer::new(&sc.tokenizers, &sc.filters);
+	let mut tks: Vec<String> = az.analyze(query).into_iter().map(|t| t.term).collect();
+	tks.sort();
+	tks.dedup();
+	tks
+}
+
+// Check if a value contains every term of a query
+pub fn matches(sc: &Search, val: &Value, query: &str) -> bool {
+	let tks = self::query(sc, query);
+	if tks.is_empty() {
+		return false;
+	}
+	let az = Analyzer::new(&sc.tokenizers, &sc.filters);
+	let (tfs, _) = terms(&az, &Array::from(vec![val.clone()]));
+	tks.iter().all(|tk| tfs.contains_key(tk))
+}
+
+// Wrap each term of a value which matches a query term
+pub fn highlight(sc: &Search, terms: &[String], prefix: &str, suffix: &str, text: &str) -> String {
+	let az = Analyzer::new(&sc.tokenizers, &sc.filters);
+	let mut out = String::with_capacity(text.len());
+	let mut pos = 0;
+	for tk in az.analyze(text) {
+		if tk.beg >= pos && terms.contains(&tk.term) {
+			out.push_str(&text[pos..tk.beg]);
+			out.push_str(prefix);
+			out.push_str(&text[tk.beg..tk.end]);
+			out.push_str(suffix);
+			pos = tk.end;
+		}
+	}
+	out.push_str(&text[pos..]);
+	out
+}
+
+// Analyze the text of a set of values into terms
+fn terms(az: &Analyzer, vals: &Array) -> (HashMap<String, u64>, u64) {
+	let mut tfs = HashMap::new();
+	let mut len = 0;
+	for v in vals.iter() {
+		text(v, &mut |s| {
+			for tk in az.analyze(s) {
+				*tfs.entry(tk.term).or_insert(0) += 1;
+				len += 1;
+			}
+		});
+	}
+	(tfs, len)
+}
+
+// Pass each piece of text within a value to a function
+fn text(v: &Value, f: &mut impl FnMut(&str)) {
+	match v {
+		Value::Strand(v) => f(v.as_str()),
+		Value::Array(v) => v.iter().for_each(|v| text(v, f)),
+		_ => (),
+	}
+}
@@ -0,0 +1,249 @@
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::kvs;
+use crate::sql::array::Array;
+use crate::sql::statements::define::DefineIndexStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use geo::algorithm::bounding_rect::BoundingRect;
+use geo::algorithm::intersects::Intersects;
+use geo::{coord, Geometry, Point, Rect};
+use std::collections::BTreeSet;
+
+// A geospatial index stores an entry for each geohash cell
+// which a geometry intersects with. Each geometry is covered
+// by the cells of the finest geohash precision at which the
+// geometry spans no more than MAX_CELLS cells, so points are
+// stored in a single cell at the finest precision, and large
+// polygons are stored in a few coarse cells. A query geometry
+// is covered in the same way, and matches the entries within
+// each of its cells, and the entries of any larger cells which
+// contain them. The matching records are a superset of the
+// records which satisfy the query, and are checked afterwards.
+
+// The maximum number of cells which cover a geometry
+const MAX_CELLS: u64 = 32;
+
+// The finest geohash precision which is indexed
+const MAX_PRECISION: usize = 12;
+
+// The mean radius of the earth in metres
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+// The characters which are used to encode a geohash
+const BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+// Update the geospatial index entries for a record
+pub async fn index(
+	run: &mut kvs::Transaction,
+	opt: &Options,
+	ix: &DefineIndexStatement,
+	rid: &Thing,
+	old: &Array,
+	new: Option<&Array>,
+) -> Result<(), Error> {
+	// Remove the previously indexed cells
+	for cell in cells(old) {
+		let key = crate::key::sp::new(opt.ns(), opt.db(), &ix.what, &ix.name, &cell, &rid.id);
+		run.del(key).await?;
+	}
+	// Add the currently indexed cells
+	if let Some(new) = new {
+		for cell in cells(new) {
+			let key = crate::key::sp::new(opt.ns(), opt.db(), &ix.what, &ix.name, &cell, &rid.id);
+			run.set(key, rid).await?;
+		}
+	}
+	// Carry on
+	Ok(())
+}
+
+// Calculate the index entries of a record
+pub fn keys(opt: &Options, ix: &DefineIndexStatement, rid: &Thing, vals: &Array) -> Vec<Vec<u8>> {
+	cells(vals)
+		.iter()
+		.map(|cell| {
+			crate::key::sp::new(opt.ns(), opt.db(), &ix.what, &ix.name, cell, &rid.id).into()
+		})
+		.collect()
+}
+
+// Calculate the index key ranges which may contain geometries intersecting a query geometry
+pub fn ranges(
+	opt: &Options,
+	ix: &DefineIndexStatement,
+	g: &Geometry<f64>,
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+	let cover = cover(g);
+	let mut out = Vec::new();
+	let mut seen = BTreeSet::new();
+	for cell in cover.iter() {
+		// Scan the cell, and the smaller cells within it
+		out.push(crate::key::sp::range(opt.ns(), opt.db(), &ix.what, &ix.name, cell));
+		// Scan the larger cells which contain the cell
+		for len in 1..cell.len() {
+			let cell = &cell[..len];
+			if seen.insert(cell) {
+				out.push((
+					crate::key::sp::prefix(opt.ns(), opt.db(), &ix.what, &ix.name, cell),
+					crate::key::sp::suffix(opt.ns(), opt.db(), &ix.what, &ix.name, cell),
+				));
+			}
+		}
+	}
+	out
+}
+
+// Calculate a bounding box which contains every point within a distance of a point
+pub fn radius(p: Point<f64>, distance: f64) -> Geometry<f64> {
+	let lat = (distance.max(0.0) / EARTH_RADIUS).to_degrees();
+	let min_y = (p.y() - lat).max(-90.0);
+	let max_y = (p.y() + lat).min(90.0);
+	// The box widens towards the poles
+	let max = min_y.abs().max(max_y.abs());
+	let lon = match max < 90.0 {
+		true => lat / max.to_radians().cos(),
+		false => 180.0,
+	};
+	// Boxes which cross the antimeridian span all longitudes
+	let (min_x, max_x) = match (p.x() - lon, p.x() + lon) {
+		(min, max) if min >= -180.0 && max <= 180.0 => (min, max),
+		_ => (-180.0, 180.0),
+	};
+	Rect::new(coord! { x: min_x, y: min_y }, coord! { x: max_x, y: max_y }).into()
+}
+
+// Calculate the distinct cells which cover the geometries of a set of values
+fn cells(vals: &Array) -> BTreeSet<String> {
+	let mut out = BTreeSet::new();
+	for v in vals.iter() {
+		geometries(v, &mut |g| out.extend(cover(&g)));
+	}
+	out
+}
+
+// Pass each geometry within a value to a function
+fn geometries(v: &Value, f: &mut impl FnMut(Geometry<f64>)) {
+	match v {
+		Value::Geometry(v) => f(v.clone().into()),
+		Value::Array(v) => v.iter().for_each(|v| geometries(v, f)),
+		_ => (),
+	}
+}
+
+// Calculate the geohash cells which cover a geometry
+fn cover(g: &Geometry<f64>) -> Vec<String> {
+	let rect = match g.bounding_rect() {
+		Some(v) => v,
+		None => return vec![],
+	};
+	// Find the finest precision with few enough cells
+	let (len, xs, ys) = (1..=MAX_PRECISION)
+		.rev()
+		.map(|len| {
+			let (w, h) = size(len);
+			let xs = span(rect.min().x + 180.0, rect.max().x + 180.0, w, 360.0);
+			let ys = span(rect.min().y + 90.0, rect.max().y + 90.0, h, 180.0);
+			(len, xs, ys)
+		})
+		.find(|(_, xs, ys)| (xs.1 - xs.0 + 1) * (ys.1 - ys.0 + 1) <= MAX_CELLS)
+		.unwrap_or((1, (0, 7), (0, 3)));
+	// Keep the cells which intersect the geometry
+	let (w, h) = size(len);
+	let mut out = Vec::new();
+	for x in xs.0..=xs.1 {
+		for y in ys.0..=ys.1 {
+			let min = coord! { x: x as f64 * w - 180.0, y: y as f64 * h - 90.0 };
+			let max = coord! { x: min.x + w, y: min.y + h };
+			if g.intersects(&Rect::new(min, max).to_polygon()) {
+				out.push(hash(x, y, len));
+			}
+		}
+	}
+	out
+}
+
+// Calculate the width and height of the cells at a precision
+fn size(len: usize) -> (f64, f64) {
+	let bits = len as i32 * 5;
+	(360.0 / 2f64.powi((bits + 1) / 2), 180.0 / 2f64.powi(bits / 2))
+}
+
+// Calculate the range of cells which touch an interval, including
+// the preceding cell when the interval starts on a cell boundary
+fn span(min: f64, max: f64, size: f64, extent: f64) -> (u64, u64) {
+	let last = (extent / size).round() as u64 - 1;
+	let beg = ((min / size).ceil() as u64).saturating_sub(1).min(last);
+	let end = ((max / size).floor() as u64).min(last).max(beg);
+	(beg, end)
+}
+
+// Encode the geohash of a cell, by interleaving the bits of the cell
+// longitude and latitude offsets, starting with the longitude
+fn hash(x: u64, y: u64, len: usize) -> String {
+	let bits = len * 5;
+	let (xbits, ybits) = ((bits + 1) / 2, bits / 2);
+	let mut out = String::with_capacity(len);
+	let mut chr = 0;
+	for i in 0..bits {
+		let bit = match i % 2 {
+			0 => (x >> (xbits - 1 - i / 2)) & 1,
+			_ => (y >> (ybits - 1 - i / 2)) & 1,
+		};
+		chr = (chr << 1) | bit as usize;
+		if i % 5 == 4 {
+			out.push(BASE32[chr] as char);
+			chr = 0;
+		}
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use geo::{line_string, point, polygon};
+
+	#[test]
+	fn cover_point() {
+		let g = Geometry::from(point! { x: -0.1278, y: 51.5074 });
+		let out = cover(&g);
+		let val = crate::fnc::util::geo::encode(point! { x: -0.1278, y: 51.5074 }, 12);
+		assert_eq!(out, vec![val.as_string()]);
+	}
+
+	#[test]
+	fn cover_polygon() {
+		let g = Geometry::from(polygon![
+			(x: -0.15, y: 51.49),
+			(x: -0.10, y: 51.49),
+			(x: -0.10, y: 51.52),
+			(x: -0.15, y: 51.52),
+		]);
+		let out = cover(&g);
+		assert!(!out.is_empty() && out.len() as u64 <= MAX_CELLS);
+		assert!(out.iter().all(|c| c.len() == out[0].len()));
+		// The point within the polygon is within one of the cells
+		let p = cover(&Geometry::from(point! { x: -0.1278, y: 51.5074 })).remove(0);
+		assert!(out.iter().any(|c| p.starts_with(c.as_str())));
+	}
+
+	#[test]
+	fn cover_line() {
+		let g = Geometry::from(line_string![(x: -10.0, y: 0.0), (x: 10.0, y: 0.0)]);
+		let out = cover(&g);
+		assert!(!out.is_empty() && out.len() as u64 <= MAX_CELLS);
+	}
+
+	#[test]
+	fn radius_contains_distance() {
+		let p = point! { x: 13.4050, y: 52.5200 };
+		let out = radius(p, 10_000.0).bounding_rect().unwrap();
+		assert!(out.min().y < 52.44 && out.max().y > 52.60);
+		assert!(out.min().x < 13.26 && out.max().x > 13.55);
+		let out = radius(point! { x: 179.9, y: 0.0 }, 50_000.0).bounding_rect().unwrap();
+		assert_eq!(out.min().x, -180.0);
+		assert_eq!(out.max().x, 180.0);
+	}
+}
@@ -0,0 +1,299 @@
+// An implementation of the Porter stemming algorithm, which
+// reduces english words to their word stem, so that words
+// like 'connect', 'connected', and 'connection' all result
+// in the same index term. Words which are not made up only
+// of lowercase ascii letters are returned unchanged.
+// See https://tartarus.org/martin/PorterStemmer/def.txt
+
+pub fn stem(word: &str) -> String {
+	// Short words are not stemmed
+	if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+		return word.to_owned();
+	}
+	// Process the word
+	let mut s = Stemmer {
+		b: word.as_bytes().to_vec(),
+		k: word.len() as isize - 1,
+		j: 0,
+	};
+	s.step1ab();
+	if s.k > 0 {
+		s.step1c();
+		s.step2();
+		s.step3();
+		s.step4();
+		s.step5();
+	}
+	// Output the word stem
+	s.b.truncate(s.k as usize + 1);
+	String::from_utf8(s.b).unwrap()
+}
+
+struct Stemmer {
+	// The word which is being stemmed
+	b: Vec<u8>,
+	// The offset of the end of the word
+	k: isize,
+	// The offset of the end of the stem
+	j: isize,
+}
+
+impl Stemmer {
+	// Check if the character at the offset is a consonant
+	fn cons(&self, i: isize) -> bool {
+		match self.b[i as usize] {
+			b'a' | b'e' | b'i' | b'o' | b'u' => false,
+			b'y' => i == 0 || !self.cons(i - 1),
+			_ => true,
+		}
+	}
+	// Measure the number of vowel-consonant sequences in the stem
+	fn m(&self) -> usize {
+		let mut n = 0;
+		let mut i = 0;
+		loop {
+			if i > self.j {
+				return n;
+			}
+			if !self.cons(i) {
+				break;
+			}
+			i += 1;
+		}
+		i += 1;
+		loop {
+			loop {
+				if i > self.j {
+					return n;
+				}
+				if self.cons(i) {
+					break;
+				}
+				i += 1;
+			}
+			i += 1;
+			n += 1;
+			loop {
+				if i > self.j {
+					return n;
+				}
+				if !self.cons(i) {
+					break;
+				}
+				i += 1;
+			}
+			i += 1;
+		}
+	}
+	// Check if the stem contains a vowel
+	fn vowel_in_stem(&self) -> bool {
+		(0..=self.j).any(|i| !self.cons(i))
+	}
+	// Check if the offset ends with a double consonant
+	fn double_cons(&self, j: isize) -> bool {
+		j >= 1 && self.b[j as usize] == self.b[j as usize - 1] && self.cons(j)
+	}
+	// Check if the offset ends with consonant-vowel-consonant,
+	// where the last consonant is not 'w', 'x', or 'y'
+	fn cvc(&self, i: isize) -> bool {
+		if i < 2 || !self.cons(i) || self.cons(i - 1) || !self.cons(i - 2) {
+			return false;
+		}
+		!matches!(self.b[i as usize], b'w' | b'x' | b'y')
+	}
+	// Check if the word ends with the suffix, and mark the stem
+	fn ends(&mut self, s: &str) -> bool {
+		let l = s.len() as isize;
+		if l > self.k + 1 {
+			return false;
+		}
+		if &self.b[(self.k + 1 - l) as usize..=self.k as usize] != s.as_bytes() {
+			return false;
+		}
+		self.j = self.k - l;
+		true
+	}
+	// Replace the characters after the stem with the suffix
+	fn set_to(&mut self, s: &str) {
+		let j = (self.j + 1) as usize;
+		self.b.truncate(j);
+		self.b.extend_from_slice(s.as_bytes());
+		self.k = self.j + s.len() as isize;
+	}
+	// Replace the suffix if the stem is long enough
+	fn r(&mut self, s: &str) {
+		if self.m() > 0 {
+			self.set_to(s);
+		}
+	}
+	// Process plurals and -ed or -ing suffixes
+	fn step1ab(&mut self) {
+		if self.b[self.k as usize] == b's' {
+			if self.ends("sses") {
+				self.k -= 2;
+			} else if self.ends("ies") {
+				self.set_to("i");
+			} else if self.b[self.k as usize - 1] != b's' {
+				self.k -= 1;
+			}
+		}
+		if self.ends("eed") {
+			if self.m() > 0 {
+				self.k -= 1;
+			}
+		} else if (self.ends("ed") || self.ends("ing")) && self.vowel_in_stem() {
+			self.k = self.j;
+			if self.ends("at") {
+				self.set_to("ate");
+			} else if self.ends("bl") {
+				self.set_to("ble");
+			} else if self.ends("iz") {
+				self.set_to("ize");
+			} else if self.double_cons(self.k) {
+				self.k -= 1;
+				if matches!(self.b[self.k as usize], b'l' | b's' | b'z') {
+					self.k += 1;
+				}
+			} else if self.m() == 1 && self.cvc(self.k) {
+				self.set_to("e");
+			}
+		}
+		self.b.truncate(self.k as usize + 1);
+	}
+	// Turn a terminal 'y' into 'i' when there is another vowel in the stem
+	fn step1c(&mut self) {
+		if self.ends("y") && self.vowel_in_stem() {
+			self.b[self.k as usize] = b'i';
+		}
+	}
+	// Map double suffixes to single ones
+	fn step2(&mut self) {
+		let rules: &[(&str, &str)] = match self.b[self.k as usize - 1] {
+			b'a' => &[("ational", "ate"), ("tional", "tion")],
+			b'c' => &[("enci", "ence"), ("anci", "ance")],
+			b'e' => &[("izer", "ize")],
+			b'l' => {
+				&[("bli", "ble"), ("alli", "al"), ("entli", "ent"), ("eli", "e"), ("ousli", "ous")]
+			}
+			b'o' => &[("ization", "ize"), ("ation", "ate"), ("ator", "ate")],
+			b's' => &[("alism", "al"), ("iveness", "ive"), ("fulness", "ful"), ("ousness", "ous")],
+			b't' => &[("aliti", "al"), ("iviti", "ive"), ("biliti", "ble")],
+			b'g' => &[("logi", "log")],
+			_ => &[],
+		};
+		for (suffix, replace) in rules {
+			if self.ends(suffix) {
+				self.r(replace);
+				break;
+			}
+		}
+	}
+	// Process -ic-, -full, -ness etc. suffixes
+	fn step3(&mut self) {
+		let rules: &[(&str, &str)] = match self.b[self.k as usize] {
+			b'e' => &[("icate", "ic"), ("ative", ""), ("alize", "al")],
+			b'i' => &[("iciti", "ic")],
+			b'l' => &[("ical", "ic"), ("ful", "")],
+			b's' => &[("ness", "")],
+			_ => &[],
+		};
+		for (suffix, replace) in rules {
+			if self.ends(suffix) {
+				self.r(replace);
+				break;
+			}
+		}
+	}
+	// Remove -ant, -ence etc. suffixes from longer stems
+	fn step4(&mut self) {
+		let suffixes: &[&str] = match self.b[self.k as usize - 1] {
+			b'a' => &["al"],
+			b'c' => &["ance", "ence"],
+			b'e' => &["er"],
+			b'i' => &["ic"],
+			b'l' => &["able", "ible"],
+			b'n' => &["ant", "ement", "ment", "ent"],
+			b'o' => &["ion", "ou"],
+			b's' => &["ism"],
+			b't' => &["ate", "iti"],
+			b'u' => &["ous"],
+			b'v' => &["ive"],
+			b'z' => &["ize"],
+			_ => return,
+		};
+		let mut found = false;
+		for suffix in suffixes {
+			if self.ends(suffix) {
+				// The -ion suffix must follow an 's' or 't'
+				if *suffix == "ion"
+					&& !(self.j >= 0 && matches!(self.b[self.j as usize], b's' | b't'))
+				{
+					continue;
+				}
+				found = true;
+				break;
+			}
+		}
+		if found && self.m() > 1 {
+			self.k = self.j;
+		}
+	}
+	// Remove a final -e and change -ll to -l on longer stems
+	fn step5(&mut self) {
+		self.j = self.k;
+		if self.b[self.k as usize] == b'e' {
+			let a = self.m();
+			if a > 1 || a == 1 && !self.cvc(self.k - 1) {
+				self.k -= 1;
+			}
+		}
+		if self.b[self.k as usize] == b'l' && self.double_cons(self.k) && self.m() > 1 {
+			self.k -= 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn stem_words() {
+		let words = [
+			("caresses", "caress"),
+			("ponies", "poni"),
+			("cats", "cat"),
+			("feed", "feed"),
+			("agreed", "agre"),
+			("plastered", "plaster"),
+			("motoring", "motor"),
+			("sing", "sing"),
+			("hopping", "hop"),
+			("filing", "file"),
+			("happy", "happi"),
+			("relational", "relat"),
+			("conditional", "condit"),
+			("generalization", "gener"),
+			("hopeful", "hope"),
+			("goodness", "good"),
+			("adjustment", "adjust"),
+			("adoption", "adopt"),
+			("controlling", "control"),
+			("running", "run"),
+			("shoes", "shoe"),
+			("connection", "connect"),
+			("connected", "connect"),
+		];
+		for (word, stem) in words {
+			assert_eq!(super::stem(word), stem, "stemming '{}'", word);
+		}
+	}
+
+	#[test]
+	fn stem_skips_other_words() {
+		assert_eq!(stem("is"), "is");
+		assert_eq!(stem("Running"), "Running");
+		assert_eq!(stem("café"), "café");
+	}
+}
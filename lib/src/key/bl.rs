@@ -0,0 +1,63 @@
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Bl {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub ix: String,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+	pub id: Id,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, ix: &str, id: &Id) -> Bl {
+	Bl::new(ns.to_string(), db.to_string(), tb.to_string(), ix.to_string(), id.to_owned())
+}
+
+impl Bl {
+	pub fn new(ns: String, db: String, tb: String, ix: String, id: Id) -> Bl {
+		Bl {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0xa4, // ¤
+			ix,
+			_e: 0x21, // !
+			_f: 0x62, // b
+			_g: 0x6c, // l
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Bl::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+		);
+		let enc = Bl::encode(&val).unwrap();
+		let dec = Bl::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,132 @@
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub ix: String,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+	pub tk: String,
+}
+
+impl Prefix {
+	fn new(ns: &str, db: &str, tb: &str, ix: &str, tk: &str) -> Prefix {
+		Prefix {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0xa4, // ¤
+			ix: ix.to_string(),
+			_e: 0x21, // !
+			_f: 0x62, // b
+			_g: 0x74, // t
+			tk: tk.to_string(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Bt {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub ix: String,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+	pub tk: String,
+	pub id: Id,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, ix: &str, tk: &str, id: &Id) -> Bt {
+	Bt::new(
+		ns.to_string(),
+		db.to_string(),
+		tb.to_string(),
+		ix.to_string(),
+		tk.to_string(),
+		id.to_owned(),
+	)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str, ix: &str, tk: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, ix, tk).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str, ix: &str, tk: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, ix, tk).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl Bt {
+	pub fn new(ns: String, db: String, tb: String, ix: String, tk: String, id: Id) -> Bt {
+		Bt {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0xa4, // ¤
+			ix,
+			_e: 0x21, // !
+			_f: 0x62, // b
+			_g: 0x74, // t
+			tk,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Bt::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+		);
+		let enc = Bt::encode(&val).unwrap();
+		let dec = Bt::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn prefix() {
+		use super::*;
+		let val = new("test", "test", "test", "test", "test", &"test".into()).encode().unwrap();
+		assert!(super::prefix("test", "test", "test", "test", "test") < val);
+		assert!(super::suffix("test", "test", "test", "test", "test") > val);
+		let val = new("test", "test", "test", "test", "tests", &"test".into()).encode().unwrap();
+		assert!(super::suffix("test", "test", "test", "test", "test") < val);
+	}
+}
@@ -0,0 +1,82 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Cf {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub vs: u64,
+	pub nr: u32,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, vs: u64, nr: u32) -> Cf {
+	Cf::new(ns.to_string(), db.to_string(), tb.to_string(), vs, nr)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::table::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x63, 0x66, 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::table::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x63, 0x66, 0xff]);
+	k
+}
+
+impl Cf {
+	pub fn new(ns: String, db: String, tb: String, vs: u64, nr: u32) -> Cf {
+		Cf {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0x21, // !
+			_e: 0x63, // c
+			_f: 0x66, // f
+			vs,
+			nr,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cf::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			12345,
+			1,
+		);
+		let enc = Cf::encode(&val).unwrap();
+		let dec = Cf::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		let beg = new("test", "test", "test", 9, u32::MAX).encode().unwrap();
+		let end = new("test", "test", "test", 10, 0).encode().unwrap();
+		assert!(beg < end);
+		assert!(prefix("test", "test", "test") < beg);
+		assert!(suffix("test", "test", "test") > end);
+	}
+}
@@ -0,0 +1,69 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Cl {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub vs: u64,
+	pub nr: u32,
+}
+
+pub fn new(vs: u64, nr: u32) -> Cl {
+	Cl::new(vs, nr)
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x63, 0x6c, 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x63, 0x6c, 0xff]);
+	k
+}
+
+impl Cl {
+	pub fn new(vs: u64, nr: u32) -> Cl {
+		Cl {
+			__: 0x2f, // /
+			_a: 0x21, // !
+			_b: 0x63, // c
+			_c: 0x6c, // l
+			vs,
+			nr,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cl::new(
+			1_660_000_000_000_000_000,
+			1,
+		);
+		let enc = Cl::encode(&val).unwrap();
+		let dec = Cl::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		let a = new(1_660_000_000_000_000_000, 0).encode().unwrap();
+		let b = new(1_660_000_000_000_000_000, 1).encode().unwrap();
+		let c = new(1_660_000_000_000_000_001, 0).encode().unwrap();
+		assert!(a < b);
+		assert!(b < c);
+		assert!(super::prefix() < a);
+		assert!(super::suffix() > c);
+	}
+}
@@ -0,0 +1,87 @@
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct History {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub id: Id,
+	// The version stamp is stored inverted, so that the
+	// versions of a record are ordered from newest to oldest
+	vs: u64,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, id: &Id, vs: u64) -> History {
+	History::new(ns.to_string(), db.to_string(), tb.to_string(), id.to_owned(), vs)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::table::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0x23, 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::table::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0x23, 0xff]);
+	k
+}
+
+impl History {
+	pub fn new(ns: String, db: String, tb: String, id: Id, vs: u64) -> History {
+		History {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0x23, // #
+			id,
+			vs: u64::MAX - vs,
+		}
+	}
+	/// The version stamp of the transaction which wrote this version
+	pub fn vs(&self) -> u64 {
+		u64::MAX - self.vs
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = History::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+			1_600_000_000_000_000_000,
+		);
+		let enc = History::encode(&val).unwrap();
+		let dec = History::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		// Newer versions are ordered first
+		let beg = new("test", "test", "test", &"test".into(), 2).encode().unwrap();
+		let end = new("test", "test", "test", &"test".into(), 1).encode().unwrap();
+		assert!(beg < end);
+		assert_eq!(History::decode(&beg).unwrap().vs(), 2);
+		assert!(prefix("test", "test", "test") < beg);
+		assert!(suffix("test", "test", "test") > end);
+	}
+}
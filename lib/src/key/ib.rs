@@ -0,0 +1,71 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ib {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub ns: String,
+	pub db: String,
+	pub tb: String,
+	pub ix: String,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, ix: &str) -> Ib {
+	Ib::new(ns.to_string(), db.to_string(), tb.to_string(), ix.to_string())
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x69, 0x62, 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x69, 0x62, 0xff]);
+	k
+}
+
+impl Ib {
+	pub fn new(ns: String, db: String, tb: String, ix: String) -> Ib {
+		Ib {
+			__: 0x2f, // /
+			_a: 0x21, // !
+			_b: 0x69, // i
+			_c: 0x62, // b
+			ns,
+			db,
+			tb,
+			ix,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ib::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+		);
+		let enc = Ib::encode(&val).unwrap();
+		let dec = Ib::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		let a = new("test", "test", "test", "test").encode().unwrap();
+		assert!(super::prefix() < a);
+		assert!(super::suffix() > a);
+	}
+}
@@ -1,5 +1,7 @@
 use crate::sql::array::Array;
 use crate::sql::id::Id;
+use crate::sql::number::Number;
+use crate::sql::value::Value;
 use derive::Key;
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +34,42 @@ impl Prefix {
 	}
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct PrefixIds {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub ix: String,
+	pub fd: Array,
+}
+
+impl PrefixIds {
+	fn new(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> PrefixIds {
+		PrefixIds {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0xa4, // ¤
+			ix: ix.to_string(),
+			fd: fd.to_owned(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Column {
+	pub fd: Value,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
 pub struct Index {
 	__: u8,
@@ -70,6 +108,62 @@ pub fn suffix(ns: &str, db: &str, tb: &str, ix: &str) -> Vec<u8> {
 	k
 }
 
+/// The start of the entries with exactly these column values
+pub fn prefix_ids(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+	let mut k = PrefixIds::new(ns, db, tb, ix, fd).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// The end of the entries with exactly these column values
+pub fn suffix_ids(ns: &str, db: &str, tb: &str, ix: &str, fd: &Array) -> Vec<u8> {
+	let mut k = PrefixIds::new(ns, db, tb, ix, fd).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+/// The start of the entries whose leading column values are these values
+pub fn prefix_fds(ns: &str, db: &str, tb: &str, ix: &str, fd: &[Value]) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, ix).encode().unwrap();
+	for v in fd.iter() {
+		k.extend(Column::new(v).encode().unwrap());
+	}
+	k
+}
+
+/// The end of the entries whose leading column values are these values
+pub fn suffix_fds(ns: &str, db: &str, tb: &str, ix: &str, fd: &[Value]) -> Vec<u8> {
+	let mut k = prefix_fds(ns, db, tb, ix, fd);
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+/// The start of the entries whose next column value is any decimal number
+pub fn prefix_decimals(ns: &str, db: &str, tb: &str, ix: &str, fd: &[Value]) -> Vec<u8> {
+	// Decimals are not encoded in numeric order, so the
+	// range covers the type encoding shared by all decimals
+	let a = Column::new(&Number::Decimal(0.into()).into()).encode().unwrap();
+	let b = Column::new(&Number::Decimal(1.into()).into()).encode().unwrap();
+	let mut k = prefix_fds(ns, db, tb, ix, fd);
+	k.extend(a.iter().zip(b.iter()).take_while(|(a, b)| a == b).map(|(a, _)| a));
+	k
+}
+
+/// The end of the entries whose next column value is any decimal number
+pub fn suffix_decimals(ns: &str, db: &str, tb: &str, ix: &str, fd: &[Value]) -> Vec<u8> {
+	let mut k = prefix_decimals(ns, db, tb, ix, fd);
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl Column {
+	fn new(fd: &Value) -> Column {
+		Column {
+			fd: fd.to_owned(),
+		}
+	}
+}
+
 impl Index {
 	pub fn new(ns: String, db: String, tb: String, ix: String, fd: Array, id: Option<Id>) -> Index {
 		Index {
@@ -106,4 +200,32 @@ mod tests {
 		let dec = Index::decode(&enc).unwrap();
 		assert_eq!(val, dec);
 	}
+
+	#[test]
+	fn ranges() {
+		use super::*;
+		let val = Index::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			vec![Value::from("test"), Value::from(Number::Decimal(5.into()))].into(),
+			Some("test".into()),
+		);
+		let enc = Index::encode(&val).unwrap();
+		// The entry is within the exact column values
+		let fd: Array = vec![Value::from("test"), Value::from(Number::Decimal(5.into()))].into();
+		assert!(prefix_ids("test", "test", "test", "test", &fd) < enc);
+		assert!(suffix_ids("test", "test", "test", "test", &fd) > enc);
+		// The entry is within the leading column values
+		let fd = vec![Value::from("test")];
+		assert!(prefix_fds("test", "test", "test", "test", &fd) < enc);
+		assert!(suffix_fds("test", "test", "test", "test", &fd) > enc);
+		// The entry is within the decimal column values
+		assert!(prefix_decimals("test", "test", "test", "test", &fd) < enc);
+		assert!(suffix_decimals("test", "test", "test", "test", &fd) > enc);
+		// The entry is not within other leading column values
+		let fd = vec![Value::from("other")];
+		assert!(suffix_fds("test", "test", "test", "test", &fd) < enc);
+	}
 }
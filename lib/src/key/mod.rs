@@ -1,5 +1,9 @@
 /// KV              /
 /// NS              /!ns{ns}
+/// CL              /!cl{vs}{nr}
+/// IB              /!ib{ns}{db}{tb}{ix}
+/// VB              /!vb{ns}{db}{tb}
+/// VS              /!vs
 ///
 /// Namespace       /*{ns}
 /// NL              /*{ns}!nl{us}
@@ -9,32 +13,51 @@
 /// Database        /*{ns}*{db}
 /// DL              /*{ns}*{db}!dl{us}
 /// DT              /*{ns}*{db}!dt{tk}
+/// FC              /*{ns}*{db}!fc{fc}
 /// SC              /*{ns}*{db}!sc{sc}
 /// ST              /*{ns}*{db}!st{sc}!tk{tk}
 /// TB              /*{ns}*{db}!tb{tb}
 /// LQ              /*{ns}*{db}!lq{lq}
 ///
 /// Table           /*{ns}*{db}*{tb}
+/// CF              /*{ns}*{db}*{tb}!cf{vs}{nr}
 /// FT              /*{ns}*{db}*{tb}!ft{ft}
 /// FD              /*{ns}*{db}*{tb}!fd{fd}
 /// EV              /*{ns}*{db}*{tb}!ev{ev}
 /// IX              /*{ns}*{db}*{tb}!ix{ix}
 /// LV              /*{ns}*{db}*{tb}!lv{lv}
+/// RE              /*{ns}*{db}*{tb}!re{in}{out}{id}
+/// VA              /*{ns}*{db}*{tb}!va{id}{fd}{val}
+/// VL              /*{ns}*{db}*{tb}!vl{id}{vw}{ft}{fk}
 ///
 /// Thing           /*{ns}*{db}*{tb}*{id}
 ///
 /// Graph           /*{ns}*{db}*{tb}~{id}{eg}{fk}
 ///
+/// History         /*{ns}*{db}*{tb}#{id}{vs}
+///
 /// Index           /*{ns}*{db}*{tb}¤{ix}{fd}{id}
+/// BL              /*{ns}*{db}*{tb}¤{ix}!bl{id}
+/// BS              /*{ns}*{db}*{tb}¤{ix}!bs
+/// BT              /*{ns}*{db}*{tb}¤{ix}!bt{tk}{id}
+/// SP              /*{ns}*{db}*{tb}¤{ix}!sp{cell}{id}
 ///
+pub mod bl;
+pub mod bs;
+pub mod bt;
+pub mod cf;
+pub mod cl;
 pub mod database;
 pub mod db;
 pub mod dl;
 pub mod dt;
 pub mod ev;
+pub mod fc;
 pub mod fd;
 pub mod ft;
 pub mod graph;
+pub mod history;
+pub mod ib;
 pub mod index;
 pub mod ix;
 pub mod kv;
@@ -44,8 +67,14 @@ pub mod namespace;
 pub mod nl;
 pub mod ns;
 pub mod nt;
+pub mod re;
 pub mod sc;
+pub mod sp;
 pub mod st;
 pub mod table;
 pub mod tb;
 pub mod thing;
+pub mod va;
+pub mod vb;
+pub mod vl;
+pub mod vs;
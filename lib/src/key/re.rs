@@ -0,0 +1,135 @@
+use crate::sql::id::Id;
+use crate::sql::thing::Thing;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub it: String,
+	pub ik: Id,
+	pub ot: String,
+	pub ok: Id,
+}
+
+impl Prefix {
+	fn new(ns: &str, db: &str, tb: &str, i: &Thing, o: &Thing) -> Prefix {
+		Prefix {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0x21, // !
+			_e: 0x72, // r
+			_f: 0x65, // e
+			it: i.tb.to_owned(),
+			ik: i.id.to_owned(),
+			ot: o.tb.to_owned(),
+			ok: o.id.to_owned(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Re {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub it: String,
+	pub ik: Id,
+	pub ot: String,
+	pub ok: Id,
+	pub id: Id,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, i: &Thing, o: &Thing, id: &Id) -> Re {
+	Re::new(ns.to_string(), db.to_string(), tb.to_string(), i.clone(), o.clone(), id.to_owned())
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str, i: &Thing, o: &Thing) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, i, o).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str, i: &Thing, o: &Thing) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, i, o).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl Re {
+	pub fn new(ns: String, db: String, tb: String, i: Thing, o: Thing, id: Id) -> Re {
+		Re {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0x21, // !
+			_e: 0x72, // r
+			_f: 0x65, // e
+			it: i.tb,
+			ik: i.id,
+			ot: o.tb,
+			ok: o.id,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		use crate::sql::test::Parse;
+		#[rustfmt::skip]
+		let val = Re::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			Thing::parse("person:tobie"),
+			Thing::parse("person:jaime"),
+			"test".into(),
+		);
+		let enc = Re::encode(&val).unwrap();
+		let dec = Re::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		use crate::sql::test::Parse;
+		let i = Thing::parse("person:tobie");
+		let o = Thing::parse("person:jaime");
+		let k = new("test", "test", "test", &i, &o, &"test".into()).encode().unwrap();
+		assert!(prefix("test", "test", "test", &i, &o) < k);
+		assert!(suffix("test", "test", "test", &i, &o) > k);
+		let x = Thing::parse("person:jaimes");
+		let k = new("test", "test", "test", &i, &x, &"test".into()).encode().unwrap();
+		assert!(suffix("test", "test", "test", &i, &o) < k);
+	}
+}
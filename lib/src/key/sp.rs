@@ -0,0 +1,153 @@
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub ix: String,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+	pub cell: String,
+}
+
+impl Prefix {
+	fn new(ns: &str, db: &str, tb: &str, ix: &str, cell: &str) -> Prefix {
+		Prefix {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0xa4, // ¤
+			ix: ix.to_string(),
+			_e: 0x21, // !
+			_f: 0x73, // s
+			_g: 0x70, // p
+			cell: cell.to_string(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Sp {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	pub ix: String,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+	pub cell: String,
+	pub id: Id,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, ix: &str, cell: &str, id: &Id) -> Sp {
+	Sp::new(
+		ns.to_string(),
+		db.to_string(),
+		tb.to_string(),
+		ix.to_string(),
+		cell.to_string(),
+		id.to_owned(),
+	)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str, ix: &str, cell: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, ix, cell).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str, ix: &str, cell: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, ix, cell).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+pub fn range(ns: &str, db: &str, tb: &str, ix: &str, cell: &str) -> (Vec<u8>, Vec<u8>) {
+	let mut beg = Prefix::new(ns, db, tb, ix, cell).encode().unwrap();
+	// Include the cells which start with this cell
+	beg.pop();
+	let mut end = beg.clone();
+	end.extend_from_slice(&[0xff]);
+	(beg, end)
+}
+
+impl Sp {
+	pub fn new(ns: String, db: String, tb: String, ix: String, cell: String, id: Id) -> Sp {
+		Sp {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0xa4, // ¤
+			ix,
+			_e: 0x21, // !
+			_f: 0x73, // s
+			_g: 0x70, // p
+			cell,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Sp::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+		);
+		let enc = Sp::encode(&val).unwrap();
+		let dec = Sp::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn prefix() {
+		use super::*;
+		let val = new("test", "test", "test", "test", "test", &"test".into()).encode().unwrap();
+		assert!(super::prefix("test", "test", "test", "test", "test") < val);
+		assert!(super::suffix("test", "test", "test", "test", "test") > val);
+		let val = new("test", "test", "test", "test", "tests", &"test".into()).encode().unwrap();
+		assert!(super::suffix("test", "test", "test", "test", "test") < val);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let (beg, end) = super::range("test", "test", "test", "test", "u10h");
+		let val = new("test", "test", "test", "test", "u10h", &"test".into()).encode().unwrap();
+		assert!(beg < val && val < end);
+		let val = new("test", "test", "test", "test", "u10hbp", &"test".into()).encode().unwrap();
+		assert!(beg < val && val < end);
+		let val = new("test", "test", "test", "test", "u10j", &"test".into()).encode().unwrap();
+		assert!(val > end);
+	}
+}
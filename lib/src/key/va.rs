@@ -0,0 +1,228 @@
+use crate::sql::id::Id;
+use crate::sql::value::Value;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+}
+
+impl Prefix {
+	fn new(ns: &str, db: &str, tb: &str) -> Prefix {
+		Prefix {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0x21, // !
+			_e: 0x76, // v
+			_f: 0x61, // a
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct PrefixId {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+impl PrefixId {
+	fn new(ns: &str, db: &str, tb: &str, id: &Id) -> PrefixId {
+		PrefixId {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0x21, // !
+			_e: 0x76, // v
+			_f: 0x61, // a
+			id: id.to_owned(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct PrefixFd {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub fd: String,
+}
+
+impl PrefixFd {
+	fn new(ns: &str, db: &str, tb: &str, id: &Id, fd: &str) -> PrefixFd {
+		PrefixFd {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0x21, // !
+			_e: 0x76, // v
+			_f: 0x61, // a
+			id: id.to_owned(),
+			fd: fd.to_string(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Va {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub fd: String,
+	pub val: Value,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, id: &Id, fd: &str, val: &Value) -> Va {
+	Va::new(
+		ns.to_string(),
+		db.to_string(),
+		tb.to_string(),
+		id.to_owned(),
+		fd.to_string(),
+		val.to_owned(),
+	)
+}
+
+/// The start of the aggregate values of every record in the table
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// The end of the aggregate values of every record in the table
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+/// The start of the aggregate values of a record
+pub fn prefix_id(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = PrefixId::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// The end of the aggregate values of a record
+pub fn suffix_id(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = PrefixId::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+/// The start of the aggregate values of a single field of a record
+pub fn prefix_fd(ns: &str, db: &str, tb: &str, id: &Id, fd: &str) -> Vec<u8> {
+	let mut k = PrefixFd::new(ns, db, tb, id, fd).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// The end of the aggregate values of a single field of a record
+pub fn suffix_fd(ns: &str, db: &str, tb: &str, id: &Id, fd: &str) -> Vec<u8> {
+	let mut k = PrefixFd::new(ns, db, tb, id, fd).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl Va {
+	pub fn new(ns: String, db: String, tb: String, id: Id, fd: String, val: Value) -> Va {
+		Va {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0x21, // !
+			_e: 0x76, // v
+			_f: 0x61, // a
+			id,
+			fd,
+			val,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Va::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+			"test".to_string(),
+			Value::from(10),
+		);
+		let enc = Va::encode(&val).unwrap();
+		let dec = Va::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		let id = Id::from("test");
+		let k = new("test", "test", "test", &id, "test", &Value::from(10)).encode().unwrap();
+		assert!(prefix("test", "test", "test") < k);
+		assert!(suffix("test", "test", "test") > k);
+		assert!(prefix_id("test", "test", "test", &id) < k);
+		assert!(suffix_id("test", "test", "test", &id) > k);
+		assert!(prefix_fd("test", "test", "test", &id, "test") < k);
+		assert!(suffix_fd("test", "test", "test", &id, "test") > k);
+		let k = new("test", "test", "test", &id, "tests", &Value::from(10)).encode().unwrap();
+		assert!(suffix_fd("test", "test", "test", &id, "test") < k);
+	}
+}
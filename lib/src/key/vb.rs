@@ -0,0 +1,68 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Vb {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+	pub ns: String,
+	pub db: String,
+	pub tb: String,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str) -> Vb {
+	Vb::new(ns.to_string(), db.to_string(), tb.to_string())
+}
+
+pub fn prefix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x76, 0x62, 0x00]);
+	k
+}
+
+pub fn suffix() -> Vec<u8> {
+	let mut k = super::kv::new().encode().unwrap();
+	k.extend_from_slice(&[0x21, 0x76, 0x62, 0xff]);
+	k
+}
+
+impl Vb {
+	pub fn new(ns: String, db: String, tb: String) -> Vb {
+		Vb {
+			__: 0x2f, // /
+			_a: 0x21, // !
+			_b: 0x76, // v
+			_c: 0x62, // b
+			ns,
+			db,
+			tb,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Vb::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+		);
+		let enc = Vb::encode(&val).unwrap();
+		let dec = Vb::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		let a = new("test", "test", "test").encode().unwrap();
+		assert!(super::prefix() < a);
+		assert!(super::suffix() > a);
+	}
+}
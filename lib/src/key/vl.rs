@@ -0,0 +1,133 @@
+use crate::sql::id::Id;
+use crate::sql::thing::Thing;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+impl Prefix {
+	fn new(ns: &str, db: &str, tb: &str, id: &Id) -> Prefix {
+		Prefix {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns: ns.to_string(),
+			_b: 0x2a, // *
+			db: db.to_string(),
+			_c: 0x2a, // *
+			tb: tb.to_string(),
+			_d: 0x21, // !
+			_e: 0x76, // v
+			_f: 0x6c, // l
+			id: id.to_owned(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Vl {
+	__: u8,
+	_a: u8,
+	pub ns: String,
+	_b: u8,
+	pub db: String,
+	_c: u8,
+	pub tb: String,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub vw: String,
+	pub ft: String,
+	pub fk: Id,
+}
+
+pub fn new(ns: &str, db: &str, tb: &str, id: &Id, vw: &str, fk: &Thing) -> Vl {
+	Vl::new(
+		ns.to_string(),
+		db.to_string(),
+		tb.to_string(),
+		id.to_owned(),
+		vw.to_string(),
+		fk.tb.to_string(),
+		fk.id.to_owned(),
+	)
+}
+
+/// The start of the view records which join fields from a record
+pub fn prefix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// The end of the view records which join fields from a record
+pub fn suffix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl Vl {
+	pub fn new(ns: String, db: String, tb: String, id: Id, vw: String, ft: String, fk: Id) -> Vl {
+		Vl {
+			__: 0x2f, // /
+			_a: 0x2a, // *
+			ns,
+			_b: 0x2a, // *
+			db,
+			_c: 0x2a, // *
+			tb,
+			_d: 0x21, // !
+			_e: 0x76, // v
+			_f: 0x6c, // l
+			id,
+			vw,
+			ft,
+			fk,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Vl::new(
+			"test".to_string(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+			"test".to_string(),
+			"test".to_string(),
+			"test".into(),
+		);
+		let enc = Vl::encode(&val).unwrap();
+		let dec = Vl::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn order() {
+		use super::*;
+		let id = Id::from("test");
+		let fk = Thing::from(("test", "test"));
+		let k = new("test", "test", "test", &id, "test", &fk).encode().unwrap();
+		assert!(prefix("test", "test", "test", &id) < k);
+		assert!(suffix("test", "test", "test", &id) > k);
+	}
+}
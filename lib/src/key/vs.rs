@@ -0,0 +1,44 @@
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Vs {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+pub fn new() -> Vs {
+	Vs::new()
+}
+
+impl Default for Vs {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Vs {
+	pub fn new() -> Vs {
+		Vs {
+			__: 0x2f, // /
+			_a: 0x21, // !
+			_b: 0x76, // v
+			_c: 0x73, // s
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Vs::new();
+		let enc = Vs::encode(&val).unwrap();
+		let dec = Vs::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
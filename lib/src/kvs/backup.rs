@@ -0,0 +1,637 @@
+use crate::err::Error;
+use crate::key::cl;
+use crate::kvs::clock::nanos;
+use crate::kvs::Datastore;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::kvs::Val;
+use channel::Sender;
+use chrono::{DateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek};
+
+// A backup is a binary snapshot of the datastore, which is read
+// from within a single transaction, so that the snapshot is
+// consistent across all storage engines. The entries are written
+// in blocks, each of which is followed by a checksum of its
+// contents, and the backup ends with a footer which contains the
+// total number of entries and a checksum of all of the preceding
+// bytes, so that corrupt or truncated backups are detected before
+// any data is restored.
+//
+// A full backup contains every key-value entry in the datastore,
+// apart from the change log. When the change log is enabled on a
+// datastore, each write transaction records the keys which it
+// changed in the change log when it is committed, and an
+// incremental backup contains the change log entries which were
+// committed after a point in time, so that the changes can be
+// replayed on top of a restored backup, up to a chosen time.
+//
+// Header   {magic}{version:u16}{kind:u8}{time:i64}{since:i64}
+// Block    0x01{count:u32}{size:u32}{entries}{checksum:32}
+// Entry    {size:u32}{key}{size:u32}{val}
+// Footer   0x00{total:u64}{checksum:32}
+//
+// The changes of each transaction are stored in the change log in
+// chunks, keyed by the version stamp of the transaction and the number
+// of the chunk, so that no single value exceeds the value size limits of
+// the storage engines. Each chunk contains whole changes, so a change
+// which is larger than a chunk is stored in a chunk of its own.
+//
+// The time of a backup is the version stamp which the snapshot was
+// taken at, so that an incremental backup taken since that time
+// contains exactly the changes which were not in the snapshot.
+//
+// Change   {size:u32}{key}0x00 or {size:u32}{key}0x01{size:u32}{val}
+//
+// All numbers are encoded as big-endian integers, and times and
+// version stamps are stored as nanoseconds since the unix epoch.
+
+/// The bytes which identify a backup file
+pub const MAGIC: &[u8] = b"SURREALDB-BACKUP";
+
+/// The version of the backup format which is written
+pub const VERSION: u16 = 2;
+
+// The number of entries which are written in each block
+const BLOCK: u32 = 1000;
+
+// The number of change log chunks which are written in each block
+const CHANGES: u32 = 16;
+
+// The maximum size of the changes within a change log chunk
+const CHUNK: usize = 64 * 1024;
+
+// The marker which starts a block of entries
+const NEXT: u8 = 0x01;
+
+// The marker which starts the backup footer
+const DONE: u8 = 0x00;
+
+/// The type of data which a backup contains
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+	/// A snapshot of every entry in the datastore
+	Full,
+	/// The changes committed since an earlier backup
+	Incremental,
+}
+
+/// The details of a verified backup
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Info {
+	/// The version of the backup format
+	pub version: u16,
+	/// The type of data which the backup contains
+	pub kind: Kind,
+	/// The time at which the backup was taken
+	pub time: DateTime<Utc>,
+	/// The time after which the changes of an incremental backup were committed
+	pub since: Option<DateTime<Utc>>,
+	/// The number of entries within the backup
+	pub total: u64,
+}
+
+// Writes the encoded parts of a backup
+struct Writer {
+	// The running checksum of the backup
+	hash: Sha256,
+	// The number of entries which have been written
+	total: u64,
+}
+
+impl Writer {
+	fn new() -> Writer {
+		Writer {
+			hash: Sha256::new(),
+			total: 0,
+		}
+	}
+	// Encode the backup header
+	fn header(&mut self, kind: Kind, time: i64, since: i64) -> Vec<u8> {
+		let mut out = MAGIC.to_vec();
+		out.extend_from_slice(&VERSION.to_be_bytes());
+		out.push(match kind {
+			Kind::Full => 0,
+			Kind::Incremental => 1,
+		});
+		out.extend_from_slice(&time.to_be_bytes());
+		out.extend_from_slice(&since.to_be_bytes());
+		self.hash.update(&out);
+		out
+	}
+	// Encode a block of entries
+	fn block(&mut self, entries: &[(Key, Val)]) -> Vec<u8> {
+		let mut body = Vec::new();
+		for (k, v) in entries {
+			put(&mut body, k);
+			put(&mut body, v);
+		}
+		let mut out = vec![NEXT];
+		out.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+		out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+		out.extend_from_slice(&Sha256::digest(&body));
+		out.splice(9..9, body);
+		self.hash.update(&out);
+		self.total += entries.len() as u64;
+		out
+	}
+	// Encode the backup footer
+	fn footer(self) -> Vec<u8> {
+		let mut hash = self.hash;
+		let mut out = vec![DONE];
+		out.extend_from_slice(&self.total.to_be_bytes());
+		hash.update(&out);
+		out.extend_from_slice(&hash.finalize());
+		out
+	}
+}
+
+/// Reads and verifies the encoded parts of a backup
+pub struct Reader<R: Read> {
+	// The source of the backup data
+	src: R,
+	// The running checksum of the backup
+	hash: Sha256,
+	// The number of entries which have been read
+	total: u64,
+	// The type of data which the backup contains
+	kind: Kind,
+	// The checksums of the blocks which have been read
+	sums: Vec<Vec<u8>>,
+}
+
+impl<R: Read> Reader<R> {
+	/// Create a new backup reader
+	pub fn new(src: R) -> Reader<R> {
+		Reader {
+			src,
+			hash: Sha256::new(),
+			total: 0,
+			kind: Kind::Full,
+			sums: Vec::new(),
+		}
+	}
+	/// Read the backup header
+	pub fn header(&mut self) -> Result<Info, Error> {
+		let magic = self.take(MAGIC.len())?;
+		if magic != MAGIC {
+			return Err(invalid("The data is not a SurrealDB backup"));
+		}
+		let version = u16::from_be_bytes(self.array()?);
+		let (kind, time, since) = match version {
+			// Version 1 only stored full backups, timed in seconds
+			1 => {
+				let time = i64::from_be_bytes(self.array()?);
+				(Kind::Full, Utc.timestamp(time, 0), None)
+			}
+			2 => {
+				let kind = match self.take(1)?[0] {
+					0 => Kind::Full,
+					1 => Kind::Incremental,
+					_ => return Err(invalid("The backup type is not supported")),
+				};
+				let time = i64::from_be_bytes(self.array()?);
+				let since = i64::from_be_bytes(self.array()?);
+				let since = match kind {
+					Kind::Full => None,
+					Kind::Incremental => Some(Utc.timestamp_nanos(since)),
+				};
+				(kind, Utc.timestamp_nanos(time), since)
+			}
+			v => return Err(invalid(&format!("Backup format version {} is not supported", v))),
+		};
+		self.kind = kind;
+		Ok(Info {
+			version,
+			kind,
+			time,
+			since,
+			total: 0,
+		})
+	}
+	/// Read the next block of entries, returning None once the footer has been verified
+	pub fn next(&mut self) -> Result<Option<Vec<(Key, Val)>>, Error> {
+		match self.take(1)?[0] {
+			NEXT => {
+				let count = u32::from_be_bytes(self.array()?);
+				let size = u32::from_be_bytes(self.array()?) as usize;
+				let body = self.take(size)?;
+				let sum = self.take(32)?;
+				// Check the block contents
+				if Sha256::digest(&body).as_slice() != sum.as_slice() {
+					return Err(invalid("A block checksum does not match its contents"));
+				}
+				// Decode the block entries
+				let mut out = Vec::with_capacity(count as usize);
+				let mut pos = 0;
+				for _ in 0..count {
+					let k = get(&body, &mut pos)?;
+					let v = get(&body, &mut pos)?;
+					out.push((k, v));
+				}
+				if pos != body.len() {
+					return Err(invalid("A block contains unexpected data"));
+				}
+				// Check the change log entries can be replayed
+				if self.kind == Kind::Incremental {
+					for (k, v) in out.iter() {
+						cl::Cl::decode(k).map_err(|_| invalid("A change log key is invalid"))?;
+						decode(v)?;
+					}
+				}
+				self.sums.push(sum);
+				self.total += count as u64;
+				Ok(Some(out))
+			}
+			DONE => {
+				let total = u64::from_be_bytes(self.array()?);
+				let sum = self.hash.clone().finalize();
+				// Check the total number of entries
+				if total != self.total {
+					return Err(invalid("The number of entries does not match the footer"));
+				}
+				// Check the whole backup contents
+				let mut end = [0; 32];
+				self.src.read_exact(&mut end).map_err(|_| invalid("The backup is truncated"))?;
+				if sum.as_slice() != end {
+					return Err(invalid("The backup checksum does not match its contents"));
+				}
+				Ok(None)
+			}
+			_ => Err(invalid("The backup contains an unknown block type")),
+		}
+	}
+	// Read a number of bytes, adding them to the running checksum
+	fn take(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+		let mut out = vec![0; len];
+		self.src.read_exact(&mut out).map_err(|_| invalid("The backup is truncated"))?;
+		self.hash.update(&out);
+		Ok(out)
+	}
+	// Read a fixed number of bytes
+	fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+		let mut out = [0; N];
+		out.copy_from_slice(&self.take(N)?);
+		Ok(out)
+	}
+}
+
+/// Verify the structure and checksums of a backup, without restoring it
+pub fn verify<R: Read>(src: R) -> Result<Info, Error> {
+	let mut rdr = Reader::new(src);
+	let mut info = rdr.header()?;
+	while rdr.next()?.is_some() {}
+	info.total = rdr.total;
+	Ok(info)
+}
+
+impl Transaction {
+	/// Write a binary backup of the datastore, or of the changes committed after a point in time
+	///
+	/// The version stamp is the last version stamp which is
+	/// contained within the snapshot of this transaction.
+	pub async fn backup(
+		&mut self,
+		vs: u64,
+		since: Option<DateTime<Utc>>,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		let mut out = Writer::new();
+		let time = vs as i64;
+		match since {
+			// Output every entry apart from the change log
+			None => {
+				chn.send(out.header(Kind::Full, time, 0)).await?;
+				self.blocks(&mut out, &chn, vec![0x00], cl::prefix(), BLOCK).await?;
+				self.blocks(&mut out, &chn, cl::suffix(), vec![0xff], BLOCK).await?;
+			}
+			// Output the change log entries within the time range
+			Some(since) => {
+				let since = since.timestamp_nanos();
+				chn.send(out.header(Kind::Incremental, time, since)).await?;
+				let beg = cl::new(nanos(since) + 1, 0).encode()?;
+				let end = cl::new(vs + 1, 0).encode()?;
+				self.blocks(&mut out, &chn, beg, end, CHANGES).await?;
+			}
+		}
+		// Output the footer
+		chn.send(out.footer()).await?;
+		// Everything ok
+		Ok(())
+	}
+	// Remove a batch of the existing entries, returning whether any remain
+	async fn clear(&mut self) -> Result<bool, Error> {
+		// The removed entries are not a new change
+		self.log = None;
+		// Remove the next batch of entries
+		let res = self.scan(vec![0x00]..vec![0xff], BLOCK).await?;
+		let more = res.len() == BLOCK as usize;
+		for (k, _) in res {
+			self.del(k).await?;
+		}
+		Ok(more)
+	}
+	// Restore a block of a backup, replaying any changes up to a version stamp
+	async fn restore(
+		&mut self,
+		kind: Kind,
+		entries: Vec<(Key, Val)>,
+		until: Option<u64>,
+	) -> Result<(), Error> {
+		// The restored data is not a new change
+		self.log = None;
+		match kind {
+			// Write the entries of the block
+			Kind::Full => {
+				for (k, v) in entries {
+					self.set(k, v).await?;
+				}
+			}
+			// Replay the changes of each transaction in commit order
+			Kind::Incremental => {
+				for (k, v) in entries {
+					let vs = cl::Cl::decode(&k)?.vs;
+					// Skip the changes committed after the recovery time
+					if matches!(until, Some(until) if vs > until) {
+						continue;
+					}
+					for (k, v) in decode(&v)? {
+						match v {
+							Some(v) => self.set(k, v).await?,
+							None => self.del(k).await?,
+						}
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+	/// Remove the change log entries which were committed before a point in time
+	pub async fn prune(&mut self, before: DateTime<Utc>) -> Result<(), Error> {
+		// The removed entries are not a new change
+		self.log = None;
+		// Remove the change log entries
+		let end = cl::new(nanos(before.timestamp_nanos()), 0).encode()?;
+		self.delr(cl::prefix()..end, u32::MAX).await?;
+		// Everything ok
+		Ok(())
+	}
+	// Output the entries within a key range in blocks of a number of entries
+	async fn blocks(
+		&mut self,
+		out: &mut Writer,
+		chn: &Sender<Vec<u8>>,
+		beg: Key,
+		end: Key,
+		limit: u32,
+	) -> Result<(), Error> {
+		let mut nxt: Option<Key> = None;
+		loop {
+			let min = match nxt.take() {
+				Some(mut k) => {
+					k.push(0x00);
+					k
+				}
+				None => beg.clone(),
+			};
+			let res = self.scan(min..end.clone(), limit).await?;
+			if res.is_empty() {
+				break;
+			}
+			nxt = res.last().map(|(k, _)| k.clone());
+			chn.send(out.block(&res)).await?;
+		}
+		Ok(())
+	}
+}
+
+/// Restore a binary backup, replaying any changes up to a point in time
+///
+/// The checksum of every block, and of the whole backup, is verified
+/// before any data is changed, and each block of the backup is then
+/// restored in its own transaction. Each block is checked against the
+/// checksum which was verified for it, so that a backup which changes
+/// while it is restored is rejected at the first differing block.
+pub(super) async fn restore<R: Read + Seek>(
+	ds: &Datastore,
+	mut src: R,
+	until: Option<DateTime<Utc>>,
+) -> Result<Info, Error> {
+	// Check the whole backup
+	let mut rdr = Reader::new(&mut src);
+	let mut info = rdr.header()?;
+	while rdr.next()?.is_some() {}
+	info.total = rdr.total;
+	let sums = rdr.sums;
+	// Check the backup was taken before the recovery time
+	if let Some(until) = until {
+		if info.kind == Kind::Full && info.time > until {
+			return Err(invalid("The backup was taken after the recovery time"));
+		}
+	}
+	// Read the backup from the start
+	src.rewind().map_err(|_| invalid("The backup could not be read"))?;
+	let mut rdr = Reader::new(src);
+	rdr.header()?;
+	// Remove all existing entries before a full backup
+	if info.kind == Kind::Full {
+		loop {
+			let mut txn = ds.transaction(true, false).await?;
+			match txn.clear().await {
+				Ok(more) => {
+					txn.commit().await?;
+					if !more {
+						break;
+					}
+				}
+				Err(e) => {
+					txn.cancel().await?;
+					return Err(e);
+				}
+			}
+		}
+	}
+	// Restore each block of the backup
+	let until = until.map(|v| nanos(v.timestamp_nanos()));
+	while let Some(entries) = rdr.next()? {
+		// Check the block is the one which was verified
+		if rdr.sums.last() != sums.get(rdr.sums.len() - 1) {
+			return Err(invalid("The backup changed while it was being restored"));
+		}
+		let mut txn = ds.transaction(true, false).await?;
+		match txn.restore(info.kind, entries, until).await {
+			Ok(_) => txn.commit().await?,
+			Err(e) => {
+				txn.cancel().await?;
+				return Err(e);
+			}
+		}
+	}
+	// Everything ok
+	Ok(info)
+}
+
+// Encode the changes of a transaction as change log chunks
+pub(super) fn changes(vs: u64, log: Vec<(Key, Option<Val>)>) -> Vec<(Key, Val)> {
+	let mut out = Vec::new();
+	let mut val = Vec::new();
+	for (k, v) in log {
+		let mut chg = Vec::new();
+		put(&mut chg, &k);
+		match v {
+			Some(v) => {
+				chg.push(0x01);
+				put(&mut chg, &v);
+			}
+			None => chg.push(0x00),
+		}
+		// Start a new chunk once this one is full
+		if !val.is_empty() && val.len() + chg.len() > CHUNK {
+			let key = cl::new(vs, out.len() as u32).encode().unwrap();
+			out.push((key, std::mem::take(&mut val)));
+		}
+		val.append(&mut chg);
+	}
+	if !val.is_empty() {
+		let key = cl::new(vs, out.len() as u32).encode().unwrap();
+		out.push((key, val));
+	}
+	out
+}
+
+// Decode the changes of a change log entry
+fn decode(val: &[u8]) -> Result<Vec<(Key, Option<Val>)>, Error> {
+	let mut out = Vec::new();
+	let mut pos = 0;
+	while pos < val.len() {
+		let k = get(val, &mut pos)?;
+		let v = match val.get(pos) {
+			Some(0x00) => {
+				pos += 1;
+				None
+			}
+			Some(0x01) => {
+				pos += 1;
+				Some(get(val, &mut pos)?)
+			}
+			_ => return Err(invalid("A change log entry is invalid")),
+		};
+		out.push((k, v));
+	}
+	Ok(out)
+}
+
+// Write a length-prefixed byte slice
+fn put(out: &mut Vec<u8>, v: &[u8]) {
+	out.extend_from_slice(&(v.len() as u32).to_be_bytes());
+	out.extend_from_slice(v);
+}
+
+// Read a length-prefixed byte slice
+fn get(body: &[u8], pos: &mut usize) -> Result<Vec<u8>, Error> {
+	let err = || invalid("A block contains a truncated entry");
+	let len = body.get(*pos..*pos + 4).ok_or_else(err)?;
+	let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+	*pos += 4;
+	let out = body.get(*pos..*pos + len).ok_or_else(err)?.to_vec();
+	*pos += len;
+	Ok(out)
+}
+
+fn invalid(message: &str) -> Error {
+	Error::InvalidBackup {
+		message: message.to_owned(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	fn backup(entries: &[(Key, Val)]) -> Vec<u8> {
+		let mut out = Writer::new();
+		let mut buf = out.header(Kind::Full, 1_660_000_000_000_000_000, 0);
+		for block in entries.chunks(2) {
+			buf.extend(out.block(block));
+		}
+		buf.extend(out.footer());
+		buf
+	}
+
+	fn entries() -> Vec<(Key, Val)> {
+		vec![
+			(b"/a".to_vec(), b"1".to_vec()),
+			(b"/b".to_vec(), b"22".to_vec()),
+			(b"/c".to_vec(), vec![]),
+		]
+	}
+
+	#[test]
+	fn backup_roundtrip() {
+		let buf = backup(&entries());
+		let mut rdr = Reader::new(&buf[..]);
+		let info = rdr.header().unwrap();
+		assert_eq!(info.version, VERSION);
+		assert_eq!(info.kind, Kind::Full);
+		assert_eq!(info.time, Utc.timestamp(1_660_000_000, 0));
+		let mut out = vec![];
+		while let Some(mut v) = rdr.next().unwrap() {
+			out.append(&mut v);
+		}
+		assert_eq!(out, entries());
+		let info = verify(&buf[..]).unwrap();
+		assert_eq!(info.total, 3);
+	}
+
+	#[test]
+	fn backup_detects_corruption() {
+		let buf = backup(&entries());
+		// Flip a byte of an entry value
+		let mut bad = buf.clone();
+		let pos = bad.windows(2).position(|w| w == b"22").unwrap();
+		bad[pos] = b'3';
+		assert!(verify(&bad[..]).is_err());
+		// Remove the end of the footer
+		let bad = &buf[..buf.len() - 1];
+		assert!(verify(bad).is_err());
+		// Change the format version
+		let mut bad = buf.clone();
+		bad[MAGIC.len() + 1] = 9;
+		assert!(verify(&bad[..]).is_err());
+		// Not a backup at all
+		assert!(verify(&b"-- SQL export"[..]).is_err());
+	}
+
+	#[test]
+	fn changes_roundtrip() {
+		let log = vec![
+			(b"/a".to_vec(), Some(b"1".to_vec())),
+			(b"/b".to_vec(), None),
+			(b"/c".to_vec(), Some(vec![])),
+		];
+		let out = changes(1_660_000_000_000_000_000, log.clone());
+		assert_eq!(out.len(), 1);
+		let (key, val) = &out[0];
+		assert!(key > &cl::prefix() && key < &cl::suffix());
+		assert_eq!(decode(val).unwrap(), log);
+	}
+
+	#[test]
+	fn changes_chunked() {
+		let log: Vec<(Key, Option<Val>)> =
+			(0..100u32).map(|i| (i.to_be_bytes().to_vec(), Some(vec![0; 2000]))).collect();
+		let out = changes(1_660_000_000_000_000_000, log.clone());
+		assert!(out.len() > 1);
+		let mut all = vec![];
+		for (i, (key, val)) in out.iter().enumerate() {
+			let key = cl::Cl::decode(key).unwrap();
+			assert_eq!(key.vs, 1_660_000_000_000_000_000);
+			assert_eq!(key.nr, i as u32);
+			assert!(val.len() <= CHUNK);
+			all.extend(decode(val).unwrap());
+		}
+		assert_eq!(all, log);
+	}
+}
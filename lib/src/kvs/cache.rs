@@ -2,6 +2,7 @@ use crate::kvs::kv::Key;
 use crate::sql::statements::DefineDatabaseStatement;
 use crate::sql::statements::DefineEventStatement;
 use crate::sql::statements::DefineFieldStatement;
+use crate::sql::statements::DefineFunctionStatement;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::statements::DefineLoginStatement;
 use crate::sql::statements::DefineNamespaceStatement;
@@ -23,6 +24,7 @@ pub enum Entry {
 	Dbs(Arc<Vec<DefineDatabaseStatement>>),
 	Dls(Arc<Vec<DefineLoginStatement>>),
 	Dts(Arc<Vec<DefineTokenStatement>>),
+	Fcs(Arc<Vec<DefineFunctionStatement>>),
 	Scs(Arc<Vec<DefineScopeStatement>>),
 	Sts(Arc<Vec<DefineTokenStatement>>),
 	Tbs(Arc<Vec<DefineTableStatement>>),
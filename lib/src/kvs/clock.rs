@@ -0,0 +1,83 @@
+use super::Key;
+use chrono::Utc;
+use futures::lock::Mutex;
+use std::sync::Arc;
+
+// A version stamp orders the transactions which were committed
+// against a datastore. Each stamp is the number of nanoseconds
+// since the unix epoch at which the transaction was committed,
+// moved forward when necessary so that each stamp is greater than
+// every stamp which was assigned before it. This means that stamps
+// can be compared with points in time, for queries which read the
+// data as at a time in the past, and are in commit order even when
+// the system clock moves backwards.
+//
+// A transaction which writes stamped keys holds the datastore clock
+// from the point that its stamp is assigned until it has committed,
+// so that the stamps are in the same order as the commits.
+//
+// The datastore clock, and the system time which it is based on, are
+// local to the process, so they only order the commits of single-node
+// storage engines. On distributed storage engines, each transaction
+// which writes stamped keys also reads and writes the last version
+// stamp which is stored in the datastore, so that two transactions
+// which assign stamps on any nodes conflict, and the stamps remain in
+// commit order across the cluster. TiKV stamps are based on the time
+// from the timestamp oracle of the cluster. FoundationDB versionstamps
+// are commit versions rather than times, so FoundationDB stamps are
+// based on the system time of the node, and only their order is
+// guaranteed across the cluster.
+
+/// The last version stamp which was assigned on a datastore
+pub(super) type Clock = Arc<Mutex<u64>>;
+
+/// A key which is completed with the version stamp of its transaction,
+/// and the position of the key amongst the stamped keys of the transaction
+pub(super) type Stamped = Box<dyn FnOnce(u64, u32) -> Key + Send>;
+
+/// Assign the next version stamp
+pub(super) fn next(last: &mut u64) -> u64 {
+	*last = after(now(), *last);
+	*last
+}
+
+/// Assign the version stamp which follows the last version stamp, at a time
+pub(super) fn after(time: u64, last: u64) -> u64 {
+	time.max(last + 1)
+}
+
+/// Convert the system time into a version stamp
+pub(super) fn now() -> u64 {
+	nanos(Utc::now().timestamp_nanos())
+}
+
+/// Convert a time into a version stamp
+pub(crate) fn nanos(v: i64) -> u64 {
+	v.max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn stamps_increase() {
+		let mut last = 0;
+		let a = next(&mut last);
+		assert!(a > 0);
+		// The clock is ahead of the system time
+		last = u64::MAX - 10;
+		let b = next(&mut last);
+		let c = next(&mut last);
+		assert_eq!(b, u64::MAX - 9);
+		assert_eq!(c, u64::MAX - 8);
+	}
+
+	#[test]
+	fn stamps_follow_stored_stamp() {
+		assert_eq!(after(10, 5), 10);
+		assert_eq!(after(10, 10), 11);
+		assert_eq!(after(10, 20), 21);
+	}
+}
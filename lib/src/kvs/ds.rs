@@ -1,23 +1,40 @@
+use super::backup;
+use super::clock::Clock;
 use super::tx::Transaction;
 use crate::ctx::Context;
 use crate::dbs::Attach;
+use crate::dbs::Chunk;
 use crate::dbs::Executor;
+use crate::dbs::Notification;
 use crate::dbs::Options;
+use crate::dbs::PreparedQuery;
+use crate::dbs::QueryCache;
 use crate::dbs::Response;
+use crate::dbs::Running;
 use crate::dbs::Session;
 use crate::dbs::Variables;
 use crate::err::Error;
 use crate::kvs::LOG;
-use crate::sql;
 use crate::sql::Query;
 use crate::sql::Value;
+use channel::Receiver;
 use channel::Sender;
+use chrono::{DateTime, TimeZone, Utc};
 use futures::lock::Mutex;
+use std::io::{Read, Seek};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// The underlying datastore instance which stores the dataset.
 pub struct Datastore {
 	pub(super) inner: Inner,
+	pub(super) notifications: Option<(Sender<Notification>, Receiver<Notification>)>,
+	pub(super) queries: QueryCache,
+	pub(crate) running: Arc<Running>,
+	pub(crate) timeout: Option<Duration>,
+	pub(super) clock: Clock,
+	pub(super) changelog: Option<Duration>,
+	pub(crate) builds: bool,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -79,6 +96,13 @@ impl Datastore {
 				info!(target: LOG, "Starting kvs store in {}", path);
 				let v = super::mem::Datastore::new().await.map(|v| Datastore {
 					inner: Inner::Mem(v),
+					notifications: None,
+					queries: QueryCache::default(),
+					running: Arc::default(),
+					timeout: None,
+					clock: Clock::default(),
+					changelog: None,
+					builds: false,
 				});
 				info!(target: LOG, "Started kvs store in {}", path);
 				v
@@ -91,6 +115,13 @@ impl Datastore {
 				let s = s.trim_start_matches("file:");
 				let v = super::rocksdb::Datastore::new(s).await.map(|v| Datastore {
 					inner: Inner::RocksDB(v),
+					notifications: None,
+					queries: QueryCache::default(),
+					running: Arc::default(),
+					timeout: None,
+					clock: Clock::default(),
+					changelog: None,
+					builds: false,
 				});
 				info!(target: LOG, "Started kvs store at {}", path);
 				v
@@ -103,6 +134,13 @@ impl Datastore {
 				let s = s.trim_start_matches("rocksdb:");
 				let v = super::rocksdb::Datastore::new(s).await.map(|v| Datastore {
 					inner: Inner::RocksDB(v),
+					notifications: None,
+					queries: QueryCache::default(),
+					running: Arc::default(),
+					timeout: None,
+					clock: Clock::default(),
+					changelog: None,
+					builds: false,
 				});
 				info!(target: LOG, "Started kvs store at {}", path);
 				v
@@ -115,6 +153,13 @@ impl Datastore {
 				let s = s.trim_start_matches("indxdb:");
 				let v = super::indxdb::Datastore::new(s).await.map(|v| Datastore {
 					inner: Inner::IndxDB(v),
+					notifications: None,
+					queries: QueryCache::default(),
+					running: Arc::default(),
+					timeout: None,
+					clock: Clock::default(),
+					changelog: None,
+					builds: false,
 				});
 				info!(target: LOG, "Started kvs store at {}", path);
 				v
@@ -127,6 +172,13 @@ impl Datastore {
 				let s = s.trim_start_matches("tikv:");
 				let v = super::tikv::Datastore::new(s).await.map(|v| Datastore {
 					inner: Inner::TiKV(v),
+					notifications: None,
+					queries: QueryCache::default(),
+					running: Arc::default(),
+					timeout: None,
+					clock: Clock::default(),
+					changelog: None,
+					builds: false,
 				});
 				info!(target: LOG, "Connected to kvs store at {}", path);
 				v
@@ -139,6 +191,13 @@ impl Datastore {
 				let s = s.trim_start_matches("fdb:");
 				let v = super::fdb::Datastore::new(s).await.map(|v| Datastore {
 					inner: Inner::FDB(v),
+					notifications: None,
+					queries: QueryCache::default(),
+					running: Arc::default(),
+					timeout: None,
+					clock: Clock::default(),
+					changelog: None,
+					builds: false,
 				});
 				info!(target: LOG, "Connected to kvs store at {}", path);
 				v
@@ -148,6 +207,97 @@ impl Datastore {
 		}
 	}
 
+	/// Enable the delivery of live query notifications for this datastore
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::Datastore;
+	/// # use surrealdb::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new("memory").await?.with_notifications();
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_notifications(mut self) -> Self {
+		self.notifications = Some(channel::unbounded());
+		self
+	}
+
+	/// Set a default timeout for the statements executed on this datastore
+	///
+	/// Statements which specify a `TIMEOUT` clause use that timeout instead.
+	///
+	/// ```rust,no_run
+	/// # use std::time::Duration;
+	/// # use surrealdb::Datastore;
+	/// # use surrealdb::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new("memory").await?.with_query_timeout(Some(Duration::from_secs(30)));
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_query_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Enable the change log of this datastore, which incremental backups are taken from
+	///
+	/// Each write transaction records the keys which it changed in
+	/// the change log when it is committed. The changes are removed
+	/// by [`Datastore::tick`] once they are older than the retention.
+	///
+	/// ```rust,no_run
+	/// # use std::time::Duration;
+	/// # use surrealdb::Datastore;
+	/// # use surrealdb::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new("memory").await?.with_change_log(Some(Duration::from_secs(86400)));
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_change_log(mut self, retention: Option<Duration>) -> Self {
+		self.changelog = retention;
+		self
+	}
+
+	/// Continue index and foreign table builds in the background, with [`Datastore::build_indexes`]
+	///
+	/// Without this, an index or foreign table is built in full by the
+	/// statement which defines or rebuilds it. With this, the statement
+	/// processes the first batch of records, and the remaining batches
+	/// must be processed by calling [`Datastore::build_indexes`] periodically.
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::Datastore;
+	/// # use surrealdb::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new("memory").await?.with_index_builds();
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_index_builds(mut self) -> Self {
+		self.builds = true;
+		self
+	}
+
+	/// Get a receiver for the live query notifications of this datastore
+	///
+	/// Returns `None` if notifications have not been enabled with
+	/// [`Datastore::with_notifications`]. Notifications are only sent
+	/// once the transaction which produced them has been committed.
+	pub fn notifications(&self) -> Option<Receiver<Notification>> {
+		self.notifications.as_ref().map(|(_, r)| r.clone())
+	}
+
+	/// Get a sender for the live query notifications of this datastore
+	pub(crate) fn sender(&self) -> Option<Sender<Notification>> {
+		self.notifications.as_ref().map(|(s, _)| s.clone())
+	}
+
 	/// Create a new transaction on this datastore
 	///
 	/// *You must ensure that a [`Transaction`] does not ever outlive a [`Datastore`] instance.*
@@ -172,6 +322,9 @@ impl Datastore {
 				Ok(Transaction {
 					inner: super::tx::Inner::Mem(tx),
 					cache: super::cache::Cache::default(),
+					log: (write && self.changelog.is_some()).then(Vec::new),
+					clock: self.clock.clone(),
+					stamped: Vec::new(),
 				})
 			}
 			#[cfg(feature = "kv-rocksdb")]
@@ -180,6 +333,9 @@ impl Datastore {
 				Ok(Transaction {
 					inner: super::tx::Inner::RocksDB(tx),
 					cache: super::cache::Cache::default(),
+					log: (write && self.changelog.is_some()).then(Vec::new),
+					clock: self.clock.clone(),
+					stamped: Vec::new(),
 				})
 			}
 			#[cfg(feature = "kv-indxdb")]
@@ -188,6 +344,9 @@ impl Datastore {
 				Ok(Transaction {
 					inner: super::tx::Inner::IndxDB(tx),
 					cache: super::cache::Cache::default(),
+					log: (write && self.changelog.is_some()).then(Vec::new),
+					clock: self.clock.clone(),
+					stamped: Vec::new(),
 				})
 			}
 			#[cfg(feature = "kv-tikv")]
@@ -196,6 +355,9 @@ impl Datastore {
 				Ok(Transaction {
 					inner: super::tx::Inner::TiKV(tx),
 					cache: super::cache::Cache::default(),
+					log: (write && self.changelog.is_some()).then(Vec::new),
+					clock: self.clock.clone(),
+					stamped: Vec::new(),
 				})
 			}
 			#[cfg(feature = "kv-fdb")]
@@ -204,6 +366,9 @@ impl Datastore {
 				Ok(Transaction {
 					inner: super::tx::Inner::FDB(tx),
 					cache: super::cache::Cache::default(),
+					log: (write && self.changelog.is_some()).then(Vec::new),
+					clock: self.clock.clone(),
+					stamped: Vec::new(),
 				})
 			}
 		}
@@ -232,8 +397,8 @@ impl Datastore {
 		vars: Variables,
 		strict: bool,
 	) -> Result<Vec<Response>, Error> {
-		// Create a new query options
-		let mut opt = Options::default();
+		// Setup the query options
+		let mut opt = self.options(sess);
 		// Create a new query executor
 		let mut exe = Executor::new(self);
 		// Create a default context
@@ -243,20 +408,169 @@ impl Datastore {
 		// Store the query variables
 		let ctx = vars.attach(ctx);
 		// Parse the SQL query text
-		let ast = sql::parse(txt)?;
-		// Setup the auth options
-		opt.auth = sess.au.clone();
-		// Setup the live options
-		opt.live = sess.rt;
-		// Set current NS and DB
-		opt.ns = sess.ns();
-		opt.db = sess.db();
+		let ast = self.queries.parse(txt)?;
 		// Set strict config
 		opt.strict = strict;
 		// Process all statements
 		exe.execute(ctx, opt, ast).await
 	}
 
+	/// Parse and execute an SQL query, streaming the results to a channel
+	///
+	/// The records output by each read-only statement are sent in
+	/// batches as they are processed, and each statement is followed
+	/// by its [`Response`]. The channel is closed once the query has
+	/// completed.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::Chunk;
+	/// use surrealdb::Datastore;
+	/// use surrealdb::Error;
+	/// use surrealdb::Session;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::for_kv().with_ns("test").with_db("test");
+	///     let (snd, rcv) = surrealdb::channel::new(1);
+	///     let ast = "SELECT * FROM log;";
+	///     let (res, _) = futures::join!(ds.execute_stream(ast, &ses, None, false, snd), async {
+	///         while let Ok(v) = rcv.recv().await {
+	///             match v {
+	///                 Chunk::Values(v) => println!("{} records", v.len()),
+	///                 Chunk::Response(v) => println!("{:?}", v.result),
+	///             }
+	///         }
+	///     });
+	///     res
+	/// }
+	/// ```
+	pub async fn execute_stream(
+		&self,
+		txt: &str,
+		sess: &Session,
+		vars: Variables,
+		strict: bool,
+		chn: Sender<Chunk>,
+	) -> Result<(), Error> {
+		// Parse the SQL query text
+		let qry = self.prepare(txt)?;
+		// Process all statements
+		self.execute_prepared_stream(&qry, sess, vars, strict, chn).await
+	}
+
+	/// Execute a prepared SQL query, streaming the results of read-only statements
+	///
+	/// ```rust,no_run
+	/// use surrealdb::Datastore;
+	/// use surrealdb::Error;
+	/// use surrealdb::Session;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::for_kv().with_ns("test").with_db("test");
+	///     let qry = ds.prepare("SELECT * FROM log;")?;
+	///     let (snd, rcv) = surrealdb::channel::new(1);
+	///     let (res, _) = futures::join!(ds.execute_prepared_stream(&qry, &ses, None, false, snd), async {
+	///         while rcv.recv().await.is_ok() {}
+	///     });
+	///     res
+	/// }
+	/// ```
+	pub async fn execute_prepared_stream(
+		&self,
+		qry: &PreparedQuery,
+		sess: &Session,
+		vars: Variables,
+		strict: bool,
+		chn: Sender<Chunk>,
+	) -> Result<(), Error> {
+		// Setup the query options
+		let mut opt = self.options(sess);
+		// Create a new query executor
+		let mut exe = Executor::new(self).with_stream(chn);
+		// Create a default context
+		let ctx = Context::default();
+		// Start an execution context
+		let ctx = sess.context(ctx);
+		// Store the query variables
+		let ctx = vars.attach(ctx);
+		// Set strict config
+		opt.strict = strict;
+		// Process all statements
+		exe.execute(ctx, opt, qry.ast.clone()).await?;
+		// Everything ok
+		Ok(())
+	}
+
+	/// Parse an SQL query so that it can be executed repeatedly
+	///
+	/// ```rust,no_run
+	/// use surrealdb::Datastore;
+	/// use surrealdb::Error;
+	/// use surrealdb::Session;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::for_kv().with_ns("test").with_db("test");
+	///     let qry = ds.prepare("SELECT * FROM person WHERE age > $age;")?;
+	///     for age in [18, 21] {
+	///         let var = Some([("age".to_owned(), age.into())].into());
+	///         let res = ds.execute_prepared(&qry, &ses, var, false).await?;
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn prepare(&self, txt: &str) -> Result<PreparedQuery, Error> {
+		// Parse the SQL query text
+		let ast = self.queries.parse(txt)?;
+		// Return the prepared query
+		Ok(PreparedQuery {
+			ast,
+		})
+	}
+
+	/// Execute a prepared SQL query
+	///
+	/// ```rust,no_run
+	/// use surrealdb::Datastore;
+	/// use surrealdb::Error;
+	/// use surrealdb::Session;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::for_kv().with_ns("test").with_db("test");
+	///     let qry = ds.prepare("SELECT * FROM person;")?;
+	///     let res = ds.execute_prepared(&qry, &ses, None, false).await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn execute_prepared(
+		&self,
+		qry: &PreparedQuery,
+		sess: &Session,
+		vars: Variables,
+		strict: bool,
+	) -> Result<Vec<Response>, Error> {
+		// Setup the query options
+		let mut opt = self.options(sess);
+		// Create a new query executor
+		let mut exe = Executor::new(self);
+		// Create a default context
+		let ctx = Context::default();
+		// Start an execution context
+		let ctx = sess.context(ctx);
+		// Store the query variables
+		let ctx = vars.attach(ctx);
+		// Set strict config
+		opt.strict = strict;
+		// Process all statements
+		exe.execute(ctx, opt, qry.ast.clone()).await
+	}
+
 	/// Execute a pre-parsed SQL query
 	///
 	/// ```rust,no_run
@@ -281,8 +595,8 @@ impl Datastore {
 		vars: Variables,
 		strict: bool,
 	) -> Result<Vec<Response>, Error> {
-		// Create a new query options
-		let mut opt = Options::default();
+		// Setup the query options
+		let mut opt = self.options(sess);
 		// Create a new query executor
 		let mut exe = Executor::new(self);
 		// Create a default context
@@ -291,17 +605,10 @@ impl Datastore {
 		let ctx = sess.context(ctx);
 		// Store the query variables
 		let ctx = vars.attach(ctx);
-		// Setup the auth options
-		opt.auth = sess.au.clone();
-		// Setup the live options
-		opt.live = sess.rt;
-		// Set current NS and DB
-		opt.ns = sess.ns();
-		opt.db = sess.db();
 		// Set strict config
 		opt.strict = strict;
 		// Process all statements
-		exe.execute(ctx, opt, ast).await
+		exe.execute(ctx, opt, Arc::new(ast)).await
 	}
 
 	/// Ensure a SQL [`Value`] is fully computed
@@ -359,6 +666,23 @@ impl Datastore {
 		Ok(res)
 	}
 
+	// Setup the query options for a session
+	fn options(&self, sess: &Session) -> Options {
+		Options {
+			// Setup the auth options
+			auth: sess.au.clone(),
+			// Setup the live options
+			live: sess.rt,
+			// Set current NS and DB
+			ns: sess.ns(),
+			db: sess.db(),
+			// Set transaction retries
+			retries: sess.retries(),
+			// Create a new query options
+			..Options::default()
+		}
+	}
+
 	/// Performs a full database export as SQL
 	pub async fn export(&self, ns: String, db: String, chn: Sender<Vec<u8>>) -> Result<(), Error> {
 		// Start a new transaction
@@ -368,4 +692,136 @@ impl Datastore {
 		// Everything ok
 		Ok(())
 	}
+
+	/// Performs a binary backup of the datastore
+	///
+	/// When a time is specified, only the changes which were
+	/// committed after that time are included in the backup.
+	pub async fn backup(
+		&self,
+		since: Option<DateTime<Utc>>,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Incremental backups are taken from the change log
+		if since.is_some() && self.changelog.is_none() {
+			return Err(Error::ChangeLogDisabled);
+		}
+		// Start a new read transaction while no transaction is
+		// committing, so that the snapshot contains exactly the
+		// changes up to the version stamp of the backup
+		let (vs, mut txn) = {
+			let mut last = self.clock.lock().await;
+			let mut txn = self.transaction(false, false).await?;
+			match txn.snapshot(&mut last).await {
+				Ok(vs) => (vs, txn),
+				Err(e) => {
+					txn.cancel().await?;
+					return Err(e);
+				}
+			}
+		};
+		// Process the backup
+		let res = txn.backup(vs, since, chn).await;
+		// Close the transaction
+		txn.cancel().await?;
+		// Return any backup error
+		res
+	}
+
+	/// Restores a binary backup into the datastore
+	///
+	/// A full backup replaces every entry in the datastore, and an
+	/// incremental backup replays the changes which it contains on
+	/// top of the existing data. When a time is specified, only the
+	/// changes which were committed before that time are replayed.
+	///
+	/// The whole backup is verified before any data is changed. The
+	/// backup is then restored in batches, with each block committed
+	/// in its own transaction, and the restored entries are not
+	/// recorded in the change log.
+	pub async fn restore<R: Read + Seek>(
+		&self,
+		src: R,
+		until: Option<DateTime<Utc>>,
+	) -> Result<backup::Info, Error> {
+		backup::restore(self, src, until).await
+	}
+
+	/// Continues building the indexes and foreign tables which are being built in the background
+	///
+	/// Each build processes the remaining records of its source tables
+	/// in batches, with each batch committed in its own transaction, so
+	/// that a build which was interrupted resumes where it stopped.
+	pub async fn build_indexes(&self) -> Result<(), Error> {
+		crate::idx::build::resume(self).await
+	}
+
+	/// Performs the periodic maintenance of the datastore
+	///
+	/// This removes the change log entries which are older than
+	/// the change log retention, and the record history and change
+	/// feed entries which are older than the retention of each table.
+	pub async fn tick(&self) -> Result<(), Error> {
+		// Get the current version stamp
+		let now = super::clock::nanos(Utc::now().timestamp_nanos());
+		// Prune the change log
+		if let Some(v) = self.changelog {
+			let vs = now.saturating_sub(v.as_nanos() as u64);
+			self.prune(Utc.timestamp_nanos(vs as i64)).await?;
+		}
+		// Find the tables which keep a record history or change feed
+		let mut tbs = Vec::new();
+		let mut txn = self.transaction(false, false).await?;
+		for ns in txn.all_ns().await?.iter() {
+			for db in txn.all_db(&ns.name).await?.iter() {
+				for tb in txn.all_tb(&ns.name, &db.name).await?.iter() {
+					if tb.history.is_some() || tb.changefeed.is_some() {
+						let (h, c) =
+							(tb.history.as_ref().map(|v| v.0), tb.changefeed.as_ref().map(|v| v.0));
+						tbs.push((ns.name.to_raw(), db.name.to_raw(), tb.name.to_raw(), h, c));
+					}
+				}
+			}
+		}
+		txn.cancel().await?;
+		// Prune the record history and change feed of each table
+		for (ns, db, tb, h, c) in tbs {
+			let mut txn = self.transaction(true, false).await?;
+			let res = async {
+				if let Some(v) = h {
+					let vs = now.saturating_sub(v.as_nanos() as u64);
+					txn.prune_history(&ns, &db, &tb, vs).await?;
+				}
+				if let Some(v) = c {
+					let vs = now.saturating_sub(v.as_nanos() as u64);
+					txn.prune_changefeed(&ns, &db, &tb, vs).await?;
+				}
+				Ok::<(), Error>(())
+			}
+			.await;
+			match res {
+				Ok(_) => txn.commit().await?,
+				Err(e) => {
+					txn.cancel().await?;
+					return Err(e);
+				}
+			}
+		}
+		// Everything ok
+		Ok(())
+	}
+
+	/// Removes the change log entries which were committed before a point in time
+	pub async fn prune(&self, before: DateTime<Utc>) -> Result<(), Error> {
+		// Start a new write transaction
+		let mut txn = self.transaction(true, false).await?;
+		// Process the pruning
+		match txn.prune(before).await {
+			Ok(_) => txn.commit().await,
+			Err(e) => {
+				txn.cancel().await?;
+				Err(e)
+			}
+		}
+	}
 }
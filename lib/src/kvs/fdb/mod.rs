@@ -138,6 +138,9 @@ impl Transaction {
 		};
 		match r {
 			Ok(_r) => {}
+			Err(e) if e.is_retryable_not_committed() => {
+				return Err(Error::TxConflict);
+			}
 			Err(e) => {
 				return Err(Error::Tx(format!("Transaction commit error: {}", e).to_string()));
 			}
@@ -1,4 +1,6 @@
+pub(crate) mod backup;
 mod cache;
+pub(crate) mod clock;
 mod ds;
 mod fdb;
 mod indxdb;
@@ -4,11 +4,12 @@ use crate::err::Error;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use std::ops::Range;
+use std::sync::Arc;
 use tikv::CheckLevel;
 use tikv::TransactionOptions;
 
 pub struct Datastore {
-	db: tikv::TransactionClient,
+	db: Arc<tikv::TransactionClient>,
 }
 
 pub struct Transaction {
@@ -18,6 +19,8 @@ pub struct Transaction {
 	rw: bool,
 	// The distributed datastore transaction
 	tx: tikv::Transaction,
+	// The distributed datastore client
+	db: Arc<tikv::TransactionClient>,
 }
 
 impl Datastore {
@@ -25,7 +28,7 @@ impl Datastore {
 	pub async fn new(path: &str) -> Result<Datastore, Error> {
 		match tikv::TransactionClient::new(vec![path]).await {
 			Ok(db) => Ok(Datastore {
-				db,
+				db: Arc::new(db),
 			}),
 			Err(e) => Err(Error::Ds(e.to_string())),
 		}
@@ -46,6 +49,7 @@ impl Datastore {
 						ok: false,
 						rw: write,
 						tx,
+						db: self.db.clone(),
 					}),
 					Err(e) => Err(Error::Tx(e.to_string())),
 				}
@@ -63,6 +67,7 @@ impl Datastore {
 						ok: false,
 						rw: write,
 						tx,
+						db: self.db.clone(),
 					}),
 					Err(e) => Err(Error::Tx(e.to_string())),
 				}
@@ -106,6 +111,17 @@ impl Transaction {
 		// Continue
 		Ok(())
 	}
+	// Fetch the current time from the timestamp oracle of the cluster
+	pub async fn timestamp(&mut self) -> Result<u64, Error> {
+		// Check to see if transaction is closed
+		if self.ok {
+			return Err(Error::TxFinished);
+		}
+		// Get the timestamp
+		let ts = self.db.current_timestamp().await?;
+		// The physical time is in milliseconds, and the logical time is below a million
+		Ok(ts.physical as u64 * 1_000_000 + ts.logical as u64)
+	}
 	// Check if a key exists
 	pub async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
 	where
@@ -1,3 +1,5 @@
+use super::clock::Clock;
+use super::clock::Stamped;
 use super::kv::Add;
 use super::kv::Convert;
 use super::Key;
@@ -13,6 +15,7 @@ use sql::permission::Permissions;
 use sql::statements::DefineDatabaseStatement;
 use sql::statements::DefineEventStatement;
 use sql::statements::DefineFieldStatement;
+use sql::statements::DefineFunctionStatement;
 use sql::statements::DefineIndexStatement;
 use sql::statements::DefineLoginStatement;
 use sql::statements::DefineNamespaceStatement;
@@ -27,6 +30,9 @@ use std::sync::Arc;
 pub struct Transaction {
 	pub(super) inner: Inner,
 	pub(super) cache: Cache,
+	pub(super) log: Option<Vec<(Key, Option<Val>)>>,
+	pub(super) clock: Clock,
+	pub(super) stamped: Vec<(Stamped, Val)>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -44,6 +50,12 @@ pub(super) enum Inner {
 }
 
 impl Transaction {
+	// Add a successful change to the change log
+	fn record(&mut self, res: &Result<(), Error>, chg: Option<(Key, Option<Val>)>) {
+		if let (Ok(_), Some(log), Some(chg)) = (res, self.log.as_mut(), chg) {
+			log.push(chg);
+		}
+	}
 	/// Check if transactions is finished.
 	///
 	/// If the transaction has been cancelled or committed,
@@ -115,6 +127,30 @@ impl Transaction {
 	///
 	/// This attempts to commit all changes made within the transaction.
 	pub async fn commit(&mut self) -> Result<(), Error> {
+		// Check if there are changes for the change log
+		let changed = matches!(self.log, Some(ref v) if !v.is_empty());
+		// Hold the clock until the transaction is committed
+		let clock = self.clock.clone();
+		let mut last = match changed || !self.stamped.is_empty() {
+			true => Some(clock.lock().await),
+			false => None,
+		};
+		if let Some(last) = last.as_mut() {
+			// The changes are complete
+			let log = self.log.take();
+			// Assign the version stamp
+			let vs = self.version(last).await?;
+			// Write the keys which are ordered by commit
+			for (i, (key, val)) in std::mem::take(&mut self.stamped).into_iter().enumerate() {
+				self.set(key(vs, i as u32), val).await?;
+			}
+			// Write the changes to the change log
+			if let Some(log) = log {
+				for (key, val) in super::backup::changes(vs, log) {
+					self.set(key, val).await?;
+				}
+			}
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -143,12 +179,95 @@ impl Transaction {
 			} => v.commit().await,
 		}
 	}
+	/// Assign the version stamp of this transaction, after the last version stamp.
+	///
+	/// Distributed storage engines store the last version stamp in the
+	/// datastore, so that the stamps are ordered across every node.
+	async fn version(&mut self, last: &mut u64) -> Result<u64, Error> {
+		let time = match self {
+			#[cfg(feature = "kv-mem")]
+			Transaction {
+				inner: Inner::Mem(_),
+				..
+			} => None,
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(_),
+				..
+			} => None,
+			#[cfg(feature = "kv-indxdb")]
+			Transaction {
+				inner: Inner::IndxDB(_),
+				..
+			} => None,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(v),
+				..
+			} => Some(v.timestamp().await?),
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FDB(_),
+				..
+			} => Some(super::clock::now()),
+		};
+		match time {
+			// Follow the version stamp which is stored in the datastore
+			Some(time) => {
+				let vs = super::clock::after(time, self.stored().await?);
+				self.set(crate::key::vs::new(), vs.to_be_bytes().to_vec()).await?;
+				Ok(vs)
+			}
+			// Follow the version stamp of the datastore clock
+			None => Ok(super::clock::next(last)),
+		}
+	}
+	/// Fetch the last version stamp which is contained within the snapshot of this transaction.
+	pub(super) async fn snapshot(&mut self, last: &mut u64) -> Result<u64, Error> {
+		match self {
+			#[cfg(feature = "kv-mem")]
+			Transaction {
+				inner: Inner::Mem(_),
+				..
+			} => Ok(super::clock::next(last)),
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(_),
+				..
+			} => Ok(super::clock::next(last)),
+			#[cfg(feature = "kv-indxdb")]
+			Transaction {
+				inner: Inner::IndxDB(_),
+				..
+			} => Ok(super::clock::next(last)),
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(_),
+				..
+			} => self.stored().await,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FDB(_),
+				..
+			} => self.stored().await,
+		}
+	}
+	/// Fetch the last version stamp which is stored in the datastore.
+	async fn stored(&mut self) -> Result<u64, Error> {
+		match self.get(crate::key::vs::new()).await? {
+			Some(v) => stamp(&v),
+			None => Ok(0),
+		}
+	}
 	/// Delete a key from the datastore.
 	pub async fn del<K>(&mut self, key: K) -> Result<(), Error>
 	where
 		K: Into<Key>,
 	{
-		match self {
+		let key: Key = key.into();
+		// Keep a copy of the change for the change log
+		let chg = self.log.as_ref().map(|_| (key.clone(), None));
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -174,7 +293,10 @@ impl Transaction {
 				inner: Inner::FDB(v),
 				..
 			} => v.del(key).await,
-		}
+		};
+		// Record the change in the change log
+		self.record(&res, chg);
+		res
 	}
 	/// Check if a key exists in the datastore.
 	pub async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
@@ -248,7 +370,11 @@ impl Transaction {
 		K: Into<Key>,
 		V: Into<Val>,
 	{
-		match self {
+		let key: Key = key.into();
+		let val: Val = val.into();
+		// Keep a copy of the change for the change log
+		let chg = self.log.as_ref().map(|_| (key.clone(), Some(val.clone())));
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -274,7 +400,23 @@ impl Transaction {
 				inner: Inner::FDB(v),
 				..
 			} => v.set(key, val).await,
-		}
+		};
+		// Record the change in the change log
+		self.record(&res, chg);
+		res
+	}
+	/// Insert a key which is completed with the version stamp of the transaction.
+	///
+	/// The key is written when the transaction is committed, and the
+	/// version stamps of the transactions are in commit order. The key
+	/// is also given its position amongst the stamped keys of the
+	/// transaction, so that keys with the same stamp can be ordered.
+	pub fn stamp<F, V>(&mut self, key: F, val: V)
+	where
+		F: FnOnce(u64, u32) -> Key + Send + 'static,
+		V: Into<Val>,
+	{
+		self.stamped.push((Box::new(key), val.into()));
 	}
 	/// Insert a key if it doesn't exist in the datastore.
 	pub async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
@@ -282,7 +424,11 @@ impl Transaction {
 		K: Into<Key>,
 		V: Into<Val>,
 	{
-		match self {
+		let key: Key = key.into();
+		let val: Val = val.into();
+		// Keep a copy of the change for the change log
+		let chg = self.log.as_ref().map(|_| (key.clone(), Some(val.clone())));
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -308,7 +454,10 @@ impl Transaction {
 				inner: Inner::FDB(v),
 				..
 			} => v.put(key, val).await,
-		}
+		};
+		// Record the change in the change log
+		self.record(&res, chg);
+		res
 	}
 	/// Retrieve a specific range of keys from the datastore.
 	///
@@ -351,7 +500,12 @@ impl Transaction {
 		K: Into<Key>,
 		V: Into<Val>,
 	{
-		match self {
+		let key: Key = key.into();
+		let val: Val = val.into();
+		let chk: Option<Val> = chk.map(Into::into);
+		// Keep a copy of the change for the change log
+		let chg = self.log.as_ref().map(|_| (key.clone(), Some(val.clone())));
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -377,7 +531,10 @@ impl Transaction {
 				inner: Inner::FDB(v),
 				..
 			} => v.putc(key, val, chk).await,
-		}
+		};
+		// Record the change in the change log
+		self.record(&res, chg);
+		res
 	}
 	/// Delete a key from the datastore if the current value matches a condition.
 	pub async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
@@ -385,7 +542,11 @@ impl Transaction {
 		K: Into<Key>,
 		V: Into<Val>,
 	{
-		match self {
+		let key: Key = key.into();
+		let chk: Option<Val> = chk.map(Into::into);
+		// Keep a copy of the change for the change log
+		let chg = self.log.as_ref().map(|_| (key.clone(), None));
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -411,7 +572,10 @@ impl Transaction {
 				inner: Inner::FDB(v),
 				..
 			} => v.delc(key, chk).await,
-		}
+		};
+		// Record the change in the change log
+		self.record(&res, chg);
+		res
 	}
 	/// Retrieve a specific range of keys from the datastore.
 	///
@@ -727,6 +891,28 @@ impl Transaction {
 			}
 		}
 	}
+	/// Retrieve all function definitions for a specific database.
+	pub async fn all_fc(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<Vec<DefineFunctionStatement>>, Error> {
+		let key = crate::key::fc::prefix(ns, db);
+		match self.cache.exi(&key) {
+			true => match self.cache.get(&key) {
+				Some(Entry::Fcs(v)) => Ok(v),
+				_ => unreachable!(),
+			},
+			_ => {
+				let beg = crate::key::fc::prefix(ns, db);
+				let end = crate::key::fc::suffix(ns, db);
+				let val = self.getr(beg..end, u32::MAX).await?;
+				let val = Arc::new(val.convert());
+				self.cache.set(key, Entry::Fcs(val.clone()));
+				Ok(val)
+			}
+		}
+	}
 	/// Retrieve all scope definitions for a specific database.
 	pub async fn all_sc(
 		&mut self,
@@ -955,6 +1141,19 @@ impl Transaction {
 		let val = self.get(key).await?.ok_or(Error::DtNotFound)?;
 		Ok(val.into())
 	}
+	/// Retrieve a specific function definition.
+	pub async fn get_fc(
+		&mut self,
+		ns: &str,
+		db: &str,
+		fc: &str,
+	) -> Result<DefineFunctionStatement, Error> {
+		let key = crate::key::fc::new(ns, db, fc);
+		let val = self.get(key).await?.ok_or_else(|| Error::FcNotFound {
+			value: fc.to_owned(),
+		})?;
+		Ok(val.into())
+	}
 	/// Retrieve a specific scope definition.
 	pub async fn get_sc(
 		&mut self,
@@ -989,6 +1188,18 @@ impl Transaction {
 		let val = self.get(key).await?.ok_or(Error::TbNotFound)?;
 		Ok(val.into())
 	}
+	/// Retrieve a specific index definition.
+	pub async fn get_ix(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<DefineIndexStatement, Error> {
+		let key = crate::key::ix::new(ns, db, tb, ix);
+		let val = self.get(key).await?.ok_or(Error::IxNotFound)?;
+		Ok(val.into())
+	}
 	/// Add a namespace with a default configuration, only if we are in dynamic mode.
 	pub async fn add_ns(
 		&mut self,
@@ -1211,6 +1422,87 @@ impl Transaction {
 			}
 		}
 	}
+	/// Retrieve the version of a record as at a version stamp.
+	pub async fn get_version(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: &sql::id::Id,
+		vs: u64,
+	) -> Result<Option<Val>, Error> {
+		// The versions are ordered from newest to oldest
+		let beg = crate::key::history::new(ns, db, tb, id, vs);
+		let end = crate::key::history::new(ns, db, tb, id, 0);
+		// Fetch the latest version up to the version stamp
+		let res = self.scan(beg..end, 1).await?;
+		// Return the version of the record
+		Ok(res.into_iter().next().map(|(_, v)| v))
+	}
+	/// Remove the versions of the records in a table which are older than a version stamp.
+	///
+	/// The latest version of each record before the version stamp is
+	/// kept, as it is the version of the record at that point in time,
+	/// unless the record had been deleted.
+	pub async fn prune_history(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		vs: u64,
+	) -> Result<(), Error> {
+		let beg = crate::key::history::prefix(ns, db, tb);
+		let end = crate::key::history::suffix(ns, db, tb);
+		// Keep track of the last record which was kept
+		let mut cur: Option<sql::id::Id> = None;
+		// Prepare the next holder key
+		let mut nxt: Option<Key> = None;
+		// Loop until no more keys
+		loop {
+			let min = match nxt.take() {
+				Some(mut k) => {
+					k.push(0x00);
+					k
+				}
+				None => beg.clone(),
+			};
+			let res = self.scan(min..end.clone(), 1000).await?;
+			if res.is_empty() {
+				break;
+			}
+			nxt = res.last().map(|(k, _)| k.clone());
+			for (k, v) in res {
+				let key: crate::key::history::History = (&k).into();
+				// Versions within the retention window are kept
+				if key.vs() >= vs {
+					continue;
+				}
+				// The first older version is the record as at the version stamp
+				match cur {
+					Some(ref id) if *id == key.id => self.del(k).await?,
+					_ => {
+						if sql::Value::from(v).is_none() {
+							self.del(k).await?;
+						}
+						cur = Some(key.id);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+	/// Remove the change feed entries of a table which are older than a version stamp.
+	pub async fn prune_changefeed(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		vs: u64,
+	) -> Result<(), Error> {
+		let beg = crate::key::cf::prefix(ns, db, tb);
+		let end = crate::key::cf::new(ns, db, tb, vs, 0);
+		self.delr(beg..end.into(), u32::MAX).await
+	}
 	/// Writes the full database contents as binary SQL.
 	pub async fn export(&mut self, ns: &str, db: &str, chn: Sender<Vec<u8>>) -> Result<(), Error> {
 		// Output OPTIONS
@@ -1250,6 +1542,20 @@ impl Transaction {
 				chn.send(bytes!("")).await?;
 			}
 		}
+		// Output FUNCTIONS
+		{
+			let fcs = self.all_fc(ns, db).await?;
+			if !fcs.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- FUNCTIONS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for fc in fcs.iter() {
+					chn.send(bytes!(format!("{};", fc))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
 		// Output SCOPES
 		{
 			let scs = self.all_sc(ns, db).await?;
@@ -1374,3 +1680,11 @@ impl Transaction {
 		Ok(())
 	}
 }
+
+// Decode a version stamp which is stored in the datastore
+fn stamp(v: &[u8]) -> Result<u64, Error> {
+	match <[u8; 8]>::try_from(v) {
+		Ok(v) => Ok(u64::from_be_bytes(v)),
+		Err(_) => Err(Error::Ds("The stored version stamp is invalid".to_string())),
+	}
+}
@@ -19,6 +19,7 @@ mod dbs;
 mod doc;
 mod err;
 mod fnc;
+mod idx;
 mod key;
 mod kvs;
 
@@ -26,7 +27,12 @@ mod kvs;
 pub mod sql;
 
 // Exports
+pub use dbs::Action;
 pub use dbs::Auth;
+pub use dbs::Chunk;
+pub use dbs::Notification;
+pub use dbs::PreparedQuery;
+pub use dbs::QueryType;
 pub use dbs::Response;
 pub use dbs::Session;
 pub use err::Error;
@@ -36,6 +42,14 @@ pub use kvs::Transaction;
 pub use kvs::Val;
 
 // Re-exports
+pub mod backup {
+	pub use crate::kvs::backup::verify;
+	pub use crate::kvs::backup::Info;
+	pub use crate::kvs::backup::Kind;
+	pub use crate::kvs::backup::MAGIC;
+	pub use crate::kvs::backup::VERSION;
+}
+
 pub mod channel {
 	pub use channel::bounded as new;
 	pub use channel::Receiver;
@@ -0,0 +1,72 @@
+use crate::ctx::Context;
+use crate::dbs::Cursor;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::value::{value, Value};
+use nom::bytes::complete::tag_no_case;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct After(pub Value);
+
+impl After {
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Option<Cursor>, Error> {
+		match self.0.compute(ctx, opt, txn, None).await? {
+			// No cursor starts from the first page
+			Value::None | Value::Null => Ok(None),
+			// Decode the opaque cursor string
+			Value::Strand(v) => Cursor::decode(&v).map(Some),
+			// Anything else is not a cursor
+			v => Err(Error::InvalidCursor {
+				message: format!("Expected a cursor string but found '{}'", v),
+			}),
+		}
+	}
+}
+
+impl fmt::Display for After {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "AFTER {}", self.0)
+	}
+}
+
+pub fn after(i: &str) -> IResult<&str, After> {
+	let (i, _) = tag_no_case("AFTER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = value(i)?;
+	Ok((i, After(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn after_statement_param() {
+		let sql = "AFTER $cursor";
+		let res = after(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("AFTER $cursor", format!("{}", out));
+	}
+
+	#[test]
+	fn after_statement_string() {
+		let sql = "AFTER \"0000000000000000\"";
+		let res = after(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, After(Value::from("0000000000000000")));
+		assert_eq!("AFTER \"0000000000000000\"", format!("{}", out));
+	}
+}
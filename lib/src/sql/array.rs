@@ -250,6 +250,25 @@ impl Concat<Array> for Array {
 
 // ------------------------------
 
+pub trait Flatten<T> {
+	fn flatten(self) -> T;
+}
+
+impl Flatten<Array> for Array {
+	fn flatten(self) -> Array {
+		let mut out = Array::new();
+		for v in self.into_iter() {
+			match v {
+				Value::Array(mut v) => out.append(&mut v),
+				_ => out.push(v),
+			}
+		}
+		out
+	}
+}
+
+// ------------------------------
+
 pub trait Difference<T> {
 	fn difference(self, other: T) -> T;
 }
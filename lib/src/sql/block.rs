@@ -0,0 +1,186 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::mightbespace;
+use crate::sql::common::colons;
+use crate::sql::error::IResult;
+use crate::sql::statements::create::{create, CreateStatement};
+use crate::sql::statements::delete::{delete, DeleteStatement};
+use crate::sql::statements::ifelse::{ifelse, IfelseStatement};
+use crate::sql::statements::insert::{insert, InsertStatement};
+use crate::sql::statements::output::{output, OutputStatement};
+use crate::sql::statements::relate::{relate, RelateStatement};
+use crate::sql::statements::select::{select, SelectStatement};
+use crate::sql::statements::set::{set, SetStatement};
+use crate::sql::statements::update::{update, UpdateStatement};
+use crate::sql::statements::upsert::{upsert, UpsertStatement};
+use crate::sql::value::{value, Value};
+use async_recursion::async_recursion;
+use nom::branch::alt;
+use nom::character::complete::char;
+use nom::combinator::{map, opt};
+use nom::multi::separated_list0;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Block(pub Vec<Entry>);
+
+impl Deref for Block {
+	type Target = Vec<Entry>;
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl Block {
+	#[cfg_attr(feature = "parallel", async_recursion)]
+	#[cfg_attr(not(feature = "parallel"), async_recursion(?Send))]
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&'async_recursion Value>,
+	) -> Result<Value, Error> {
+		// Duplicate context
+		let mut ctx = Context::new(ctx);
+		// Process each block entry
+		let mut out = Value::None;
+		for v in self.iter() {
+			out = match v {
+				Entry::Set(v) => {
+					let val = v.compute(&ctx, opt, txn, doc).await?;
+					ctx.add_value(v.name.to_owned(), val);
+					Value::None
+				}
+				Entry::Output(v) => return v.compute(&ctx, opt, txn, doc).await,
+				Entry::Ifelse(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Select(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Create(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Update(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Delete(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Relate(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Insert(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Upsert(v) => v.compute(&ctx, opt, txn, doc).await?,
+				Entry::Value(v) => v.compute(&ctx, opt, txn, doc).await?,
+			};
+		}
+		// Return the last value
+		Ok(out)
+	}
+}
+
+impl fmt::Display for Block {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.is_empty() {
+			true => write!(f, "{{}}"),
+			false => write!(
+				f,
+				"{{ {}; }}",
+				self.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("; ")
+			),
+		}
+	}
+}
+
+pub fn block(i: &str) -> IResult<&str, Block> {
+	let (i, _) = char('{')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, v) = separated_list0(colons, entry)(i)?;
+	let (i, _) = opt(colons)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('}')(i)?;
+	Ok((i, Block(v)))
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Entry {
+	Set(SetStatement),
+	Output(OutputStatement),
+	Ifelse(IfelseStatement),
+	Select(SelectStatement),
+	Create(CreateStatement),
+	Update(UpdateStatement),
+	Delete(DeleteStatement),
+	Relate(RelateStatement),
+	Insert(InsertStatement),
+	Upsert(UpsertStatement),
+	Value(Value),
+}
+
+impl fmt::Display for Entry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Entry::Set(v) => write!(f, "{}", v),
+			Entry::Output(v) => write!(f, "{}", v),
+			Entry::Ifelse(v) => write!(f, "{}", v),
+			Entry::Select(v) => write!(f, "{}", v),
+			Entry::Create(v) => write!(f, "{}", v),
+			Entry::Update(v) => write!(f, "{}", v),
+			Entry::Delete(v) => write!(f, "{}", v),
+			Entry::Relate(v) => write!(f, "{}", v),
+			Entry::Insert(v) => write!(f, "{}", v),
+			Entry::Upsert(v) => write!(f, "{}", v),
+			Entry::Value(v) => write!(f, "{}", v),
+		}
+	}
+}
+
+fn entry(i: &str) -> IResult<&str, Entry> {
+	alt((
+		map(set, Entry::Set),
+		map(output, Entry::Output),
+		map(ifelse, Entry::Ifelse),
+		map(select, Entry::Select),
+		map(create, Entry::Create),
+		map(update, Entry::Update),
+		map(delete, Entry::Delete),
+		map(relate, Entry::Relate),
+		map(insert, Entry::Insert),
+		map(upsert, Entry::Upsert),
+		map(value, Entry::Value),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn block_empty() {
+		let sql = "{}";
+		let res = block(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("{}", format!("{}", out))
+	}
+
+	#[test]
+	fn block_value() {
+		let sql = "{ 80 }";
+		let res = block(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("{ 80; }", format!("{}", out))
+	}
+
+	#[test]
+	fn block_statements() {
+		let sql = "{
+			LET $person = (SELECT * FROM person WHERE first = $first);
+			CREATE person SET first = $first;
+			RETURN $person;
+		}";
+		let res = block(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"{ LET $person = (SELECT * FROM person WHERE first = $first); CREATE person SET first = $first; RETURN $person; }",
+			format!("{}", out)
+		)
+	}
+}
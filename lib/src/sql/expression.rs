@@ -3,8 +3,11 @@ use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::fnc;
+use crate::idx::search;
 use crate::sql::error::IResult;
+use crate::sql::index::{Index, Search};
 use crate::sql::operator::{operator, Operator};
+use crate::sql::paths::ID;
 use crate::sql::value::{single, value, Value};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -64,6 +67,10 @@ impl Expression {
 		txn: &Transaction,
 		doc: Option<&Value>,
 	) -> Result<Value, Error> {
+		// Full-text matches are analyzed differently
+		if let Operator::Matches = self.o {
+			return self.matches(ctx, opt, txn, doc).await;
+		}
 		let l = self.l.compute(ctx, opt, txn, doc).await?;
 		match self.o {
 			Operator::Or => {
@@ -114,6 +121,35 @@ impl Expression {
 			_ => unreachable!(),
 		}
 	}
+	// Check if a field contains every term of a full-text query
+	async fn matches(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		let l = self.l.compute(ctx, opt, txn, doc).await?;
+		let r = self.r.compute(ctx, opt, txn, doc).await?;
+		// Use the analyzer of a search index on the field
+		let mut sc = Search::default();
+		if let (Value::Idiom(f), Some(doc)) = (&self.l, doc) {
+			if let (Value::Thing(rid), Some(_), Some(_)) = (doc.pick(&*ID), &opt.ns, &opt.db) {
+				let ixs = txn.clone().lock().await.all_ix(opt.ns(), opt.db(), &rid.tb).await?;
+				if let Some(v) = ixs.iter().find_map(|ix| match &ix.index {
+					Index::Search(v) if ix.cols.len() == 1 && &ix.cols[0] == f => Some(v),
+					_ => None,
+				}) {
+					sc = v.clone();
+				}
+			}
+		}
+		// Check the value against the query terms
+		match r {
+			Value::Strand(r) => Ok(search::matches(&sc, &l, r.as_str()).into()),
+			_ => Ok(Value::False),
+		}
+	}
 }
 
 impl fmt::Display for Expression {
@@ -200,4 +236,14 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("(3 * 3 * 3) = (3 * 3 * 3)", format!("{}", out));
 	}
+
+	#[test]
+	fn expression_matches() {
+		let sql = "description @@ \"red shoes\" AND price < 100";
+		let res = expression(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("description @@ \"red shoes\" AND price < 100", format!("{}", out));
+		assert_eq!(out.o, Operator::And);
+	}
 }
@@ -5,12 +5,16 @@ use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
 use crate::sql::error::IResult;
+use crate::sql::function::Function;
 use crate::sql::idiom::{idiom, Idiom};
 use crate::sql::part::Part;
 use crate::sql::value::{value, Value};
+use crate::sql::window::{window, windowed, Window};
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
+use nom::combinator::opt;
 use nom::multi::separated_list1;
+use nom::sequence::{preceded, tuple};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::ops::Deref;
@@ -25,12 +29,24 @@ impl Fields {
 	pub fn other(&self) -> impl Iterator<Item = &Field> {
 		self.0.iter().filter(|v| !matches!(v, Field::All))
 	}
+	pub fn windowed(&self) -> bool {
+		self.0.iter().any(|v| matches!(v, Field::Window(..)))
+	}
+	pub fn windows(&self) -> impl Iterator<Item = (&Function, &Window, Idiom)> {
+		self.0.iter().filter_map(|v| match v {
+			Field::Window(f, w, Some(i)) => Some((f, w, i.to_owned())),
+			Field::Window(f, w, None) => Some((f, w, Value::from(f.to_owned()).to_idiom())),
+			_ => None,
+		})
+	}
 	pub fn single(&self) -> Option<Idiom> {
 		match self.0.len() {
 			1 => match self.0.first() {
 				Some(Field::All) => None,
 				Some(Field::Alone(e)) => Some(e.to_idiom()),
 				Some(Field::Alias(_, i)) => Some(i.to_owned()),
+				Some(Field::Window(f, _, None)) => Some(Value::from(f.to_owned()).to_idiom()),
+				Some(Field::Window(_, _, Some(i))) => Some(i.to_owned()),
 				_ => None,
 			},
 			_ => None,
@@ -179,6 +195,24 @@ impl Fields {
 						out.set(ctx, opt, txn, i, x).await?;
 					}
 				},
+				// This expression is a window function
+				Field::Window(f, _, a) => {
+					let x = match f.args().first() {
+						// If arguments, then pass the first value through
+						Some(v) => v.compute(ctx, opt, txn, Some(doc)).await?,
+						// If an aggregate, then compute the result
+						None if f.is_aggregate() => f.compute(ctx, opt, txn, Some(doc)).await?,
+						// Otherwise the function needs no input
+						None => Value::None,
+					};
+					match a {
+						Some(i) => out.set(ctx, opt, txn, i, x).await?,
+						None => {
+							let i = Value::from(f.to_owned()).to_idiom();
+							out.set(ctx, opt, txn, &i, x).await?
+						}
+					}
+				}
 			}
 		}
 		Ok(out)
@@ -195,6 +229,7 @@ pub enum Field {
 	All,
 	Alone(Value),
 	Alias(Value, Idiom),
+	Window(Function, Window, Option<Idiom>),
 }
 
 impl Default for Field {
@@ -209,12 +244,14 @@ impl fmt::Display for Field {
 			Field::All => write!(f, "*"),
 			Field::Alone(e) => write!(f, "{}", e),
 			Field::Alias(e, a) => write!(f, "{} AS {}", e, a),
+			Field::Window(e, w, None) => write!(f, "{} {}", e, w),
+			Field::Window(e, w, Some(a)) => write!(f, "{} {} AS {}", e, w, a),
 		}
 	}
 }
 
 pub fn field(i: &str) -> IResult<&str, Field> {
-	alt((all, alias, alone))(i)
+	alt((all, over, alias, alone))(i)
 }
 
 pub fn all(i: &str) -> IResult<&str, Field> {
@@ -227,6 +264,14 @@ pub fn alone(i: &str) -> IResult<&str, Field> {
 	Ok((i, Field::Alone(f)))
 }
 
+pub fn over(i: &str) -> IResult<&str, Field> {
+	let (i, f) = windowed(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, w) = window(i)?;
+	let (i, a) = opt(preceded(tuple((shouldbespace, tag_no_case("AS"), shouldbespace)), idiom))(i)?;
+	Ok((i, Field::Window(f, w, a)))
+}
+
 pub fn alias(i: &str) -> IResult<&str, Field> {
 	let (i, f) = value(i)?;
 	let (i, _) = shouldbespace(i)?;
@@ -276,4 +321,21 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("field AS one, other.field AS two", format!("{}", out));
 	}
+
+	#[test]
+	fn field_windows() {
+		let sql = "row_number() OVER (ORDER BY time), math::sum(amount) OVER (PARTITION BY account) AS total";
+		let res = fields(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"row_number() OVER (ORDER BY time), math::sum(amount) OVER (PARTITION BY account) AS total",
+			format!("{}", out)
+		);
+		assert!(out.windowed());
+		assert_eq!(
+			out.windows().map(|(_, _, i)| i.to_string()).collect::<Vec<_>>(),
+			vec!["row_number", "total"]
+		);
+	}
 }
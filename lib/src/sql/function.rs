@@ -4,14 +4,16 @@ use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::fnc;
 use crate::sql::comment::mightbespace;
-use crate::sql::common::commas;
+use crate::sql::common::{commas, val_char};
 use crate::sql::error::IResult;
+use crate::sql::permission::Permission;
 use crate::sql::script::{script as func, Script};
 use crate::sql::value::{single, value, Value};
 use nom::branch::alt;
-use nom::bytes::complete::tag;
+use nom::bytes::complete::{tag, take_while1};
 use nom::character::complete::char;
-use nom::multi::separated_list0;
+use nom::combinator::recognize;
+use nom::multi::{separated_list0, separated_list1};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
@@ -21,6 +23,7 @@ pub enum Function {
 	Future(Value),
 	Cast(String, Value),
 	Normal(String, Vec<Value>),
+	Custom(String, Vec<Value>),
 	Script(Script, Vec<Value>),
 }
 
@@ -43,9 +46,14 @@ impl Function {
 	pub fn args(&self) -> &[Value] {
 		match self {
 			Function::Normal(_, a) => a,
+			Function::Custom(_, a) => a,
 			_ => &[],
 		}
 	}
+	// Check if this function is a user-defined function
+	pub fn is_custom(&self) -> bool {
+		matches!(self, Function::Custom(_, _))
+	}
 	// Convert this function to an aggregate
 	pub fn aggregate(&self, val: Value) -> Function {
 		match self {
@@ -66,11 +74,15 @@ impl Function {
 	// Check if this function is a rolling function
 	pub fn is_rolling(&self) -> bool {
 		match self {
+			Function::Normal(f, _) if f == "array::distinct" => true,
+			Function::Normal(f, _) if f == "array::group" => true,
 			Function::Normal(f, _) if f == "count" => true,
 			Function::Normal(f, _) if f == "math::max" => true,
 			Function::Normal(f, _) if f == "math::mean" => true,
 			Function::Normal(f, _) if f == "math::min" => true,
+			Function::Normal(f, _) if f == "math::stddev" => true,
 			Function::Normal(f, _) if f == "math::sum" => true,
+			Function::Normal(f, _) if f == "math::variance" => true,
 			_ => false,
 		}
 	}
@@ -79,6 +91,7 @@ impl Function {
 		match self {
 			Function::Normal(f, _) if f == "array::concat" => true,
 			Function::Normal(f, _) if f == "array::distinct" => true,
+			Function::Normal(f, _) if f == "array::group" => true,
 			Function::Normal(f, _) if f == "array::union" => true,
 			Function::Normal(f, _) if f == "count" => true,
 			Function::Normal(f, _) if f == "math::bottom" => true,
@@ -128,7 +141,64 @@ impl Function {
 				for v in x {
 					a.push(v.compute(ctx, opt, txn, doc).await?);
 				}
-				fnc::run(ctx, s, a).await
+				fnc::run(ctx, opt, txn, doc, s, a).await
+			}
+			Function::Custom(s, x) => {
+				// Get the function definition
+				let val = {
+					// Clone transaction
+					let run = txn.clone();
+					// Claim transaction
+					let mut run = run.lock().await;
+					// Get the function definition
+					run.get_fc(opt.ns(), opt.db(), s).await?
+				};
+				// Check permissions
+				if opt.perms && opt.auth.perms() {
+					match &val.permissions {
+						Permission::Full => (),
+						Permission::None => {
+							return Err(Error::FunctionPermissions {
+								name: s.to_owned(),
+							})
+						}
+						Permission::Specific(e) => {
+							// Disable permissions
+							let opt = &opt.perms(false);
+							// Process the PERMISSION clause
+							if !e.compute(ctx, opt, txn, doc).await?.is_truthy() {
+								return Err(Error::FunctionPermissions {
+									name: s.to_owned(),
+								});
+							}
+						}
+					}
+				}
+				// Check the function arguments
+				if x.len() != val.args.len() {
+					return Err(Error::InvalidArguments {
+						name: format!("fn::{}", val.name.0),
+						message: match val.args.len() {
+							1 => String::from("The function expects 1 argument."),
+							l => format!("The function expects {} arguments.", l),
+						},
+					});
+				}
+				// Compute the function arguments
+				let mut a: Vec<Value> = Vec::with_capacity(x.len());
+				for v in x {
+					a.push(v.compute(ctx, opt, txn, doc).await?);
+				}
+				// Duplicate options
+				let opt = opt.dive()?;
+				// Duplicate context
+				let mut ctx = Context::new(ctx);
+				// Add the function arguments
+				for (v, (name, kind)) in a.into_iter().zip(val.args.iter()) {
+					ctx.add_value(name.0.to_owned(), v.convert_to(kind));
+				}
+				// Run the function block
+				val.block.compute(&ctx, &opt, txn, doc).await
 			}
 			#[allow(unused_variables)]
 			Function::Script(s, x) => {
@@ -168,12 +238,22 @@ impl fmt::Display for Function {
 				s,
 				e.iter().map(|ref v| format!("{}", v)).collect::<Vec<_>>().join(", ")
 			),
+			Function::Custom(ref s, ref e) => write!(
+				f,
+				"fn::{}({})",
+				s,
+				e.iter().map(|ref v| format!("{}", v)).collect::<Vec<_>>().join(", ")
+			),
 		}
 	}
 }
 
 pub fn function(i: &str) -> IResult<&str, Function> {
-	alt((normal, script, future, cast))(i)
+	alt((normal, script, custom, future, cast))(i)
+}
+
+pub fn function_name(i: &str) -> IResult<&str, &str> {
+	recognize(separated_list1(tag("::"), take_while1(val_char)))(i)
 }
 
 fn normal(i: &str) -> IResult<&str, Function> {
@@ -199,6 +279,17 @@ fn script(i: &str) -> IResult<&str, Function> {
 	Ok((i, Function::Script(v, a)))
 }
 
+fn custom(i: &str) -> IResult<&str, Function> {
+	let (i, _) = tag("fn::")(i)?;
+	let (i, s) = function_name(i)?;
+	let (i, _) = char('(')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, a) = separated_list0(commas, value)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char(')')(i)?;
+	Ok((i, Function::Custom(s.to_string(), a)))
+}
+
 fn future(i: &str) -> IResult<&str, Function> {
 	let (i, _) = char('<')(i)?;
 	let (i, _) = tag("future")(i)?;
@@ -240,11 +331,13 @@ fn function_names(i: &str) -> IResult<&str, &str> {
 		function_count,
 		function_crypto,
 		function_geo,
+		function_graph,
 		function_http,
 		function_is,
 		function_math,
 		function_parse,
 		function_rand,
+		function_search,
 		function_session,
 		function_string,
 		function_time,
@@ -258,6 +351,7 @@ fn function_array(i: &str) -> IResult<&str, &str> {
 		tag("array::concat"),
 		tag("array::difference"),
 		tag("array::distinct"),
+		tag("array::group"),
 		tag("array::intersect"),
 		tag("array::len"),
 		tag("array::sort::asc"),
@@ -297,6 +391,16 @@ fn function_geo(i: &str) -> IResult<&str, &str> {
 	))(i)
 }
 
+fn function_graph(i: &str) -> IResult<&str, &str> {
+	alt((
+		tag("graph::connected_components"),
+		tag("graph::degree"),
+		tag("graph::neighbours"),
+		tag("graph::pagerank"),
+		tag("graph::shortest_path"),
+	))(i)
+}
+
 fn function_http(i: &str) -> IResult<&str, &str> {
 	alt((
 		tag("http::head"),
@@ -385,6 +489,10 @@ fn function_rand(i: &str) -> IResult<&str, &str> {
 	))(i)
 }
 
+fn function_search(i: &str) -> IResult<&str, &str> {
+	alt((tag("search::highlight"), tag("search::score")))(i)
+}
+
 fn function_session(i: &str) -> IResult<&str, &str> {
 	alt((
 		tag("session::db"),
@@ -491,6 +599,19 @@ mod tests {
 		assert_eq!(out, Function::Normal(String::from("is::numeric"), vec![Value::Null]));
 	}
 
+	#[test]
+	fn function_custom() {
+		let sql = "fn::foo::bar(1, $test)";
+		let res = function(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("fn::foo::bar(1, $test)", format!("{}", out));
+		assert_eq!(
+			out,
+			Function::Custom(String::from("foo::bar"), vec![1.into(), Value::parse("$test")])
+		);
+	}
+
 	#[test]
 	fn function_casting_number() {
 		let sql = "<int>1.2345";
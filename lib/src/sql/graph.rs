@@ -1,16 +1,31 @@
+use crate::cnf::{MAX_GRAPH_DEPTH, MAX_GRAPH_PATHS};
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
 use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
+use crate::sql::common::take_u32;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::dir::{dir, Dir};
+use crate::sql::edges::Edges;
 use crate::sql::error::IResult;
+use crate::sql::field::{Field, Fields};
 use crate::sql::idiom::{idiom, Idiom};
+use crate::sql::statements::select::SelectStatement;
 use crate::sql::table::{table, tables, Tables};
+use crate::sql::thing::Thing;
+use crate::sql::value::{Value, Values};
 use nom::branch::alt;
+use nom::bytes::complete::tag;
 use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
 use nom::combinator::map;
 use nom::combinator::opt;
+use nom::combinator::verify;
+use nom::sequence::preceded;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -19,16 +34,173 @@ pub struct Graph {
 	pub what: Tables,
 	pub cond: Option<Cond>,
 	pub alias: Option<Idiom>,
+	#[serde(default)]
+	pub rec: Option<Box<Recurse>>,
 }
 
 impl Graph {
 	pub fn to_raw(&self) -> String {
 		self.to_string()
 	}
+	// Fetch the records which are one step away from a record
+	async fn step(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		from: Thing,
+	) -> Result<Vec<Thing>, Error> {
+		let stm = SelectStatement {
+			expr: Fields(vec![Field::All]),
+			what: Values(vec![Value::from(Edges {
+				from,
+				dir: self.dir.clone(),
+				what: self.what.clone(),
+			})]),
+			cond: self.cond.clone(),
+			..SelectStatement::default()
+		};
+		match stm.compute(ctx, opt, txn, None).await? {
+			Value::Array(v) => Ok(v.into_iter().filter_map(Value::rid).collect()),
+			_ => Ok(vec![]),
+		}
+	}
+	// Fetch the edges and nodes which are one hop away from a record
+	async fn hop(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		rec: &Recurse,
+		from: &Thing,
+	) -> Result<Vec<(Thing, Thing)>, Error> {
+		let mut out = Vec::new();
+		for e in self.step(ctx, opt, txn, from.clone()).await? {
+			for n in rec.node.step(ctx, opt, txn, e.clone()).await? {
+				out.push((e.clone(), n));
+			}
+		}
+		Ok(out)
+	}
+	// Recursively traverse the graph from a record
+	pub(crate) async fn traverse(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		from: &Thing,
+	) -> Result<Value, Error> {
+		// Get the recursion bounds
+		let rec = match &self.rec {
+			Some(rec) => rec,
+			None => return Ok(Value::None),
+		};
+		// Store the traversal output
+		let mut out: Vec<Value> = Vec::new();
+		match rec.paths {
+			// Walk each simple path in depth-first order
+			Paths::All => {
+				// Each record is only fetched once
+				let mut hops: HashMap<String, Vec<(Thing, Thing)>> = HashMap::new();
+				let mut stack = vec![(from.clone(), vec![Value::from(from.clone())])];
+				let mut count = 0;
+				while let Some((v, path)) = stack.pop() {
+					// Check if the context is finished
+					ctx.check()?;
+					// Check the number of visited paths
+					count += 1;
+					if count > MAX_GRAPH_PATHS {
+						return Err(Error::TooManyGraphPaths {
+							limit: MAX_GRAPH_PATHS,
+						});
+					}
+					// Output paths within the bounds
+					let depth = path.len() / 2;
+					if depth >= rec.min as usize {
+						out.push(Value::from(path.clone()));
+					}
+					// Stop at the maximum depth
+					if depth >= rec.max as usize {
+						continue;
+					}
+					let key = v.to_raw();
+					if !hops.contains_key(&key) {
+						let res = self.hop(ctx, opt, txn, rec, &v).await?;
+						hops.insert(key.clone(), res);
+					}
+					let mut next = Vec::new();
+					for (e, n) in hops[&key].iter() {
+						// Skip nodes which are already on this path
+						let node = Value::from(n.clone());
+						if path.iter().step_by(2).any(|v| v == &node) {
+							continue;
+						}
+						let mut path = path.clone();
+						path.push(Value::from(e.clone()));
+						path.push(node);
+						next.push((n.clone(), path));
+					}
+					// Visit the first neighbour first
+					stack.extend(next.into_iter().rev());
+				}
+			}
+			// Walk the graph in breadth-first order
+			_ => {
+				let mut seen = HashSet::new();
+				seen.insert(from.to_raw());
+				let mut frontier = vec![(from.clone(), vec![Value::from(from.clone())])];
+				for depth in 1..=rec.max {
+					let mut next = Vec::new();
+					for (v, path) in frontier.into_iter() {
+						// Check if the context is finished
+						ctx.check()?;
+						for (e, n) in self.hop(ctx, opt, txn, rec, &v).await? {
+							// Skip nodes which have already been reached
+							if !seen.insert(n.to_raw()) {
+								continue;
+							}
+							// Check the number of visited paths
+							if seen.len() > MAX_GRAPH_PATHS {
+								return Err(Error::TooManyGraphPaths {
+									limit: MAX_GRAPH_PATHS,
+								});
+							}
+							let mut path = path.clone();
+							path.push(Value::from(e));
+							path.push(Value::from(n.clone()));
+							if depth >= rec.min {
+								out.push(match rec.paths {
+									Paths::Shortest => Value::from(path.clone()),
+									_ => Value::from(n.clone()),
+								});
+							}
+							next.push((n, path));
+						}
+					}
+					// Stop when no more nodes were reached
+					if next.is_empty() {
+						break;
+					}
+					frontier = next;
+				}
+			}
+		}
+		Ok(out.into())
+	}
 }
 
 impl fmt::Display for Graph {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.fmt_hop(f)?;
+		if let Some(ref v) = self.rec {
+			write!(f, "{}", v)?
+		}
+		Ok(())
+	}
+}
+
+impl Graph {
+	fn fmt_hop(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		if self.what.0.len() <= 1 && self.cond.is_none() && self.alias.is_none() {
 			write!(f, "{}", self.dir)?;
 			match self.what.len() {
@@ -52,9 +224,41 @@ impl fmt::Display for Graph {
 	}
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Recurse {
+	pub min: u32,
+	pub max: u32,
+	pub paths: Paths,
+	pub node: Graph,
+}
+
+impl fmt::Display for Recurse {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.min == self.max {
+			true => write!(f, "{{{}", self.max)?,
+			false => write!(f, "{{{}..{}", self.min, self.max)?,
+		}
+		match self.paths {
+			Paths::None => write!(f, "}}")?,
+			Paths::Shortest => write!(f, " SHORTEST PATH}}")?,
+			Paths::All => write!(f, " ALL PATHS}}")?,
+		}
+		write!(f, "{}", self.node)
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum Paths {
+	#[default]
+	None,
+	Shortest,
+	All,
+}
+
 pub fn graph(i: &str) -> IResult<&str, Graph> {
 	let (i, dir) = dir(i)?;
 	let (i, (what, cond, alias)) = alt((simple, custom))(i)?;
+	let (i, rec) = opt(recurse)(i)?;
 	Ok((
 		i,
 		Graph {
@@ -62,10 +266,80 @@ pub fn graph(i: &str) -> IResult<&str, Graph> {
 			what,
 			cond,
 			alias,
+			rec: rec.map(Box::new),
+		},
+	))
+}
+
+fn hop(i: &str) -> IResult<&str, Graph> {
+	let (i, dir) = dir(i)?;
+	let (i, (what, cond, alias)) = alt((simple, custom))(i)?;
+	Ok((
+		i,
+		Graph {
+			dir,
+			what,
+			cond,
+			alias,
+			rec: None,
+		},
+	))
+}
+
+fn recurse(i: &str) -> IResult<&str, Recurse> {
+	let (i, _) = char('{')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, (min, max)) =
+		verify(bounds, |(min, max)| *min >= 1 && min <= max && *max <= MAX_GRAPH_DEPTH)(i)?;
+	let (i, paths) = opt(preceded(shouldbespace, paths))(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('}')(i)?;
+	let (i, node) = hop(i)?;
+	Ok((
+		i,
+		Recurse {
+			min,
+			max,
+			paths: paths.unwrap_or_default(),
+			node,
 		},
 	))
 }
 
+fn bounds(i: &str) -> IResult<&str, (u32, u32)> {
+	alt((
+		|i| {
+			let (i, min) = take_u32(i)?;
+			let (i, _) = tag("..")(i)?;
+			let (i, max) = take_u32(i)?;
+			Ok((i, (min, max)))
+		},
+		|i| {
+			let (i, _) = tag("..")(i)?;
+			let (i, max) = take_u32(i)?;
+			Ok((i, (1, max)))
+		},
+		map(take_u32, |v| (v, v)),
+	))(i)
+}
+
+fn paths(i: &str) -> IResult<&str, Paths> {
+	alt((
+		|i| {
+			let (i, _) = tag_no_case("SHORTEST")(i)?;
+			let (i, _) = shouldbespace(i)?;
+			let (i, _) = tag_no_case("PATH")(i)?;
+			Ok((i, Paths::Shortest))
+		},
+		|i| {
+			let (i, _) = tag_no_case("ALL")(i)?;
+			let (i, _) = shouldbespace(i)?;
+			let (i, _) = tag_no_case("PATHS")(i)?;
+			Ok((i, Paths::All))
+		},
+	))(i)
+}
+
 fn simple(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
 	let (i, w) = alt((any, one))(i)?;
 	Ok((i, (w, None, None)))
@@ -168,4 +442,74 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("->(likes, follows WHERE influencer = true AS connections)", format!("{}", out));
 	}
+
+	#[test]
+	fn graph_recurse() {
+		let sql = "->knows{1..5}->person";
+		let res = graph(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("->knows{1..5}->person", format!("{}", out));
+		let rec = out.rec.unwrap();
+		assert_eq!((rec.min, rec.max, rec.paths), (1, 5, Paths::None));
+	}
+
+	#[test]
+	fn graph_recurse_bounds() {
+		let sql = "<-(knows WHERE strength > 5){ ..3 }<-person";
+		let res = graph(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("<-(knows WHERE strength > 5){1..3}<-person", format!("{}", out));
+		let sql = "->knows{2}->(person WHERE active = true)";
+		let res = graph(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("->knows{2}->(person WHERE active = true)", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_recurse_paths() {
+		let sql = "->knows{1..5 shortest path}->person";
+		let res = graph(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("->knows{1..5 SHORTEST PATH}->person", format!("{}", out));
+		let sql = "<->knows{1..3 ALL PATHS}<->?";
+		let res = graph(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("<->knows{1..3 ALL PATHS}<->?", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_recurse_invalid() {
+		assert!(graph("->knows{0..5}->person").unwrap().0 != "");
+		assert!(graph("->knows{5..1}->person").unwrap().0 != "");
+		assert!(graph("->knows{1..100000}->person").unwrap().0 != "");
+	}
+
+	#[test]
+	fn graph_stored_before_recurse() {
+		// A graph part as it was stored before recursion
+		#[derive(Serialize)]
+		struct Stored {
+			dir: Dir,
+			what: Tables,
+			cond: Option<Cond>,
+			alias: Option<Idiom>,
+		}
+		let new = graph("->(likes WHERE strength > 5 AS liked)").unwrap().1;
+		let old = Stored {
+			dir: new.dir.clone(),
+			what: new.what.clone(),
+			cond: new.cond.clone(),
+			alias: new.alias.clone(),
+		};
+		crate::sql::serde::beg_internal_serialization();
+		let val: Vec<u8> = msgpack::to_vec(&old).unwrap();
+		crate::sql::serde::end_internal_serialization();
+		let out: Graph = msgpack::from_slice(&val).unwrap();
+		assert_eq!(out, new);
+	}
 }
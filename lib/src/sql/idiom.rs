@@ -344,12 +344,14 @@ mod tests {
 					what: Table::from("like").into(),
 					cond: None,
 					alias: None,
+					rec: None,
 				}),
 				Part::from(Graph {
 					dir: Dir::Out,
 					what: Table::from("person").into(),
 					cond: None,
 					alias: None,
+					rec: None,
 				}),
 			])
 		);
@@ -0,0 +1,257 @@
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::commas;
+use crate::sql::error::IResult;
+use crate::sql::number::{number, Number};
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::character::complete::char;
+use nom::combinator::{map, opt};
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, tuple};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Index {
+	// A standard (non-unique) index
+	Idx,
+	// A unique index
+	Uniq,
+	// A full-text search index
+	Search(Search),
+	// A geospatial index
+	Spatial,
+}
+
+impl Default for Index {
+	fn default() -> Index {
+		Index::Idx
+	}
+}
+
+impl fmt::Display for Index {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Index::Idx => Ok(()),
+			Index::Uniq => write!(f, "UNIQUE"),
+			Index::Search(v) => write!(f, "{}", v),
+			Index::Spatial => write!(f, "SPATIAL"),
+		}
+	}
+}
+
+pub fn index(i: &str) -> IResult<&str, Index> {
+	alt((
+		map(tag_no_case("UNIQUE"), |_| Index::Uniq),
+		map(tag_no_case("SPATIAL"), |_| Index::Spatial),
+		map(search, Index::Search),
+	))(i)
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Search {
+	pub tokenizers: Vec<Tokenizer>,
+	pub filters: Vec<Filter>,
+	pub k1: Number,
+	pub b: Number,
+}
+
+impl Default for Search {
+	fn default() -> Search {
+		Search {
+			tokenizers: vec![Tokenizer::Blank, Tokenizer::Class],
+			filters: vec![Filter::Lowercase],
+			k1: Number::Float(1.2),
+			b: Number::Float(0.75),
+		}
+	}
+}
+
+impl fmt::Display for Search {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let tokenizers: Vec<String> = self.tokenizers.iter().map(|v| v.to_string()).collect();
+		write!(f, "SEARCH TOKENIZERS {}", tokenizers.join(","))?;
+		if !self.filters.is_empty() {
+			let filters: Vec<String> = self.filters.iter().map(|v| v.to_string()).collect();
+			write!(f, " FILTERS {}", filters.join(","))?;
+		}
+		write!(f, " BM25({},{})", self.k1, self.b)
+	}
+}
+
+pub fn search(i: &str) -> IResult<&str, Search> {
+	let (i, _) = tag_no_case("SEARCH")(i)?;
+	let (i, tokenizers) = opt(preceded(shouldbespace, search_tokenizers))(i)?;
+	let (i, filters) = opt(preceded(shouldbespace, search_filters))(i)?;
+	let (i, bm25) = opt(preceded(shouldbespace, search_bm25))(i)?;
+	let def = Search::default();
+	let (k1, b) = bm25.unwrap_or((def.k1, def.b));
+	Ok((
+		i,
+		Search {
+			tokenizers: tokenizers.unwrap_or(def.tokenizers),
+			filters: filters.unwrap_or(def.filters),
+			k1,
+			b,
+		},
+	))
+}
+
+fn search_tokenizers(i: &str) -> IResult<&str, Vec<Tokenizer>> {
+	let (i, _) = tag_no_case("TOKENIZERS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	separated_list1(commas, tokenizer)(i)
+}
+
+fn search_filters(i: &str) -> IResult<&str, Vec<Filter>> {
+	let (i, _) = tag_no_case("FILTERS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	separated_list1(commas, filter)(i)
+}
+
+fn search_bm25(i: &str) -> IResult<&str, (Number, Number)> {
+	let (i, _) = tag_no_case("BM25")(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('(')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, k1) = number(i)?;
+	let (i, _) = commas(i)?;
+	let (i, b) = number(i)?;
+	let (i, _) = tuple((mightbespace, char(')')))(i)?;
+	Ok((i, (k1, b)))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Tokenizer {
+	// Split the text on whitespace
+	Blank,
+	// Split the text where a lowercase letter is followed by an uppercase letter
+	Camel,
+	// Split the text where the character class changes between letters, digits, and others
+	Class,
+	// Split the text on punctuation
+	Punct,
+}
+
+impl fmt::Display for Tokenizer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Tokenizer::Blank => write!(f, "blank"),
+			Tokenizer::Camel => write!(f, "camel"),
+			Tokenizer::Class => write!(f, "class"),
+			Tokenizer::Punct => write!(f, "punct"),
+		}
+	}
+}
+
+pub fn tokenizer(i: &str) -> IResult<&str, Tokenizer> {
+	alt((
+		map(tag_no_case("blank"), |_| Tokenizer::Blank),
+		map(tag_no_case("camel"), |_| Tokenizer::Camel),
+		map(tag_no_case("class"), |_| Tokenizer::Class),
+		map(tag_no_case("punct"), |_| Tokenizer::Punct),
+	))(i)
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Filter {
+	// Convert unicode characters to their closest ascii representation
+	Ascii,
+	// Convert the text to lowercase
+	Lowercase,
+	// Reduce english words to their word stem
+	Stem,
+	// Convert the text to uppercase
+	Uppercase,
+}
+
+impl fmt::Display for Filter {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Filter::Ascii => write!(f, "ascii"),
+			Filter::Lowercase => write!(f, "lowercase"),
+			Filter::Stem => write!(f, "stem"),
+			Filter::Uppercase => write!(f, "uppercase"),
+		}
+	}
+}
+
+pub fn filter(i: &str) -> IResult<&str, Filter> {
+	alt((
+		map(tag_no_case("ascii"), |_| Filter::Ascii),
+		map(tag_no_case("lowercase"), |_| Filter::Lowercase),
+		map(tag_no_case("stem"), |_| Filter::Stem),
+		map(tag_no_case("uppercase"), |_| Filter::Uppercase),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn index_unique() {
+		let sql = "UNIQUE";
+		let res = index(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("UNIQUE", format!("{}", out));
+		assert_eq!(out, Index::Uniq);
+	}
+
+	#[test]
+	fn index_spatial() {
+		let sql = "SPATIAL";
+		let res = index(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("SPATIAL", format!("{}", out));
+		assert_eq!(out, Index::Spatial);
+	}
+
+	#[test]
+	fn index_search_default() {
+		let sql = "SEARCH";
+		let res = index(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"SEARCH TOKENIZERS blank,class FILTERS lowercase BM25(1.2,0.75)",
+			format!("{}", out)
+		);
+	}
+
+	#[test]
+	fn index_search_options() {
+		let sql = "SEARCH TOKENIZERS blank, camel FILTERS ascii,lowercase,stem BM25(1.5, 0.5)";
+		let res = index(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"SEARCH TOKENIZERS blank,camel FILTERS ascii,lowercase,stem BM25(1.5,0.5)",
+			format!("{}", out)
+		);
+		assert_eq!(
+			out,
+			Index::Search(Search {
+				tokenizers: vec![Tokenizer::Blank, Tokenizer::Camel],
+				filters: vec![Filter::Ascii, Filter::Lowercase, Filter::Stem],
+				k1: Number::Float(1.5),
+				b: Number::Float(0.5),
+			})
+		);
+	}
+}
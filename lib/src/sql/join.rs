@@ -0,0 +1,208 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::cond::Cond;
+use crate::sql::error::IResult;
+use crate::sql::expression::Expression;
+use crate::sql::field::{Field, Fields};
+use crate::sql::idiom::{basic, idiom, Idiom};
+use crate::sql::operator::Operator;
+use crate::sql::statements::select::SelectStatement;
+use crate::sql::table::{table, Table};
+use crate::sql::value::{Value, Values};
+use async_recursion::async_recursion;
+use nom::bytes::complete::tag_no_case;
+use nom::character::complete::char;
+use nom::combinator::opt;
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, tuple};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+
+// A Join links each output record with the records of another
+// table which have a field matching a field of the output record.
+// The records of the joined table are looked up in a single batch,
+// using an index on the joined field when one is defined, or by
+// scanning the joined table otherwise, and are then grouped into a
+// lookup map. The joined records are nested in the output record as
+// an array.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Joins(pub Vec<Join>);
+
+impl Deref for Joins {
+	type Target = Vec<Join>;
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl IntoIterator for Joins {
+	type Item = Join;
+	type IntoIter = std::vec::IntoIter<Self::Item>;
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+impl fmt::Display for Joins {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.0.iter().map(|ref v| format!("{}", v)).collect::<Vec<_>>().join(" "))
+	}
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Join {
+	pub what: Table,
+	pub local: Idiom,
+	pub remote: Idiom,
+	pub alias: Option<Idiom>,
+}
+
+impl fmt::Display for Join {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "JOIN {} ON {} = {}", self.what, self.local, self.remote)?;
+		if let Some(ref v) = self.alias {
+			write!(f, " AS {}", v)?
+		}
+		Ok(())
+	}
+}
+
+impl Join {
+	// Get the path at which the joined records are output
+	pub fn path(&self) -> Idiom {
+		match &self.alias {
+			Some(v) => v.to_owned(),
+			None => Idiom::from(self.what.0.to_owned()),
+		}
+	}
+	// Link the joined records to each of the output records
+	#[cfg_attr(feature = "parallel", async_recursion)]
+	#[cfg_attr(not(feature = "parallel"), async_recursion(?Send))]
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		res: &mut [Value],
+	) -> Result<(), Error> {
+		// Collect the distinct values which are matched
+		let mut keys: Vec<Value> = res
+			.iter()
+			.map(|v| v.pick(&self.local))
+			.filter(|v| !v.is_none() && !v.is_null())
+			.collect();
+		keys.sort();
+		keys.dedup();
+		// Fetch the records which could match
+		let found = match keys.is_empty() {
+			true => Value::None,
+			false => {
+				let cond = Cond(Value::from(Expression {
+					l: Value::from(self.remote.to_owned()),
+					o: Operator::Inside,
+					r: Value::from(keys),
+				}));
+				// The keys are looked up using an index if possible
+				let stm = SelectStatement {
+					expr: Fields(vec![Field::All]),
+					what: Values(vec![Value::Table(self.what.to_owned())]),
+					cond: Some(cond),
+					..SelectStatement::default()
+				};
+				stm.compute(ctx, opt, txn, None).await?
+			}
+		};
+		// Build a lookup of the joined records by the joined field
+		let mut map: BTreeMap<Value, Vec<Value>> = BTreeMap::new();
+		if let Value::Array(v) = found {
+			for v in v {
+				map.entry(v.pick(&self.remote)).or_default().push(v);
+			}
+		}
+		// Nest the matching records in each output record
+		let path = self.path();
+		for v in res.iter_mut() {
+			let key = v.pick(&self.local);
+			let val = match key.is_none() || key.is_null() {
+				true => vec![],
+				false => map.get(&key).cloned().unwrap_or_default(),
+			};
+			v.set(ctx, opt, txn, &path, Value::from(val)).await?;
+		}
+		Ok(())
+	}
+}
+
+pub fn joins(i: &str) -> IResult<&str, Joins> {
+	let (i, v) = separated_list1(shouldbespace, join)(i)?;
+	Ok((i, Joins(v)))
+}
+
+fn join(i: &str) -> IResult<&str, Join> {
+	let (i, _) = tag_no_case("JOIN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = table(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, local) = basic(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('=')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, remote) = basic(i)?;
+	let (i, alias) =
+		opt(preceded(tuple((shouldbespace, tag_no_case("AS"), shouldbespace)), idiom))(i)?;
+	Ok((
+		i,
+		Join {
+			what,
+			local,
+			remote,
+			alias,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn join_statement() {
+		let sql = "JOIN customer ON customer_email = email";
+		let res = joins(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			Joins(vec![Join {
+				what: Table::from("customer"),
+				local: Idiom::parse("customer_email"),
+				remote: Idiom::parse("email"),
+				alias: None,
+			}])
+		);
+		assert_eq!("JOIN customer ON customer_email = email", format!("{}", out));
+	}
+
+	#[test]
+	fn join_statement_multiple() {
+		let sql = "JOIN customer ON customer_email = email AS buyer JOIN product ON sku=code";
+		let res = joins(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out.len(), 2);
+		assert_eq!(
+			"JOIN customer ON customer_email = email AS buyer JOIN product ON sku = code",
+			format!("{}", out)
+		);
+	}
+}
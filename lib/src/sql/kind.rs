@@ -1,13 +1,20 @@
 use crate::sql::comment::mightbespace;
 use crate::sql::common::commas;
 use crate::sql::error::IResult;
+use crate::sql::escape::escape_key;
+use crate::sql::number::number;
+use crate::sql::object::key;
+use crate::sql::strand::strand;
 use crate::sql::table::{table, Table};
+use crate::sql::value::Value;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::char;
-use nom::combinator::map;
-use nom::multi::separated_list1;
+use nom::combinator::{map, opt};
+use nom::multi::{separated_list0, separated_list1};
+use nom::sequence::tuple;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -25,6 +32,12 @@ pub enum Kind {
 	String,
 	Record(Vec<Table>),
 	Geometry(Vec<String>),
+	Option(Box<Kind>),
+	Set(Box<Kind>),
+	Either(Vec<Kind>),
+	Literal(Value),
+	ArrayOf(Box<Kind>),
+	Schema(BTreeMap<String, Kind>),
 }
 
 impl Default for Kind {
@@ -33,6 +46,18 @@ impl Default for Kind {
 	}
 }
 
+impl Kind {
+	/// Check if this type checks the nested fields of an object
+	pub(crate) fn is_schema(&self) -> bool {
+		match self {
+			Kind::Schema(_) => true,
+			Kind::Option(v) | Kind::Set(v) | Kind::ArrayOf(v) => v.is_schema(),
+			Kind::Either(v) => v.iter().any(Kind::is_schema),
+			_ => false,
+		}
+	}
+}
+
 impl fmt::Display for Kind {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -57,14 +82,46 @@ impl fmt::Display for Kind {
 				"geometry({})",
 				v.iter().map(|ref v| v.to_string()).collect::<Vec<_>>().join(", ")
 			),
+			Kind::Option(v) => write!(f, "option<{}>", v),
+			Kind::Set(v) => match v.as_ref() {
+				Kind::Any => write!(f, "set"),
+				v => write!(f, "set<{}>", v),
+			},
+			Kind::Either(v) => {
+				write!(f, "{}", v.iter().map(|ref v| v.to_string()).collect::<Vec<_>>().join(" | "))
+			}
+			Kind::Literal(v) => write!(f, "{}", v),
+			Kind::ArrayOf(v) => write!(f, "array<{}>", v),
+			Kind::Schema(v) => write!(
+				f,
+				"{{ {} }}",
+				v.iter()
+					.map(|(k, v)| format!("{}: {}", escape_key(k), v))
+					.collect::<Vec<_>>()
+					.join(", ")
+			),
 		}
 	}
 }
 
 pub fn kind(i: &str) -> IResult<&str, Kind> {
+	let (i, mut v) = separated_list1(tuple((mightbespace, char('|'), mightbespace)), single)(i)?;
+	match v.len() {
+		1 => Ok((i, v.remove(0))),
+		_ => Ok((i, Kind::Either(v))),
+	}
+}
+
+fn single(i: &str) -> IResult<&str, Kind> {
 	alt((
 		map(tag("any"), |_| Kind::Any),
-		map(tag("array"), |_| Kind::Array),
+		map(option, |v| Kind::Option(Box::new(v))),
+		map(either, Kind::Either),
+		map(array, |v| match v {
+			Kind::Any => Kind::Array,
+			v => Kind::ArrayOf(Box::new(v)),
+		}),
+		map(set, |v| Kind::Set(Box::new(v))),
 		map(tag("bool"), |_| Kind::Bool),
 		map(tag("datetime"), |_| Kind::Datetime),
 		map(tag("decimal"), |_| Kind::Decimal),
@@ -76,9 +133,71 @@ pub fn kind(i: &str) -> IResult<&str, Kind> {
 		map(tag("string"), |_| Kind::String),
 		map(geometry, Kind::Geometry),
 		map(record, Kind::Record),
+		map(schema, Kind::Schema),
+		map(literal, Kind::Literal),
 	))(i)
 }
 
+fn generic(i: &str) -> IResult<&str, Kind> {
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('<')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, v) = kind(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('>')(i)?;
+	Ok((i, v))
+}
+
+fn option(i: &str) -> IResult<&str, Kind> {
+	let (i, _) = tag("option")(i)?;
+	generic(i)
+}
+
+fn array(i: &str) -> IResult<&str, Kind> {
+	let (i, _) = tag("array")(i)?;
+	let (i, v) = opt(generic)(i)?;
+	Ok((i, v.unwrap_or_default()))
+}
+
+fn set(i: &str) -> IResult<&str, Kind> {
+	let (i, _) = tag("set")(i)?;
+	let (i, v) = opt(generic)(i)?;
+	Ok((i, v.unwrap_or_default()))
+}
+
+fn either(i: &str) -> IResult<&str, Vec<Kind>> {
+	let (i, _) = tag("either")(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('<')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, v) = separated_list1(commas, kind)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('>')(i)?;
+	Ok((i, v))
+}
+
+fn schema(i: &str) -> IResult<&str, BTreeMap<String, Kind>> {
+	let (i, _) = char('{')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, v) = separated_list0(commas, |i| {
+		let (i, k) = key(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, _) = char(':')(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, v) = kind(i)?;
+		Ok((i, (String::from(k), v)))
+	})(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = opt(char(','))(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('}')(i)?;
+	Ok((i, v.into_iter().collect()))
+}
+
+fn literal(i: &str) -> IResult<&str, Value> {
+	alt((map(strand, Value::from), map(number, Value::from)))(i)
+}
+
 fn record(i: &str) -> IResult<&str, Vec<Table>> {
 	let (i, _) = tag("record")(i)?;
 	let (i, _) = mightbespace(i)?;
@@ -111,3 +230,108 @@ fn geometry(i: &str) -> IResult<&str, Vec<String>> {
 	let (i, _) = char(')')(i)?;
 	Ok((i, v))
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn kind_simple() {
+		let sql = "array";
+		let res = kind(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("array", format!("{}", out));
+		assert_eq!(out, Kind::Array);
+	}
+
+	#[test]
+	fn kind_nested() {
+		let sql = "option < array<record(user, admin)> >";
+		let res = kind(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("option<array<record(user, admin)>>", format!("{}", out));
+	}
+
+	#[test]
+	fn kind_set() {
+		let sql = "set<string>";
+		let res = kind(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("set<string>", format!("{}", out));
+		assert_eq!(out, Kind::Set(Box::new(Kind::String)));
+	}
+
+	#[test]
+	fn kind_either() {
+		let sql = "either<int, string>";
+		let res = kind(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("int | string", format!("{}", out));
+		assert_eq!(out, Kind::Either(vec![Kind::Int, Kind::String]));
+	}
+
+	#[test]
+	fn kind_literal_union() {
+		let sql = "'a' | \"b\" | 3";
+		let res = kind(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("\"a\" | \"b\" | 3", format!("{}", out));
+	}
+
+	#[test]
+	fn kind_schema() {
+		let sql = "{ city: string, zip: option<int>, }";
+		let res = kind(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("{ city: string, zip: option<int> }", format!("{}", out));
+		assert_eq!(
+			out,
+			Kind::Schema(BTreeMap::from([
+				(String::from("city"), Kind::String),
+				(String::from("zip"), Kind::Option(Box::new(Kind::Int))),
+			]))
+		);
+	}
+
+	#[test]
+	fn kind_stored_before_nested_types() {
+		#[derive(Serialize)]
+		#[allow(dead_code)]
+		enum Stored {
+			Any,
+			Array,
+			Bool,
+			Datetime,
+			Decimal,
+			Duration,
+			Float,
+			Int,
+			Number,
+			Object,
+			String,
+			Record(Vec<Table>),
+			Geometry(Vec<String>),
+		}
+		for (old, new) in [
+			(Stored::Any, Kind::Any),
+			(Stored::Array, Kind::Array),
+			(
+				Stored::Record(vec![Table::from("person")]),
+				Kind::Record(vec![Table::from("person")]),
+			),
+		] {
+			crate::sql::serde::beg_internal_serialization();
+			let val: Vec<u8> = msgpack::to_vec(&old).unwrap();
+			crate::sql::serde::end_internal_serialization();
+			let out: Kind = msgpack::from_slice(&val).unwrap();
+			assert_eq!(out, new);
+		}
+	}
+}
@@ -1,6 +1,8 @@
+pub(crate) mod after;
 pub(crate) mod algorithm;
 pub(crate) mod array;
 pub(crate) mod base;
+pub(crate) mod block;
 pub(crate) mod comment;
 pub(crate) mod common;
 pub(crate) mod cond;
@@ -22,6 +24,8 @@ pub(crate) mod group;
 pub(crate) mod id;
 pub(crate) mod ident;
 pub(crate) mod idiom;
+pub(crate) mod index;
+pub(crate) mod join;
 pub(crate) mod kind;
 pub(crate) mod limit;
 pub(crate) mod model;
@@ -53,6 +57,7 @@ pub(crate) mod uuid;
 pub(crate) mod value;
 pub(crate) mod version;
 pub(crate) mod view;
+pub(crate) mod window;
 
 #[cfg(test)]
 pub(crate) mod test;
@@ -61,9 +66,11 @@ pub mod statements;
 
 pub use self::parser::*;
 
+pub use self::after::After;
 pub use self::algorithm::Algorithm;
 pub use self::array::Array;
 pub use self::base::Base;
+pub use self::block::Block;
 pub use self::cond::Cond;
 pub use self::data::Data;
 pub use self::datetime::Datetime;
@@ -1,6 +1,7 @@
 use crate::sql::ending::number as ending;
 use crate::sql::error::IResult;
 use crate::sql::serde::is_internal_serialization;
+use bigdecimal::num_bigint::{BigInt, Sign};
 use bigdecimal::BigDecimal;
 use bigdecimal::FromPrimitive;
 use bigdecimal::ToPrimitive;
@@ -291,7 +292,17 @@ impl Number {
 		match self {
 			Number::Int(v) => format!("{:.1$}", v, precision).into(),
 			Number::Float(v) => format!("{:.1$}", v, precision).into(),
-			Number::Decimal(v) => v.round(precision as i64).into(),
+			Number::Decimal(v) => {
+				// Round half away from zero, without converting the digits
+				// to a fixed size integer, which overflows for long decimals
+				let half = BigDecimal::new(BigInt::from(5), precision as i64 + 1);
+				match v.sign() {
+					Sign::Minus => v - half,
+					_ => v + half,
+				}
+				.with_scale(precision as i64)
+				.into()
+			}
 		}
 	}
 }
@@ -598,4 +609,12 @@ mod tests {
 		assert_eq!("-123.45", format!("{}", out));
 		assert_eq!(out, Number::from(-123.45));
 	}
+
+	#[test]
+	fn number_fixed_long_decimal() {
+		let val = Number::Decimal(BigDecimal::from(200)).sqrt();
+		assert_eq!("14.1421", format!("{}", val.fixed(4)));
+		let val = Number::Decimal(BigDecimal::from_str("-1.23456789").unwrap());
+		assert_eq!("-1.2346", format!("{}", val.fixed(4)));
+	}
 }
@@ -50,6 +50,8 @@ pub enum Operator {
 	NoneInside,  // ⊄
 	Outside,     // ∈
 	Intersects,  // ∩
+	//
+	Matches, // @@
 }
 
 impl Default for Operator {
@@ -109,6 +111,7 @@ impl fmt::Display for Operator {
 			Operator::NoneInside => write!(f, "NONEINSIDE"),
 			Operator::Outside => write!(f, "OUTSIDE"),
 			Operator::Intersects => write!(f, "INTERSECTS"),
+			Operator::Matches => write!(f, "@@"),
 		}
 	}
 }
@@ -140,6 +143,7 @@ pub fn symbols(i: &str) -> IResult<&str, Operator> {
 			map(tag("*~"), |_| Operator::AllLike),
 			map(tag("?~"), |_| Operator::AnyLike),
 			map(char('~'), |_| Operator::Like),
+			map(tag("@@"), |_| Operator::Matches),
 		)),
 		alt((
 			map(tag("<="), |_| Operator::LessThanOrEqual),
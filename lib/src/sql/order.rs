@@ -2,12 +2,14 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
 use crate::sql::error::IResult;
 use crate::sql::idiom::{basic, Idiom};
+use crate::sql::value::Value;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::{map, opt};
 use nom::multi::separated_list1;
 use nom::sequence::tuple;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
 use std::ops::Deref;
 
@@ -21,6 +23,35 @@ impl Deref for Orders {
 	}
 }
 
+impl Orders {
+	// Compare two values using each order clause
+	pub(crate) fn compare(&self, a: &Value, b: &Value) -> Ordering {
+		// Loop over each order clause
+		for order in self.iter() {
+			// Reverse the ordering if DESC
+			let o = match order.random {
+				true => {
+					let a = rand::random::<f64>();
+					let b = rand::random::<f64>();
+					a.partial_cmp(&b)
+				}
+				false => match order.direction {
+					true => a.compare(b, order, order.collate, order.numeric),
+					false => b.compare(a, order, order.collate, order.numeric),
+				},
+			};
+			//
+			match o {
+				Some(Ordering::Greater) => return Ordering::Greater,
+				Some(Ordering::Equal) => continue,
+				Some(Ordering::Less) => return Ordering::Less,
+				None => continue,
+			}
+		}
+		Ordering::Equal
+	}
+}
+
 impl IntoIterator for Orders {
 	type Item = Order;
 	type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -11,6 +11,7 @@ use crate::sql::statements::commit::{commit, CommitStatement};
 use crate::sql::statements::create::{create, CreateStatement};
 use crate::sql::statements::define::{define, DefineStatement};
 use crate::sql::statements::delete::{delete, DeleteStatement};
+use crate::sql::statements::explain::{explain, ExplainStatement};
 use crate::sql::statements::ifelse::{ifelse, IfelseStatement};
 use crate::sql::statements::info::{info, InfoStatement};
 use crate::sql::statements::insert::{insert, InsertStatement};
@@ -18,11 +19,14 @@ use crate::sql::statements::kill::{kill, KillStatement};
 use crate::sql::statements::live::{live, LiveStatement};
 use crate::sql::statements::option::{option, OptionStatement};
 use crate::sql::statements::output::{output, OutputStatement};
+use crate::sql::statements::rebuild::{rebuild, RebuildStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
 use crate::sql::statements::select::{select, SelectStatement};
 use crate::sql::statements::set::{set, SetStatement};
+use crate::sql::statements::show::{show, ShowStatement};
 use crate::sql::statements::update::{update, UpdateStatement};
+use crate::sql::statements::upsert::{upsert, UpsertStatement};
 use crate::sql::statements::yuse::{yuse, UseStatement};
 use crate::sql::value::Value;
 use nom::branch::alt;
@@ -62,6 +66,7 @@ pub enum Statement {
 	Use(UseStatement),
 	Set(SetStatement),
 	Info(InfoStatement),
+	Show(ShowStatement),
 	Live(LiveStatement),
 	Kill(KillStatement),
 	Begin(BeginStatement),
@@ -75,9 +80,12 @@ pub enum Statement {
 	Relate(RelateStatement),
 	Delete(DeleteStatement),
 	Insert(InsertStatement),
+	Upsert(UpsertStatement),
 	Define(DefineStatement),
 	Remove(RemoveStatement),
+	Rebuild(RebuildStatement),
 	Option(OptionStatement),
+	Explain(ExplainStatement),
 }
 
 impl Statement {
@@ -89,6 +97,8 @@ impl Statement {
 			Statement::Relate(v) => v.timeout.as_ref().map(|v| *v.0),
 			Statement::Delete(v) => v.timeout.as_ref().map(|v| *v.0),
 			Statement::Insert(v) => v.timeout.as_ref().map(|v| *v.0),
+			Statement::Upsert(v) => v.timeout.as_ref().map(|v| *v.0),
+			Statement::Explain(v) => v.stmt.timeout(),
 			_ => None,
 		}
 	}
@@ -98,6 +108,7 @@ impl Statement {
 			Statement::Use(_) => false,
 			Statement::Set(v) => v.writeable(),
 			Statement::Info(_) => false,
+			Statement::Show(_) => false,
 			Statement::Live(_) => true,
 			Statement::Kill(_) => true,
 			Statement::Output(v) => v.writeable(),
@@ -108,9 +119,12 @@ impl Statement {
 			Statement::Relate(v) => v.writeable(),
 			Statement::Delete(v) => v.writeable(),
 			Statement::Insert(v) => v.writeable(),
+			Statement::Upsert(v) => v.writeable(),
 			Statement::Define(_) => true,
 			Statement::Remove(_) => true,
+			Statement::Rebuild(_) => true,
 			Statement::Option(_) => false,
+			Statement::Explain(v) => v.writeable(),
 			_ => unreachable!(),
 		}
 	}
@@ -125,6 +139,7 @@ impl Statement {
 		match self {
 			Statement::Set(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Info(v) => v.compute(ctx, opt, txn, doc).await,
+			Statement::Show(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Live(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Kill(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Output(v) => v.compute(ctx, opt, txn, doc).await,
@@ -135,8 +150,11 @@ impl Statement {
 			Statement::Relate(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Delete(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Insert(v) => v.compute(ctx, opt, txn, doc).await,
+			Statement::Upsert(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Define(v) => v.compute(ctx, opt, txn, doc).await,
 			Statement::Remove(v) => v.compute(ctx, opt, txn, doc).await,
+			Statement::Rebuild(v) => v.compute(ctx, opt, txn, doc).await,
+			Statement::Explain(v) => v.compute(ctx, opt, txn, doc).await,
 			_ => unreachable!(),
 		}
 	}
@@ -148,6 +166,7 @@ impl fmt::Display for Statement {
 			Statement::Use(v) => write!(f, "{}", v),
 			Statement::Set(v) => write!(f, "{}", v),
 			Statement::Info(v) => write!(f, "{}", v),
+			Statement::Show(v) => write!(f, "{}", v),
 			Statement::Live(v) => write!(f, "{}", v),
 			Statement::Kill(v) => write!(f, "{}", v),
 			Statement::Begin(v) => write!(f, "{}", v),
@@ -161,9 +180,12 @@ impl fmt::Display for Statement {
 			Statement::Relate(v) => write!(f, "{}", v),
 			Statement::Delete(v) => write!(f, "{}", v),
 			Statement::Insert(v) => write!(f, "{}", v),
+			Statement::Upsert(v) => write!(f, "{}", v),
 			Statement::Define(v) => write!(f, "{}", v),
 			Statement::Remove(v) => write!(f, "{}", v),
+			Statement::Rebuild(v) => write!(f, "{}", v),
 			Statement::Option(v) => write!(f, "{}", v),
+			Statement::Explain(v) => write!(f, "{}", v),
 		}
 	}
 }
@@ -175,6 +197,7 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 			map(set, Statement::Set),
 			map(yuse, Statement::Use),
 			map(info, Statement::Info),
+			map(show, Statement::Show),
 			map(live, Statement::Live),
 			map(kill, Statement::Kill),
 			map(begin, Statement::Begin),
@@ -182,15 +205,20 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 			map(commit, Statement::Commit),
 			map(output, Statement::Output),
 			map(ifelse, Statement::Ifelse),
-			map(select, Statement::Select),
-			map(create, Statement::Create),
-			map(update, Statement::Update),
-			map(relate, Statement::Relate),
-			map(delete, Statement::Delete),
-			map(insert, Statement::Insert),
+			alt((
+				map(select, Statement::Select),
+				map(create, Statement::Create),
+				map(update, Statement::Update),
+				map(relate, Statement::Relate),
+				map(delete, Statement::Delete),
+				map(insert, Statement::Insert),
+				map(upsert, Statement::Upsert),
+			)),
 			map(define, Statement::Define),
 			map(remove, Statement::Remove),
+			map(rebuild, Statement::Rebuild),
 			map(option, Statement::Option),
+			map(explain, Statement::Explain),
 		)),
 		mightbespace,
 	)(i)
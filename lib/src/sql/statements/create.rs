@@ -48,9 +48,11 @@ impl CreateStatement {
 		let mut i = Iterator::new();
 		// Ensure futures are stored
 		let opt = &opt.futures(false);
+		// Subqueries in the targets are not explained
+		let sub = &opt.explain(None);
 		// Loop over the create targets
 		for w in self.what.0.iter() {
-			let v = w.compute(ctx, opt, txn, doc).await?;
+			let v = w.compute(ctx, sub, txn, doc).await?;
 			match v {
 				Value::Table(v) => i.ingest(Iterable::Thing(v.generate())),
 				Value::Thing(v) => i.ingest(Iterable::Thing(v)),
@@ -3,18 +3,22 @@ use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::err::Error;
+use crate::idx::build::Build;
 use crate::sql::algorithm::{algorithm, Algorithm};
 use crate::sql::base::{base, Base};
-use crate::sql::comment::shouldbespace;
+use crate::sql::block::{block, Block};
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::commas;
 use crate::sql::duration::{duration, Duration};
 use crate::sql::error::IResult;
 use crate::sql::escape::escape_strand;
+use crate::sql::function::function_name;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
 use crate::sql::idiom::{Idiom, Idioms};
+use crate::sql::index::{self, Index};
 use crate::sql::kind::{kind, Kind};
-use crate::sql::permission::{permissions, Permissions};
-use crate::sql::statements::UpdateStatement;
+use crate::sql::permission::{permissions, Permission, Permissions};
 use crate::sql::strand::strand_raw;
 use crate::sql::value::{value, values, Value, Values};
 use crate::sql::view::{view, View};
@@ -22,10 +26,11 @@ use argon2::password_hash::{PasswordHasher, SaltString};
 use argon2::Argon2;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::char;
 use nom::combinator::{map, opt};
-use nom::multi::many0;
-use nom::sequence::tuple;
+use nom::multi::{many0, separated_list0};
+use nom::sequence::{preceded, tuple};
 use rand::distributions::Alphanumeric;
 use rand::rngs::OsRng;
 use rand::Rng;
@@ -39,6 +44,7 @@ pub enum DefineStatement {
 	Login(DefineLoginStatement),
 	Token(DefineTokenStatement),
 	Scope(DefineScopeStatement),
+	Function(DefineFunctionStatement),
 	Table(DefineTableStatement),
 	Event(DefineEventStatement),
 	Field(DefineFieldStatement),
@@ -59,6 +65,7 @@ impl DefineStatement {
 			DefineStatement::Login(ref v) => v.compute(ctx, opt, txn, doc).await,
 			DefineStatement::Token(ref v) => v.compute(ctx, opt, txn, doc).await,
 			DefineStatement::Scope(ref v) => v.compute(ctx, opt, txn, doc).await,
+			DefineStatement::Function(ref v) => v.compute(ctx, opt, txn, doc).await,
 			DefineStatement::Table(ref v) => v.compute(ctx, opt, txn, doc).await,
 			DefineStatement::Event(ref v) => v.compute(ctx, opt, txn, doc).await,
 			DefineStatement::Field(ref v) => v.compute(ctx, opt, txn, doc).await,
@@ -75,6 +82,7 @@ impl fmt::Display for DefineStatement {
 			DefineStatement::Login(v) => write!(f, "{}", v),
 			DefineStatement::Token(v) => write!(f, "{}", v),
 			DefineStatement::Scope(v) => write!(f, "{}", v),
+			DefineStatement::Function(v) => write!(f, "{}", v),
 			DefineStatement::Table(v) => write!(f, "{}", v),
 			DefineStatement::Event(v) => write!(f, "{}", v),
 			DefineStatement::Field(v) => write!(f, "{}", v),
@@ -90,6 +98,7 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(login, DefineStatement::Login),
 		map(token, DefineStatement::Token),
 		map(scope, DefineStatement::Scope),
+		map(function, DefineStatement::Function),
 		map(table, DefineStatement::Table),
 		map(event, DefineStatement::Event),
 		map(field, DefineStatement::Field),
@@ -556,6 +565,113 @@ fn scope_signin(i: &str) -> IResult<&str, DefineScopeOption> {
 // --------------------------------------------------
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct DefineFunctionStatement {
+	pub name: Ident,
+	pub args: Vec<(Ident, Kind)>,
+	pub block: Block,
+	pub permissions: Permission,
+}
+
+impl DefineFunctionStatement {
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Process the statement
+		let key = crate::key::fc::new(opt.ns(), opt.db(), &self.name);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		run.set(key, self).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for DefineFunctionStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"DEFINE FUNCTION fn::{}({}) {}",
+			self.name.0,
+			self.args
+				.iter()
+				.map(|(n, k)| format!("${}: {}", n.0, k))
+				.collect::<Vec<_>>()
+				.join(", "),
+			self.block
+		)?;
+		if self.permissions != Permission::Full {
+			write!(f, " PERMISSIONS {}", self.permissions)?;
+		}
+		Ok(())
+	}
+}
+
+fn function(i: &str) -> IResult<&str, DefineFunctionStatement> {
+	let (i, _) = tag_no_case("DEFINE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("FUNCTION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag("fn::")(i)?;
+	let (i, name) = function_name(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('(')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, args) = separated_list0(commas, function_arg)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char(')')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, block) = block(i)?;
+	let (i, permissions) = opt(preceded(shouldbespace, function_permissions))(i)?;
+	Ok((
+		i,
+		DefineFunctionStatement {
+			name: Ident(name.to_owned()),
+			args,
+			block,
+			permissions: permissions.unwrap_or_default(),
+		},
+	))
+}
+
+fn function_arg(i: &str) -> IResult<&str, (Ident, Kind)> {
+	let (i, _) = char('$')(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char(':')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, kind) = kind(i)?;
+	Ok((i, (name, kind)))
+}
+
+fn function_permissions(i: &str) -> IResult<&str, Permission> {
+	let (i, _) = tag_no_case("PERMISSIONS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	alt((
+		map(tag_no_case("NONE"), |_| Permission::None),
+		map(tag_no_case("FULL"), |_| Permission::Full),
+		map(tuple((tag_no_case("WHERE"), shouldbespace, value)), |(_, _, v)| {
+			Permission::Specific(v)
+		}),
+	))(i)
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
 pub struct DefineTableStatement {
 	pub name: Ident,
@@ -563,6 +679,10 @@ pub struct DefineTableStatement {
 	pub full: bool,
 	pub view: Option<View>,
 	pub permissions: Permissions,
+	#[serde(default)]
+	pub history: Option<Duration>,
+	#[serde(default)]
+	pub changefeed: Option<Duration>,
 }
 
 impl DefineTableStatement {
@@ -571,12 +691,18 @@ impl DefineTableStatement {
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		doc: Option<&Value>,
+		_doc: Option<&Value>,
 	) -> Result<Value, Error> {
 		// Selected DB?
 		opt.needs(Level::Db)?;
 		// Allowed to run?
 		opt.check(Level::Db)?;
+		// Views are not maintained in order
+		if matches!(&self.view, Some(v) if v.is_limited()) {
+			return Err(Error::TableViewLimited {
+				table: self.name.to_raw(),
+			});
+		}
 		// Clone transaction
 		let run = txn.clone();
 		// Claim transaction
@@ -585,41 +711,85 @@ impl DefineTableStatement {
 		let key = crate::key::tb::new(opt.ns(), opt.db(), &self.name);
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		// Fetch the previous definition
+		let old: Option<DefineTableStatement> = run.get(key.clone()).await?.map(|v| (&v).into());
 		run.set(key, self).await?;
+		// Remove the previous view config
+		if let Some(view) = old.as_ref().and_then(|v| v.view.as_ref()) {
+			for v in view.what.0.iter() {
+				let key = crate::key::ft::new(opt.ns(), opt.db(), v, &self.name);
+				run.del(key).await?;
+			}
+		}
 		// Check if table is a view
 		if let Some(view) = &self.view {
-			// Remove the table data
-			let key = crate::key::table::new(opt.ns(), opt.db(), &self.name);
-			run.delp(key, u32::MAX).await?;
 			// Process each foreign table
 			for v in view.what.0.iter() {
 				// Save the view config
 				let key = crate::key::ft::new(opt.ns(), opt.db(), v, &self.name);
 				run.set(key, self).await?;
 			}
-			// Release the transaction
-			drop(run);
-			// Force queries to run
-			let opt = &opt.force(true);
-			// Don't process field queries
-			let opt = &opt.fields(false);
-			// Don't process event queries
-			let opt = &opt.events(false);
-			// Don't process index queries
-			let opt = &opt.indexes(false);
-			// Process each foreign table
-			for v in view.what.0.iter() {
+			// Check if the view has changed
+			if !matches!(&old, Some(v) if v.view == self.view) {
+				// Remove the table data
+				let key = crate::key::table::new(opt.ns(), opt.db(), &self.name);
+				run.delp(key, u32::MAX).await?;
+				// Release the transaction
+				drop(run);
 				// Process the view data
-				let stm = UpdateStatement {
-					what: Values(vec![Value::Table(v.clone())]),
-					..UpdateStatement::default()
-				};
-				stm.compute(ctx, opt, txn, doc).await?;
+				self.build(ctx, opt, txn).await?;
 			}
 		}
 		// Ok all good
 		Ok(Value::None)
 	}
+	// Recompute the view data from the records of the source tables
+	pub(crate) async fn build(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<(), Error> {
+		// Check if table is a view
+		if self.view.is_some() {
+			// Clone transaction
+			let run = txn.clone();
+			// Claim transaction
+			let mut run = run.lock().await;
+			// Remove the view records
+			let beg = crate::key::thing::prefix(opt.ns(), opt.db(), &self.name);
+			let end = crate::key::thing::suffix(opt.ns(), opt.db(), &self.name);
+			run.delr(beg..end, u32::MAX).await?;
+			// Remove the view aggregate values
+			let beg = crate::key::va::prefix(opt.ns(), opt.db(), &self.name);
+			let end = crate::key::va::suffix(opt.ns(), opt.db(), &self.name);
+			run.delr(beg..end, u32::MAX).await?;
+			// Remove the view index data
+			for ix in run.all_ix(opt.ns(), opt.db(), &self.name).await?.iter() {
+				let beg = crate::key::index::prefix(opt.ns(), opt.db(), &ix.what, &ix.name);
+				let end = crate::key::index::suffix(opt.ns(), opt.db(), &ix.what, &ix.name);
+				run.delr(beg..end, u32::MAX).await?;
+			}
+			// Release the transaction
+			drop(run);
+			// Process the first batch of records
+			let mut bld = Build::default();
+			let mut done = bld.table(ctx, opt, txn, self).await?;
+			// Without background builds, process the remaining records now
+			while !done && !opt.builds {
+				ctx.check()?;
+				done = bld.table(ctx, opt, txn, self).await?;
+			}
+			// Store the progress of the view build
+			let key = crate::key::vb::new(opt.ns(), opt.db(), &self.name);
+			match done {
+				true => txn.lock().await.del(key).await?,
+				false => txn.lock().await.set(key, bld).await?,
+			};
+		}
+		// Carry on
+		Ok(())
+	}
 }
 
 impl fmt::Display for DefineTableStatement {
@@ -637,6 +807,12 @@ impl fmt::Display for DefineTableStatement {
 		if let Some(ref v) = self.view {
 			write!(f, " {}", v)?
 		}
+		if let Some(ref v) = self.changefeed {
+			write!(f, " CHANGEFEED {}", v)?
+		}
+		if let Some(ref v) = self.history {
+			write!(f, " HISTORY {}", v)?
+		}
 		if !self.permissions.is_full() {
 			write!(f, " {}", self.permissions)?;
 		}
@@ -681,6 +857,14 @@ fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 					_ => None,
 				})
 				.unwrap_or_default(),
+			history: opts.iter().find_map(|x| match x {
+				DefineTableOption::History(ref v) => Some(v.to_owned()),
+				_ => None,
+			}),
+			changefeed: opts.iter().find_map(|x| match x {
+				DefineTableOption::Changefeed(ref v) => Some(v.to_owned()),
+				_ => None,
+			}),
 		},
 	))
 }
@@ -691,11 +875,21 @@ pub enum DefineTableOption {
 	View(View),
 	Schemaless,
 	Schemafull,
+	Changefeed(Duration),
+	History(Duration),
 	Permissions(Permissions),
 }
 
 fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
-	alt((table_drop, table_view, table_schemaless, table_schemafull, table_permissions))(i)
+	alt((
+		table_drop,
+		table_view,
+		table_schemaless,
+		table_schemafull,
+		table_changefeed,
+		table_history,
+		table_permissions,
+	))(i)
 }
 
 fn table_drop(i: &str) -> IResult<&str, DefineTableOption> {
@@ -722,6 +916,22 @@ fn table_schemafull(i: &str) -> IResult<&str, DefineTableOption> {
 	Ok((i, DefineTableOption::Schemafull))
 }
 
+fn table_changefeed(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CHANGEFEED")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = duration(i)?;
+	Ok((i, DefineTableOption::Changefeed(v)))
+}
+
+fn table_history(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("HISTORY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = duration(i)?;
+	Ok((i, DefineTableOption::History(v)))
+}
+
 fn table_permissions(i: &str) -> IResult<&str, DefineTableOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = permissions(i)?;
@@ -819,6 +1029,10 @@ pub struct DefineFieldStatement {
 	pub value: Option<Value>,
 	pub assert: Option<Value>,
 	pub permissions: Permissions,
+	#[serde(default)]
+	pub default: Option<Value>,
+	#[serde(default)]
+	pub readonly: bool,
 }
 
 impl DefineFieldStatement {
@@ -854,6 +1068,12 @@ impl fmt::Display for DefineFieldStatement {
 		if let Some(ref v) = self.kind {
 			write!(f, " TYPE {}", v)?
 		}
+		if let Some(ref v) = self.default {
+			write!(f, " DEFAULT {}", v)?
+		}
+		if self.readonly {
+			write!(f, " READONLY")?
+		}
 		if let Some(ref v) = self.value {
 			write!(f, " VALUE {}", v)?
 		}
@@ -888,6 +1108,11 @@ fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 				DefineFieldOption::Kind(ref v) => Some(v.to_owned()),
 				_ => None,
 			}),
+			default: opts.iter().find_map(|x| match x {
+				DefineFieldOption::Default(ref v) => Some(v.to_owned()),
+				_ => None,
+			}),
+			readonly: opts.iter().any(|x| matches!(x, DefineFieldOption::Readonly)),
 			value: opts.iter().find_map(|x| match x {
 				DefineFieldOption::Value(ref v) => Some(v.to_owned()),
 				_ => None,
@@ -910,13 +1135,17 @@ fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum DefineFieldOption {
 	Kind(Kind),
+	Default(Value),
+	Readonly,
 	Value(Value),
 	Assert(Value),
 	Permissions(Permissions),
 }
 
 fn field_opts(i: &str) -> IResult<&str, DefineFieldOption> {
-	alt((field_kind, field_value, field_assert, field_permissions))(i)
+	alt((field_kind, field_default, field_readonly, field_value, field_assert, field_permissions))(
+		i,
+	)
 }
 
 fn field_kind(i: &str) -> IResult<&str, DefineFieldOption> {
@@ -927,6 +1156,20 @@ fn field_kind(i: &str) -> IResult<&str, DefineFieldOption> {
 	Ok((i, DefineFieldOption::Kind(v)))
 }
 
+fn field_default(i: &str) -> IResult<&str, DefineFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("DEFAULT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = value(i)?;
+	Ok((i, DefineFieldOption::Default(v)))
+}
+
+fn field_readonly(i: &str) -> IResult<&str, DefineFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("READONLY")(i)?;
+	Ok((i, DefineFieldOption::Readonly))
+}
+
 fn field_value(i: &str) -> IResult<&str, DefineFieldOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("VALUE")(i)?;
@@ -954,11 +1197,62 @@ fn field_permissions(i: &str) -> IResult<&str, DefineFieldOption> {
 // --------------------------------------------------
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[serde(from = "StoredIndexStatement", into = "StoredIndexStatement")]
 pub struct DefineIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub cols: Idioms,
-	pub uniq: bool,
+	pub index: Index,
+	// Whether the index is still being built
+	pub building: bool,
+}
+
+// The stored layout of an index definition. This keeps the
+// unique flag of earlier index definitions in its position,
+// so that index definitions stored before the index type was
+// added can still be loaded, and are loaded as an index of
+// the type which they were defined with. The build state is
+// stored with the definition, so that the planner can skip
+// indexes which are still being built without further reads.
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredIndexStatement {
+	name: Ident,
+	what: Ident,
+	cols: Idioms,
+	uniq: bool,
+	#[serde(default)]
+	index: Option<Index>,
+	#[serde(default)]
+	building: bool,
+}
+
+impl From<StoredIndexStatement> for DefineIndexStatement {
+	fn from(v: StoredIndexStatement) -> Self {
+		DefineIndexStatement {
+			name: v.name,
+			what: v.what,
+			cols: v.cols,
+			index: match (v.index, v.uniq) {
+				(Some(index), _) => index,
+				(None, true) => Index::Uniq,
+				(None, false) => Index::Idx,
+			},
+			building: v.building,
+		}
+	}
+}
+
+impl From<DefineIndexStatement> for StoredIndexStatement {
+	fn from(v: DefineIndexStatement) -> Self {
+		StoredIndexStatement {
+			name: v.name,
+			what: v.what,
+			cols: v.cols,
+			uniq: v.index == Index::Uniq,
+			index: Some(v.index),
+			building: v.building,
+		}
+	}
 }
 
 impl DefineIndexStatement {
@@ -967,7 +1261,7 @@ impl DefineIndexStatement {
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		doc: Option<&Value>,
+		_doc: Option<&Value>,
 	) -> Result<Value, Error> {
 		// Selected DB?
 		opt.needs(Level::Db)?;
@@ -982,37 +1276,65 @@ impl DefineIndexStatement {
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
+		// Fetch the previous definition
+		let old: Option<DefineIndexStatement> = run.get(key.clone()).await?.map(|v| (&v).into());
+		// Check if the index has changed
+		if matches!(old, Some(v) if v.name == self.name && v.cols == self.cols && v.index == self.index)
+		{
+			return Ok(Value::None);
+		}
 		run.set(key, self).await?;
-		// Remove the index data
-		let beg = crate::key::index::prefix(opt.ns(), opt.db(), &self.what, &self.name);
-		let end = crate::key::index::suffix(opt.ns(), opt.db(), &self.what, &self.name);
-		run.delr(beg..end, u32::MAX).await?;
 		// Release the transaction
 		drop(run);
-		// Force queries to run
-		let opt = &opt.force(true);
-		// Don't process field queries
-		let opt = &opt.fields(false);
-		// Don't process event queries
-		let opt = &opt.events(false);
-		// Don't process table queries
-		let opt = &opt.tables(false);
-		// Update the index data
-		let stm = UpdateStatement {
-			what: Values(vec![Value::Table(self.what.clone().into())]),
-			..UpdateStatement::default()
-		};
-		stm.compute(ctx, opt, txn, doc).await?;
+		// Build the index data
+		self.build(ctx, opt, txn).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
+	// Rebuild the index data from the records of the table
+	pub(crate) async fn build(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<(), Error> {
+		// Remove the index data
+		let beg = crate::key::index::prefix(opt.ns(), opt.db(), &self.what, &self.name);
+		let end = crate::key::index::suffix(opt.ns(), opt.db(), &self.what, &self.name);
+		txn.lock().await.delr(beg..end, u32::MAX).await?;
+		// Index the first batch of records
+		let mut bld = Build::default();
+		let mut done = bld.batch(ctx, opt, txn, self).await?;
+		// Without background builds, index the remaining records now
+		while !done && !opt.builds {
+			ctx.check()?;
+			done = bld.batch(ctx, opt, txn, self).await?;
+		}
+		// Store the progress of the index build
+		let key = crate::key::ib::new(opt.ns(), opt.db(), &self.what, &self.name);
+		match done {
+			true => txn.lock().await.del(key).await?,
+			false => txn.lock().await.set(key, bld).await?,
+		};
+		// Store the build state with the index definition
+		if self.building == done {
+			let key = crate::key::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
+			let ix = DefineIndexStatement {
+				building: !done,
+				..self.clone()
+			};
+			txn.lock().await.set(key, ix).await?;
+		}
+		// Carry on
+		Ok(())
+	}
 }
 
 impl fmt::Display for DefineIndexStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "DEFINE INDEX {} ON {} FIELDS {}", self.name, self.what, self.cols)?;
-		if self.uniq {
-			write!(f, " UNIQUE")?
+		if self.index != Index::Idx {
+			write!(f, " {}", self.index)?
 		}
 		Ok(())
 	}
@@ -1033,14 +1355,100 @@ fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 	let (i, _) = alt((tag_no_case("COLUMNS"), tag_no_case("FIELDS")))(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, cols) = idiom::locals(i)?;
-	let (i, uniq) = opt(tuple((shouldbespace, tag_no_case("UNIQUE"))))(i)?;
+	let (i, index) = opt(preceded(shouldbespace, index::index))(i)?;
 	Ok((
 		i,
 		DefineIndexStatement {
 			name,
 			what,
 			cols,
-			uniq: uniq.is_some(),
+			index: index.unwrap_or_default(),
+			building: false,
 		},
 	))
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_table_stored_before_table_options() {
+		// A table definition as it was stored before history and change feeds
+		#[derive(Serialize)]
+		struct Stored {
+			name: Ident,
+			drop: bool,
+			full: bool,
+			view: Option<View>,
+			permissions: Permissions,
+		}
+		let old = Stored {
+			name: Ident::from("person"),
+			drop: false,
+			full: true,
+			view: None,
+			permissions: Permissions::none(),
+		};
+		let val: Vec<u8> = msgpack::to_vec(&old).unwrap();
+		let out = DefineTableStatement::from(&val);
+		assert_eq!("DEFINE TABLE person SCHEMAFULL PERMISSIONS NONE", format!("{}", out));
+		assert_eq!(out.history, None);
+		assert_eq!(out.changefeed, None);
+	}
+
+	#[test]
+	fn define_index_stored_before_index_types() {
+		// An index definition as it was stored with a unique flag
+		#[derive(Serialize)]
+		struct Stored {
+			name: Ident,
+			what: Ident,
+			cols: Idioms,
+			uniq: bool,
+		}
+		let old = Stored {
+			name: Ident::from("email"),
+			what: Ident::from("user"),
+			cols: Idioms(vec![Idiom::from(String::from("email"))]),
+			uniq: true,
+		};
+		let val: Vec<u8> = msgpack::to_vec(&old).unwrap();
+		let out = DefineIndexStatement::from(&val);
+		assert_eq!("DEFINE INDEX email ON user FIELDS email UNIQUE", format!("{}", out));
+		assert!(!out.building);
+		// The index is stored again with its index type
+		let val: Vec<u8> = Vec::from(&out);
+		assert_eq!(out, DefineIndexStatement::from(&val));
+	}
+
+	#[test]
+	fn define_field_stored_before_default_and_readonly() {
+		// A field definition as it was stored before defaults and readonly fields
+		#[derive(Serialize)]
+		struct Stored {
+			name: Idiom,
+			what: Ident,
+			kind: Option<Kind>,
+			value: Option<Value>,
+			assert: Option<Value>,
+			permissions: Permissions,
+		}
+		let old = Stored {
+			name: Idiom::from(String::from("age")),
+			what: Ident::from("person"),
+			kind: Some(Kind::Number),
+			value: None,
+			assert: None,
+			permissions: Permissions::full(),
+		};
+		crate::sql::serde::beg_internal_serialization();
+		let val: Vec<u8> = msgpack::to_vec(&old).unwrap();
+		crate::sql::serde::end_internal_serialization();
+		let out = DefineFieldStatement::from(&val);
+		assert_eq!("DEFINE FIELD age ON person TYPE number", format!("{}", out));
+		assert_eq!(out.default, None);
+		assert!(!out.readonly);
+	}
+}
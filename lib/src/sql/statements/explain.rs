@@ -0,0 +1,114 @@
+use crate::ctx::Context;
+use crate::dbs::Explanation;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::statement::Statement;
+use crate::sql::statements::create::create;
+use crate::sql::statements::delete::delete;
+use crate::sql::statements::insert::insert;
+use crate::sql::statements::relate::relate;
+use crate::sql::statements::select::select;
+use crate::sql::statements::update::update;
+use crate::sql::statements::upsert::upsert;
+use crate::sql::value::Value;
+use async_recursion::async_recursion;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{map, opt};
+use nom::sequence::terminated;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct ExplainStatement {
+	pub analyze: bool,
+	pub stmt: Box<Statement>,
+}
+
+impl ExplainStatement {
+	pub(crate) fn writeable(&self) -> bool {
+		self.analyze && self.stmt.writeable()
+	}
+
+	#[cfg_attr(feature = "parallel", async_recursion)]
+	#[cfg_attr(not(feature = "parallel"), async_recursion(?Send))]
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&'async_recursion Value>,
+	) -> Result<Value, Error> {
+		// Create a new explanation
+		let exp = Explanation::new(self.analyze);
+		// Process the explained statement
+		let opt = &opt.explain(Some(exp.clone()));
+		self.stmt.compute(ctx, opt, txn, doc).await?;
+		// Output the explanation
+		let exp = exp.lock().await;
+		Ok(Value::from(&*exp))
+	}
+}
+
+impl fmt::Display for ExplainStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "EXPLAIN")?;
+		if self.analyze {
+			write!(f, " ANALYZE")?
+		}
+		write!(f, " {}", self.stmt)
+	}
+}
+
+pub fn explain(i: &str) -> IResult<&str, ExplainStatement> {
+	let (i, _) = tag_no_case("EXPLAIN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, analyze) = opt(terminated(tag_no_case("ANALYZE"), shouldbespace))(i)?;
+	let (i, stmt) = alt((
+		map(select, Statement::Select),
+		map(create, Statement::Create),
+		map(update, Statement::Update),
+		map(relate, Statement::Relate),
+		map(delete, Statement::Delete),
+		map(insert, Statement::Insert),
+		map(upsert, Statement::Upsert),
+	))(i)?;
+	Ok((
+		i,
+		ExplainStatement {
+			analyze: analyze.is_some(),
+			stmt: Box::new(stmt),
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn explain_statement() {
+		let sql = "EXPLAIN SELECT * FROM person WHERE age > 18";
+		let res = explain(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("EXPLAIN SELECT * FROM person WHERE age > 18", format!("{}", out))
+	}
+
+	#[test]
+	fn explain_analyze_statement() {
+		let sql = "EXPLAIN ANALYZE UPDATE person SET adult = true WHERE age > 18";
+		let res = explain(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"EXPLAIN ANALYZE UPDATE person SET adult = true WHERE age > 18",
+			format!("{}", out)
+		)
+	}
+}
@@ -3,6 +3,7 @@ use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::err::Error;
+use crate::idx::build::Build;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
@@ -21,6 +22,7 @@ pub enum InfoStatement {
 	Db,
 	Sc(Ident),
 	Tb(Ident),
+	Running,
 }
 
 impl InfoStatement {
@@ -120,6 +122,12 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("dl".to_owned(), tmp.into());
+				// Process the functions
+				let mut tmp = Object::default();
+				for v in run.all_fc(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(format!("fn::{}", v.name.0), v.to_string().into());
+				}
+				res.insert("fc".to_owned(), tmp.into());
 				// Ok all good
 				Value::from(res).ok()
 			}
@@ -172,6 +180,22 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("ix".to_owned(), tmp.into());
+				// Process the index builds
+				let mut tmp = Object::default();
+				for v in run.all_ix(opt.ns(), opt.db(), tb).await?.iter() {
+					let key = crate::key::ib::new(opt.ns(), opt.db(), tb, &v.name);
+					if let Some(bld) = run.get(key).await? {
+						let bld: Build = bld.into();
+						tmp.insert(v.name.to_string(), Value::from(&bld));
+					}
+				}
+				res.insert("ib".to_owned(), tmp.into());
+				// Process the view build
+				let key = crate::key::vb::new(opt.ns(), opt.db(), tb);
+				if let Some(bld) = run.get(key).await? {
+					let bld: Build = bld.into();
+					res.insert("vb".to_owned(), Value::from(&bld));
+				}
 				// Process the tables
 				let mut tmp = Object::default();
 				for v in run.all_ft(opt.ns(), opt.db(), tb).await?.iter() {
@@ -181,6 +205,17 @@ impl InfoStatement {
 				// Ok all good
 				Value::from(res).ok()
 			}
+			InfoStatement::Running => {
+				// No need for NS/DB
+				opt.needs(Level::Kv)?;
+				// Allowed to run?
+				opt.check(Level::Kv)?;
+				// List the running queries
+				match &opt.running {
+					Some(v) => v.list().ok(),
+					None => Value::from(Object::default()).ok(),
+				}
+			}
 		}
 	}
 }
@@ -193,6 +228,7 @@ impl fmt::Display for InfoStatement {
 			InfoStatement::Db => write!(f, "INFO FOR DATABASE"),
 			InfoStatement::Sc(ref s) => write!(f, "INFO FOR SCOPE {}", s),
 			InfoStatement::Tb(ref t) => write!(f, "INFO FOR TABLE {}", t),
+			InfoStatement::Running => write!(f, "INFO FOR RUNNING"),
 		}
 	}
 }
@@ -202,7 +238,7 @@ pub fn info(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("FOR")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	alt((kv, ns, db, sc, tb))(i)
+	alt((kv, ns, db, sc, tb, running))(i)
 }
 
 fn kv(i: &str) -> IResult<&str, InfoStatement> {
@@ -234,6 +270,11 @@ fn tb(i: &str) -> IResult<&str, InfoStatement> {
 	Ok((i, InfoStatement::Tb(table)))
 }
 
+fn running(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = tag_no_case("RUNNING")(i)?;
+	Ok((i, InfoStatement::Running))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -278,4 +319,14 @@ mod tests {
 		assert_eq!(out, InfoStatement::Tb(Ident::from("test")));
 		assert_eq!("INFO FOR TABLE test", format!("{}", out));
 	}
+
+	#[test]
+	fn info_query_running() {
+		let sql = "INFO FOR RUNNING";
+		let res = info(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Running);
+		assert_eq!("INFO FOR RUNNING", format!("{}", out));
+	}
 }
@@ -52,6 +52,8 @@ impl InsertStatement {
 		let mut i = Iterator::new();
 		// Ensure futures are stored
 		let opt = &opt.futures(false);
+		// Subqueries in the values are not explained
+		let sub = &opt.explain(None);
 		// Parse the expression
 		match &self.data {
 			// Check if this is a traditional statement
@@ -61,8 +63,8 @@ impl InsertStatement {
 					let mut o = Value::base();
 					// Set each field from the expression
 					for (k, v) in v.iter() {
-						let v = v.compute(ctx, opt, txn, None).await?;
-						o.set(ctx, opt, txn, k, v).await?;
+						let v = v.compute(ctx, sub, txn, None).await?;
+						o.set(ctx, sub, txn, k, v).await?;
 					}
 					// Specify the new table record id
 					let id = o.retable(&self.into)?;
@@ -72,7 +74,7 @@ impl InsertStatement {
 			}
 			// Check if this is a modern statement
 			Data::SingleExpression(v) => {
-				let v = v.compute(ctx, opt, txn, doc).await?;
+				let v = v.compute(ctx, sub, txn, doc).await?;
 				match v {
 					Value::Array(v) => {
 						for v in v {
@@ -9,12 +9,15 @@ use crate::sql::uuid::{uuid, Uuid};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::bytes::complete::tag_no_case;
+use nom::combinator::opt;
+use nom::sequence::terminated;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
 pub struct KillStatement {
 	pub id: Uuid,
+	pub query: bool,
 }
 
 impl KillStatement {
@@ -25,6 +28,20 @@ impl KillStatement {
 		txn: &Transaction,
 		_doc: Option<&Value>,
 	) -> Result<Value, Error> {
+		// Cancel a running query
+		if self.query {
+			// No need for NS/DB
+			opt.needs(Level::Kv)?;
+			// Allowed to run?
+			opt.check(Level::Kv)?;
+			// Cancel the query if it is running
+			return match &opt.running {
+				Some(v) if v.kill(&self.id) => Ok(Value::None),
+				_ => Err(Error::KillStatement {
+					value: self.id.to_string(),
+				}),
+			};
+		}
 		// Allowed to run?
 		opt.realtime()?;
 		// Selected DB?
@@ -67,18 +84,49 @@ impl KillStatement {
 
 impl fmt::Display for KillStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "KILL {}", self.id)
+		match self.query {
+			true => write!(f, "KILL QUERY {}", self.id),
+			false => write!(f, "KILL {}", self.id),
+		}
 	}
 }
 
 pub fn kill(i: &str) -> IResult<&str, KillStatement> {
 	let (i, _) = tag_no_case("KILL")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, query) = opt(terminated(tag_no_case("QUERY"), shouldbespace))(i)?;
 	let (i, v) = uuid(i)?;
 	Ok((
 		i,
 		KillStatement {
 			id: v,
+			query: query.is_some(),
 		},
 	))
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn kill_statement() {
+		let sql = "KILL 'e72bee20-f49b-11ec-b939-0242ac120002'";
+		let res = kill(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert!(!out.query);
+		assert_eq!("KILL \"e72bee20-f49b-11ec-b939-0242ac120002\"", format!("{}", out));
+	}
+
+	#[test]
+	fn kill_statement_query() {
+		let sql = "KILL QUERY 'e72bee20-f49b-11ec-b939-0242ac120002'";
+		let res = kill(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert!(out.query);
+		assert_eq!("KILL QUERY \"e72bee20-f49b-11ec-b939-0242ac120002\"", format!("{}", out));
+	}
+}
@@ -1,4 +1,5 @@
 use crate::ctx::Context;
+use crate::dbs::Auth;
 use crate::dbs::Level;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
@@ -28,6 +29,10 @@ pub struct LiveStatement {
 	pub what: Value,
 	pub cond: Option<Cond>,
 	pub fetch: Option<Fetchs>,
+	// The authentication of the subscriber
+	pub auth: Option<Auth>,
+	// The session of the subscriber
+	pub session: Option<Value>,
 }
 
 impl LiveStatement {
@@ -54,9 +59,13 @@ impl LiveStatement {
 				// Insert the live query
 				let key = crate::key::lq::new(opt.ns(), opt.db(), &self.id);
 				run.putc(key, tb.as_str(), None).await?;
+				// Store the subscriber details
+				let mut stm = self.clone();
+				stm.auth = Some(opt.auth.as_ref().clone());
+				stm.session = ctx.value("session").cloned();
 				// Insert the table live query
 				let key = crate::key::lv::new(opt.ns(), opt.db(), &tb, &self.id);
-				run.putc(key, self.clone(), None).await?;
+				run.putc(key, stm, None).await?;
 			}
 			v => {
 				return Err(Error::LiveStatement {
@@ -100,6 +109,8 @@ pub fn live(i: &str) -> IResult<&str, LiveStatement> {
 			what,
 			cond,
 			fetch,
+			auth: None,
+			session: None,
 		},
 	))
 }
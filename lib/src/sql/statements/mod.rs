@@ -4,6 +4,7 @@ pub(crate) mod commit;
 pub(crate) mod create;
 pub(crate) mod define;
 pub(crate) mod delete;
+pub(crate) mod explain;
 pub(crate) mod ifelse;
 pub(crate) mod info;
 pub(crate) mod insert;
@@ -11,11 +12,14 @@ pub(crate) mod kill;
 pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
+pub(crate) mod rebuild;
 pub(crate) mod relate;
 pub(crate) mod remove;
 pub(crate) mod select;
 pub(crate) mod set;
+pub(crate) mod show;
 pub(crate) mod update;
+pub(crate) mod upsert;
 pub(crate) mod yuse;
 
 pub use self::begin::BeginStatement;
@@ -23,6 +27,7 @@ pub use self::cancel::CancelStatement;
 pub use self::commit::CommitStatement;
 pub use self::create::CreateStatement;
 pub use self::delete::DeleteStatement;
+pub use self::explain::ExplainStatement;
 pub use self::ifelse::IfelseStatement;
 pub use self::info::InfoStatement;
 pub use self::insert::InsertStatement;
@@ -33,13 +38,16 @@ pub use self::output::OutputStatement;
 pub use self::relate::RelateStatement;
 pub use self::select::SelectStatement;
 pub use self::set::SetStatement;
+pub use self::show::ShowStatement;
 pub use self::update::UpdateStatement;
+pub use self::upsert::UpsertStatement;
 pub use self::yuse::UseStatement;
 
 pub use self::define::DefineDatabaseStatement;
 pub use self::define::DefineEventStatement;
 pub use self::define::DefineFieldOption;
 pub use self::define::DefineFieldStatement;
+pub use self::define::DefineFunctionStatement;
 pub use self::define::DefineIndexStatement;
 pub use self::define::DefineLoginOption;
 pub use self::define::DefineLoginStatement;
@@ -51,9 +59,14 @@ pub use self::define::DefineTableOption;
 pub use self::define::DefineTableStatement;
 pub use self::define::DefineTokenStatement;
 
+pub use self::rebuild::RebuildIndexStatement;
+pub use self::rebuild::RebuildStatement;
+pub use self::rebuild::RebuildTableStatement;
+
 pub use self::remove::RemoveDatabaseStatement;
 pub use self::remove::RemoveEventStatement;
 pub use self::remove::RemoveFieldStatement;
+pub use self::remove::RemoveFunctionStatement;
 pub use self::remove::RemoveIndexStatement;
 pub use self::remove::RemoveLoginStatement;
 pub use self::remove::RemoveNamespaceStatement;
@@ -0,0 +1,191 @@
+use crate::ctx::Context;
+use crate::dbs::Level;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{map, opt};
+use nom::sequence::tuple;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub enum RebuildStatement {
+	Table(RebuildTableStatement),
+	Index(RebuildIndexStatement),
+}
+
+impl RebuildStatement {
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		match self {
+			RebuildStatement::Table(ref v) => v.compute(ctx, opt, txn, doc).await,
+			RebuildStatement::Index(ref v) => v.compute(ctx, opt, txn, doc).await,
+		}
+	}
+}
+
+impl fmt::Display for RebuildStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RebuildStatement::Table(v) => write!(f, "{}", v),
+			RebuildStatement::Index(v) => write!(f, "{}", v),
+		}
+	}
+}
+
+pub fn rebuild(i: &str) -> IResult<&str, RebuildStatement> {
+	alt((map(table, RebuildStatement::Table), map(index, RebuildStatement::Index)))(i)
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct RebuildTableStatement {
+	pub name: Ident,
+}
+
+impl RebuildTableStatement {
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Fetch the table definition
+		let tb = txn.lock().await.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		// Check if table is a view
+		match tb.view {
+			// Recompute the view data
+			Some(_) => tb.build(ctx, opt, txn).await?,
+			// Recompute the views of this table
+			None => {
+				let fts = txn.lock().await.all_ft(opt.ns(), opt.db(), &self.name).await?;
+				for ft in fts.iter() {
+					ft.build(ctx, opt, txn).await?;
+				}
+			}
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for RebuildTableStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "REBUILD TABLE {}", self.name)
+	}
+}
+
+fn table(i: &str) -> IResult<&str, RebuildTableStatement> {
+	let (i, _) = tag_no_case("REBUILD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	Ok((
+		i,
+		RebuildTableStatement {
+			name,
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct RebuildIndexStatement {
+	pub name: Ident,
+	pub what: Ident,
+}
+
+impl RebuildIndexStatement {
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Fetch the index definition
+		let ix = txn.lock().await.get_ix(opt.ns(), opt.db(), &self.what, &self.name).await?;
+		// Rebuild the index data
+		ix.build(ctx, opt, txn).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for RebuildIndexStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "REBUILD INDEX {} ON {}", self.name, self.what)
+	}
+}
+
+fn index(i: &str) -> IResult<&str, RebuildIndexStatement> {
+	let (i, _) = tag_no_case("REBUILD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("INDEX")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = ident(i)?;
+	Ok((
+		i,
+		RebuildIndexStatement {
+			name,
+			what,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rebuild_table() {
+		let sql = "REBUILD TABLE person_by_age";
+		let res = rebuild(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("REBUILD TABLE person_by_age", format!("{}", out))
+	}
+
+	#[test]
+	fn rebuild_index() {
+		let sql = "REBUILD INDEX email ON TABLE user";
+		let res = rebuild(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("REBUILD INDEX email ON user", format!("{}", out))
+	}
+}
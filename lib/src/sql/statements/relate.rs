@@ -6,6 +6,7 @@ use crate::dbs::Options;
 use crate::dbs::Statement;
 use crate::dbs::Transaction;
 use crate::err::Error;
+use crate::key::re;
 use crate::sql::array::array;
 use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
@@ -15,7 +16,7 @@ use crate::sql::output::{output, Output};
 use crate::sql::param::param;
 use crate::sql::subquery::subquery;
 use crate::sql::table::{table, Table};
-use crate::sql::thing::thing;
+use crate::sql::thing::{thing, Thing};
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::Value;
 use derive::Store;
@@ -60,10 +61,12 @@ impl RelateStatement {
 		let mut i = Iterator::new();
 		// Ensure futures are stored
 		let opt = &opt.futures(false);
+		// Subqueries in the targets are not explained
+		let sub = &opt.explain(None);
 		// Loop over the from targets
 		let from = {
 			let mut out = Vec::new();
-			match self.from.compute(ctx, opt, txn, doc).await? {
+			match self.from.compute(ctx, sub, txn, doc).await? {
 				Value::Thing(v) => out.push(v),
 				Value::Array(v) => {
 					for v in v {
@@ -105,7 +108,7 @@ impl RelateStatement {
 		// Loop over the with targets
 		let with = {
 			let mut out = Vec::new();
-			match self.with.compute(ctx, opt, txn, doc).await? {
+			match self.with.compute(ctx, sub, txn, doc).await? {
 				Value::Thing(v) => out.push(v),
 				Value::Array(v) => {
 					for v in v {
@@ -143,13 +146,24 @@ impl RelateStatement {
 			};
 			out
 		};
+		// Relate each pair of records only once
+		let (from, with) = match self.uniq {
+			true => (distinct(from), distinct(with)),
+			false => (from, with),
+		};
 		//
 		for f in from.iter() {
 			for w in with.iter() {
-				let f = f.clone();
-				let w = w.clone();
-				let t = self.kind.generate();
-				i.ingest(Iterable::Relatable(f, t, w));
+				let t = match self.uniq {
+					// Reuse any existing edge between the records
+					true => match self.existing(opt, txn, f, w).await? {
+						Some(t) => t,
+						None => self.kind.generate(),
+					},
+					// Otherwise create a new edge
+					false => self.kind.generate(),
+				};
+				i.ingest(Iterable::Relatable(f.clone(), t, w.clone()));
 			}
 		}
 		// Assign the statement
@@ -157,6 +171,40 @@ impl RelateStatement {
 		// Output the results
 		i.output(ctx, opt, txn, &stm).await
 	}
+
+	// Find an existing edge of this kind between two records
+	async fn existing(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		f: &Thing,
+		w: &Thing,
+	) -> Result<Option<Thing>, Error> {
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Fetch the first edge between the records
+		let beg = re::prefix(opt.ns(), opt.db(), &self.kind, f, w);
+		let end = re::suffix(opt.ns(), opt.db(), &self.kind, f, w);
+		let res = run.scan(beg..end, 1).await?;
+		// Return the existing edge
+		Ok(res.first().map(|(k, _)| {
+			let key: re::Re = k.into();
+			Thing::from((key.tb, key.id))
+		}))
+	}
+}
+
+// Remove any duplicate records, keeping the first of each
+fn distinct(v: Vec<Thing>) -> Vec<Thing> {
+	let mut out: Vec<Thing> = Vec::with_capacity(v.len());
+	for v in v {
+		if !out.contains(&v) {
+			out.push(v);
+		}
+	}
+	out
 }
 
 impl fmt::Display for RelateStatement {
@@ -277,4 +325,17 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("RELATE person:tobie -> like -> animal:koala", format!("{}", out))
 	}
+
+	#[test]
+	fn relate_statement_unique() {
+		let sql = "RELATE person:tobie->like->animal:koala UNIQUE SET count += 1";
+		let res = relate(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert!(out.uniq);
+		assert_eq!(
+			"RELATE person:tobie -> like -> animal:koala UNIQUE SET count += 1",
+			format!("{}", out)
+		)
+	}
 }
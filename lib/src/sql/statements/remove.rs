@@ -6,11 +6,12 @@ use crate::err::Error;
 use crate::sql::base::{base, Base};
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
+use crate::sql::function::function_name;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
+use nom::bytes::complete::{tag, tag_no_case};
 use nom::combinator::{map, opt};
 use nom::sequence::tuple;
 use serde::{Deserialize, Serialize};
@@ -23,6 +24,7 @@ pub enum RemoveStatement {
 	Login(RemoveLoginStatement),
 	Token(RemoveTokenStatement),
 	Scope(RemoveScopeStatement),
+	Function(RemoveFunctionStatement),
 	Table(RemoveTableStatement),
 	Event(RemoveEventStatement),
 	Field(RemoveFieldStatement),
@@ -43,6 +45,7 @@ impl RemoveStatement {
 			RemoveStatement::Login(ref v) => v.compute(ctx, opt, txn, doc).await,
 			RemoveStatement::Token(ref v) => v.compute(ctx, opt, txn, doc).await,
 			RemoveStatement::Scope(ref v) => v.compute(ctx, opt, txn, doc).await,
+			RemoveStatement::Function(ref v) => v.compute(ctx, opt, txn, doc).await,
 			RemoveStatement::Table(ref v) => v.compute(ctx, opt, txn, doc).await,
 			RemoveStatement::Event(ref v) => v.compute(ctx, opt, txn, doc).await,
 			RemoveStatement::Field(ref v) => v.compute(ctx, opt, txn, doc).await,
@@ -59,6 +62,7 @@ impl fmt::Display for RemoveStatement {
 			RemoveStatement::Login(v) => write!(f, "{}", v),
 			RemoveStatement::Token(v) => write!(f, "{}", v),
 			RemoveStatement::Scope(v) => write!(f, "{}", v),
+			RemoveStatement::Function(v) => write!(f, "{}", v),
 			RemoveStatement::Table(v) => write!(f, "{}", v),
 			RemoveStatement::Event(v) => write!(f, "{}", v),
 			RemoveStatement::Field(v) => write!(f, "{}", v),
@@ -74,6 +78,7 @@ pub fn remove(i: &str) -> IResult<&str, RemoveStatement> {
 		map(login, RemoveStatement::Login),
 		map(token, RemoveStatement::Token),
 		map(scope, RemoveStatement::Scope),
+		map(function, RemoveStatement::Function),
 		map(table, RemoveStatement::Table),
 		map(event, RemoveStatement::Event),
 		map(field, RemoveStatement::Field),
@@ -408,6 +413,60 @@ fn scope(i: &str) -> IResult<&str, RemoveScopeStatement> {
 // --------------------------------------------------
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct RemoveFunctionStatement {
+	pub name: Ident,
+}
+
+impl RemoveFunctionStatement {
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Delete the definition
+		let key = crate::key::fc::new(opt.ns(), opt.db(), &self.name);
+		run.del(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for RemoveFunctionStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "REMOVE FUNCTION fn::{}", self.name.0)
+	}
+}
+
+fn function(i: &str) -> IResult<&str, RemoveFunctionStatement> {
+	let (i, _) = tag_no_case("REMOVE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("FUNCTION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag("fn::")(i)?;
+	let (i, name) = function_name(i)?;
+	Ok((
+		i,
+		RemoveFunctionStatement {
+			name: Ident(name.to_owned()),
+		},
+	))
+}
+
+// --------------------------------------------------
+// --------------------------------------------------
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
 pub struct RemoveTableStatement {
 	pub name: Ident,
@@ -435,6 +494,9 @@ impl RemoveTableStatement {
 		// Remove the resource data
 		let key = crate::key::table::new(opt.ns(), opt.db(), &self.name);
 		run.delp(key, u32::MAX).await?;
+		// Remove the view build progress
+		let key = crate::key::vb::new(opt.ns(), opt.db(), &self.name);
+		run.del(key).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -613,6 +675,9 @@ impl RemoveIndexStatement {
 		let beg = crate::key::index::prefix(opt.ns(), opt.db(), &self.what, &self.name);
 		let end = crate::key::index::suffix(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delr(beg..end, u32::MAX).await?;
+		// Remove the index build progress
+		let key = crate::key::ib::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
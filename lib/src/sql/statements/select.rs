@@ -6,12 +6,14 @@ use crate::dbs::Options;
 use crate::dbs::Statement;
 use crate::dbs::Transaction;
 use crate::err::Error;
+use crate::sql::after::{after, After};
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::error::IResult;
 use crate::sql::fetch::{fetch, Fetchs};
 use crate::sql::field::{fields, Field, Fields};
 use crate::sql::group::{group, Groups};
+use crate::sql::join::{joins, Joins};
 use crate::sql::limit::{limit, Limit};
 use crate::sql::order::{order, Orders};
 use crate::sql::split::{split, Splits};
@@ -40,6 +42,10 @@ pub struct SelectStatement {
 	pub version: Option<Version>,
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
+	#[serde(default)]
+	pub after: Option<After>,
+	#[serde(default)]
+	pub join: Option<Joins>,
 }
 
 impl SelectStatement {
@@ -64,6 +70,7 @@ impl SelectStatement {
 			Field::All => false,
 			Field::Alone(v) => v.writeable(),
 			Field::Alias(v, _) => v.writeable(),
+			Field::Window(f, _, _) => f.args().iter().any(|v| v.writeable()),
 		}) {
 			return true;
 		}
@@ -88,9 +95,11 @@ impl SelectStatement {
 		let mut i = Iterator::new();
 		// Ensure futures are processed
 		let opt = &opt.futures(true);
+		// Subqueries in the targets are not explained
+		let sub = &opt.explain(None);
 		// Loop over the select targets
 		for w in self.what.0.iter() {
-			let v = w.compute(ctx, opt, txn, doc).await?;
+			let v = w.compute(ctx, sub, txn, doc).await?;
 			match v {
 				Value::Table(v) => i.ingest(Iterable::Table(v)),
 				Value::Thing(v) => i.ingest(Iterable::Thing(v)),
@@ -129,6 +138,9 @@ impl SelectStatement {
 impl fmt::Display for SelectStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "SELECT {} FROM {}", self.expr, self.what)?;
+		if let Some(ref v) = self.join {
+			write!(f, " {}", v)?
+		}
 		if let Some(ref v) = self.cond {
 			write!(f, " {}", v)?
 		}
@@ -147,6 +159,9 @@ impl fmt::Display for SelectStatement {
 		if let Some(ref v) = self.start {
 			write!(f, " {}", v)?
 		}
+		if let Some(ref v) = self.after {
+			write!(f, " {}", v)?
+		}
 		if let Some(ref v) = self.fetch {
 			write!(f, " {}", v)?
 		}
@@ -171,12 +186,14 @@ pub fn select(i: &str) -> IResult<&str, SelectStatement> {
 	let (i, _) = tag_no_case("FROM")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, what) = selects(i)?;
+	let (i, join) = opt(preceded(shouldbespace, joins))(i)?;
 	let (i, cond) = opt(preceded(shouldbespace, cond))(i)?;
 	let (i, split) = opt(preceded(shouldbespace, split))(i)?;
 	let (i, group) = opt(preceded(shouldbespace, group))(i)?;
 	let (i, order) = opt(preceded(shouldbespace, order))(i)?;
 	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 	let (i, start) = opt(preceded(shouldbespace, start))(i)?;
+	let (i, after) = opt(preceded(shouldbespace, after))(i)?;
 	let (i, fetch) = opt(preceded(shouldbespace, fetch))(i)?;
 	let (i, version) = opt(preceded(shouldbespace, version))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
@@ -196,6 +213,8 @@ pub fn select(i: &str) -> IResult<&str, SelectStatement> {
 			version,
 			timeout,
 			parallel: parallel.is_some(),
+			after,
+			join,
 		},
 	))
 }
@@ -241,6 +260,24 @@ mod tests {
 		assert_eq!(sql, format!("{}", out))
 	}
 
+	#[test]
+	fn select_statement_join() {
+		let sql = "SELECT * FROM order JOIN customer ON customer_email = email AS customer LIMIT 10 FETCH items";
+		let res = select(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn select_statement_after() {
+		let sql = "SELECT * FROM test WHERE age > 18 LIMIT 10 AFTER $cursor FETCH friends";
+		let res = select(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
 	#[test]
 	fn select_statement_table_thing() {
 		let sql = "SELECT *, ((1 + 3) / 4), 1.3999 AS tester FROM test, test:thingy";
@@ -249,4 +286,46 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!(sql, format!("{}", out))
 	}
+
+	#[test]
+	fn select_statement_stored_before_after_and_join() {
+		// A select statement as it was stored before AFTER cursors and JOIN clauses
+		#[derive(Serialize)]
+		struct Stored {
+			expr: Fields,
+			what: Values,
+			cond: Option<Cond>,
+			split: Option<Splits>,
+			group: Option<Groups>,
+			order: Option<Orders>,
+			limit: Option<Limit>,
+			start: Option<Start>,
+			fetch: Option<Fetchs>,
+			version: Option<Version>,
+			timeout: Option<Timeout>,
+			parallel: bool,
+		}
+		let sql = "SELECT * FROM test WHERE age > 18 LIMIT 10 START 5 PARALLEL";
+		let new = select(sql).unwrap().1;
+		let old = Stored {
+			expr: new.expr.clone(),
+			what: new.what.clone(),
+			cond: new.cond.clone(),
+			split: None,
+			group: None,
+			order: None,
+			limit: new.limit.clone(),
+			start: new.start.clone(),
+			fetch: None,
+			version: None,
+			timeout: None,
+			parallel: true,
+		};
+		crate::sql::serde::beg_internal_serialization();
+		let val: Vec<u8> = msgpack::to_vec(&old).unwrap();
+		crate::sql::serde::end_internal_serialization();
+		let out = SelectStatement::from(&val);
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out, new);
+	}
 }
@@ -0,0 +1,155 @@
+use crate::ctx::Context;
+use crate::dbs::Level;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::{take_u32, take_u64};
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use chrono::{TimeZone, Utc};
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::opt;
+use nom::sequence::preceded;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct ShowStatement {
+	pub table: Ident,
+	pub since: u64,
+	pub limit: Option<u32>,
+}
+
+impl ShowStatement {
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::Db)?;
+		// Clone transaction
+		let run = txn.clone();
+		// Claim transaction
+		let mut run = run.lock().await;
+		// Fetch the changes since the versionstamp
+		let beg: Vec<u8> =
+			crate::key::cf::new(opt.ns(), opt.db(), &self.table, self.since, 0).into();
+		let end = crate::key::cf::suffix(opt.ns(), opt.db(), &self.table);
+		// Count the remaining changes
+		let mut num = self.limit.unwrap_or(u32::MAX);
+		// Prepare the next holder key
+		let mut nxt: Option<Vec<u8>> = None;
+		// Collect the changes in order
+		let mut out = vec![];
+		// Loop until no more keys
+		while num > 0 {
+			// Check if the context is finished
+			if ctx.is_done() {
+				break;
+			}
+			// Get the next batch of changes
+			let min = match nxt.take() {
+				Some(mut beg) => {
+					beg.push(0x00);
+					beg
+				}
+				None => beg.clone(),
+			};
+			let res = run.scan(min..end.clone(), num.min(1000)).await?;
+			// Ready the next
+			match res.last() {
+				Some((k, _)) => nxt = Some(k.clone()),
+				None => break,
+			}
+			num -= res.len() as u32;
+			for (k, v) in res {
+				let key: crate::key::cf::Cf = (&k).into();
+				// Add the versionstamp and commit time of the change
+				let mut val = Value::from(v);
+				if let Value::Object(ref mut v) = val {
+					v.insert("versionstamp".to_owned(), Value::from(key.vs));
+					v.insert(
+						"timestamp".to_owned(),
+						Value::from(Utc.timestamp_nanos(key.vs as i64)),
+					);
+				}
+				out.push(val);
+			}
+		}
+		// Return the changes in order
+		Ok(out.into())
+	}
+}
+
+impl fmt::Display for ShowStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "SHOW CHANGES FOR TABLE {} SINCE {}", self.table, self.since)?;
+		if let Some(ref v) = self.limit {
+			write!(f, " LIMIT {}", v)?
+		}
+		Ok(())
+	}
+}
+
+pub fn show(i: &str) -> IResult<&str, ShowStatement> {
+	let (i, _) = tag_no_case("SHOW")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CHANGES")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("FOR")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, table) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SINCE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, since) = take_u64(i)?;
+	let (i, limit) = opt(preceded(shouldbespace, show_limit))(i)?;
+	Ok((
+		i,
+		ShowStatement {
+			table,
+			since,
+			limit,
+		},
+	))
+}
+
+fn show_limit(i: &str) -> IResult<&str, u32> {
+	let (i, _) = tag_no_case("LIMIT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	take_u32(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn show_changes() {
+		let sql = "SHOW CHANGES FOR TABLE person SINCE 10";
+		let res = show(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("SHOW CHANGES FOR TABLE person SINCE 10", format!("{}", out))
+	}
+
+	#[test]
+	fn show_changes_limit() {
+		let sql = "SHOW CHANGES FOR TABLE person SINCE 10 LIMIT 100";
+		let res = show(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("SHOW CHANGES FOR TABLE person SINCE 10 LIMIT 100", format!("{}", out))
+	}
+}
@@ -0,0 +1,173 @@
+use crate::ctx::Context;
+use crate::dbs::Iterable;
+use crate::dbs::Iterator;
+use crate::dbs::Level;
+use crate::dbs::Options;
+use crate::dbs::Statement;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::data::{data, Data};
+use crate::sql::error::IResult;
+use crate::sql::output::{output, Output};
+use crate::sql::timeout::{timeout, Timeout};
+use crate::sql::value::{whats, Value, Values};
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::opt;
+use nom::sequence::preceded;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+pub struct UpsertStatement {
+	pub what: Values,
+	pub data: Option<Data>,
+	pub output: Option<Output>,
+	pub timeout: Option<Timeout>,
+	pub parallel: bool,
+}
+
+impl UpsertStatement {
+	pub(crate) fn writeable(&self) -> bool {
+		true
+	}
+
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&Value>,
+	) -> Result<Value, Error> {
+		// Selected DB?
+		opt.needs(Level::Db)?;
+		// Allowed to run?
+		opt.check(Level::No)?;
+		// Create a new iterator
+		let mut i = Iterator::new();
+		// Ensure futures are stored
+		let opt = &opt.futures(false);
+		// Subqueries in the targets are not explained
+		let sub = &opt.explain(None);
+		// Loop over the upsert targets
+		for w in self.what.0.iter() {
+			let v = w.compute(ctx, sub, txn, doc).await?;
+			match v {
+				Value::Table(v) => i.ingest(Iterable::Thing(v.generate())),
+				Value::Thing(v) => i.ingest(Iterable::Thing(v)),
+				Value::Model(v) => {
+					for v in v {
+						i.ingest(Iterable::Thing(v));
+					}
+				}
+				Value::Array(v) => {
+					for v in v {
+						match v {
+							Value::Table(v) => i.ingest(Iterable::Thing(v.generate())),
+							Value::Thing(v) => i.ingest(Iterable::Thing(v)),
+							Value::Model(v) => {
+								for v in v {
+									i.ingest(Iterable::Thing(v));
+								}
+							}
+							Value::Object(v) => match v.rid() {
+								Some(v) => i.ingest(Iterable::Thing(v)),
+								None => {
+									return Err(Error::UpsertStatement {
+										value: v.to_string(),
+									})
+								}
+							},
+							v => {
+								return Err(Error::UpsertStatement {
+									value: v.to_string(),
+								})
+							}
+						};
+					}
+				}
+				Value::Object(v) => match v.rid() {
+					Some(v) => i.ingest(Iterable::Thing(v)),
+					None => {
+						return Err(Error::UpsertStatement {
+							value: v.to_string(),
+						})
+					}
+				},
+				v => {
+					return Err(Error::UpsertStatement {
+						value: v.to_string(),
+					})
+				}
+			};
+		}
+		// Assign the statement
+		let stm = Statement::from(self);
+		// Output the results
+		i.output(ctx, opt, txn, &stm).await
+	}
+}
+
+impl fmt::Display for UpsertStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "UPSERT {}", self.what)?;
+		if let Some(ref v) = self.data {
+			write!(f, " {}", v)?
+		}
+		if let Some(ref v) = self.output {
+			write!(f, " {}", v)?
+		}
+		if let Some(ref v) = self.timeout {
+			write!(f, " {}", v)?
+		}
+		if self.parallel {
+			write!(f, " PARALLEL")?
+		}
+		Ok(())
+	}
+}
+
+pub fn upsert(i: &str) -> IResult<&str, UpsertStatement> {
+	let (i, _) = tag_no_case("UPSERT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = whats(i)?;
+	let (i, data) = opt(preceded(shouldbespace, data))(i)?;
+	let (i, output) = opt(preceded(shouldbespace, output))(i)?;
+	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
+	let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
+	Ok((
+		i,
+		UpsertStatement {
+			what,
+			data,
+			output,
+			timeout,
+			parallel: parallel.is_some(),
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn upsert_statement() {
+		let sql = "UPSERT test:tobie";
+		let res = upsert(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("UPSERT test:tobie", format!("{}", out))
+	}
+
+	#[test]
+	fn upsert_statement_merge() {
+		let sql = "UPSERT person:tobie MERGE { name: 'Tobie' } RETURN AFTER";
+		let res = upsert(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("UPSERT person:tobie MERGE { name: \"Tobie\" } RETURN AFTER", format!("{}", out))
+	}
+}
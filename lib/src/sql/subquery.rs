@@ -11,6 +11,7 @@ use crate::sql::statements::insert::{insert, InsertStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
 use crate::sql::statements::select::{select, SelectStatement};
 use crate::sql::statements::update::{update, UpdateStatement};
+use crate::sql::statements::upsert::{upsert, UpsertStatement};
 use crate::sql::value::{value, Value};
 use nom::branch::alt;
 use nom::character::complete::char;
@@ -29,6 +30,7 @@ pub enum Subquery {
 	Delete(DeleteStatement),
 	Relate(RelateStatement),
 	Insert(InsertStatement),
+	Upsert(UpsertStatement),
 }
 
 impl PartialOrd for Subquery {
@@ -49,6 +51,7 @@ impl Subquery {
 			Subquery::Delete(v) => v.writeable(),
 			Subquery::Relate(v) => v.writeable(),
 			Subquery::Insert(v) => v.writeable(),
+			Subquery::Upsert(v) => v.writeable(),
 		}
 	}
 
@@ -59,6 +62,8 @@ impl Subquery {
 		txn: &Transaction,
 		doc: Option<&Value>,
 	) -> Result<Value, Error> {
+		// Check if the query was cancelled or timed out
+		ctx.check()?;
 		match self {
 			Subquery::Value(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Subquery::Ifelse(ref v) => v.compute(ctx, opt, txn, doc).await,
@@ -175,6 +180,24 @@ impl Subquery {
 					v => Ok(v),
 				}
 			}
+			Subquery::Upsert(ref v) => {
+				// Duplicate options
+				let opt = opt.dive()?;
+				// Duplicate context
+				let mut ctx = Context::new(ctx);
+				// Add parent document
+				if let Some(doc) = doc {
+					ctx.add_value("parent".into(), doc);
+				}
+				// Process subquery
+				match v.compute(&ctx, &opt, txn, doc).await? {
+					Value::Array(mut v) => match v.len() {
+						1 => Ok(v.remove(0)),
+						_ => Ok(v.into()),
+					},
+					v => Ok(v),
+				}
+			}
 		}
 	}
 }
@@ -189,6 +212,7 @@ impl fmt::Display for Subquery {
 			Subquery::Delete(v) => write!(f, "({})", v),
 			Subquery::Relate(v) => write!(f, "({})", v),
 			Subquery::Insert(v) => write!(f, "({})", v),
+			Subquery::Upsert(v) => write!(f, "({})", v),
 			Subquery::Ifelse(v) => write!(f, "{}", v),
 		}
 	}
@@ -213,6 +237,7 @@ fn subquery_others(i: &str) -> IResult<&str, Subquery> {
 		map(delete, Subquery::Delete),
 		map(relate, Subquery::Relate),
 		map(insert, Subquery::Insert),
+		map(upsert, Subquery::Upsert),
 		map(value, Subquery::Value),
 	))(i)?;
 	let (i, _) = mightbespace(i)?;
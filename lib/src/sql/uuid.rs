@@ -15,7 +15,7 @@ use std::str;
 const SINGLE: char = '\'';
 const DOUBLE: char = '"';
 
-#[derive(Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Deserialize, Hash)]
 pub struct Uuid(pub uuid::Uuid);
 
 impl From<&str> for Uuid {
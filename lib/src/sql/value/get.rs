@@ -87,6 +87,14 @@ impl Value {
 						0 => Ok(Value::Thing(val)),
 						// Remote embedded field, so fetch the thing
 						_ => match p {
+							// This is a recursive graph traversal expression
+							Part::Graph(g) if g.rec.is_some() => {
+								let v = g.traverse(ctx, opt, txn, &val).await?;
+								match path.len() {
+									1 => Ok(v),
+									_ => v.get(ctx, opt, txn, path.next()).await,
+								}
+							}
 							// This is a graph traversal expression
 							Part::Graph(g) => {
 								let stm = SelectStatement {
@@ -5,7 +5,7 @@ use crate::dbs::Options;
 use crate::dbs::Response;
 use crate::dbs::Transaction;
 use crate::err::Error;
-use crate::sql::array::{array, Array};
+use crate::sql::array::{array, Array, Uniq};
 use crate::sql::common::commas;
 use crate::sql::datetime::{datetime, Datetime};
 use crate::sql::duration::{duration, Duration};
@@ -535,6 +535,10 @@ impl Value {
 		matches!(self, Value::Thing(_))
 	}
 
+	pub fn is_number(&self) -> bool {
+		matches!(self, Value::Number(_))
+	}
+
 	pub fn is_strand(&self) -> bool {
 		matches!(self, Value::Strand(_))
 	}
@@ -554,6 +558,38 @@ impl Value {
 		}
 	}
 
+	pub fn is_type(&self, kind: &Kind) -> bool {
+		match kind {
+			Kind::Any => true,
+			Kind::Bool => matches!(self, Value::True | Value::False),
+			Kind::Int => matches!(self, Value::Number(Number::Int(_))),
+			Kind::Float => matches!(self, Value::Number(Number::Float(_))),
+			Kind::Decimal => matches!(self, Value::Number(Number::Decimal(_))),
+			Kind::Number => matches!(self, Value::Number(_)),
+			Kind::String => matches!(self, Value::Strand(_)),
+			Kind::Datetime => matches!(self, Value::Datetime(_)),
+			Kind::Duration => matches!(self, Value::Duration(_)),
+			Kind::Object => matches!(self, Value::Object(_)),
+			Kind::Record(t) => self.is_type_record(t),
+			Kind::Geometry(t) => self.is_type_geometry(t),
+			Kind::Array => matches!(self, Value::Array(_)),
+			Kind::ArrayOf(k) | Kind::Set(k) => match self {
+				Value::Array(v) => v.iter().all(|v| v.is_type(k)),
+				_ => false,
+			},
+			Kind::Schema(k) => match self {
+				Value::Object(v) => {
+					v.keys().all(|f| k.contains_key(f))
+						&& k.iter().all(|(f, k)| v.get(f).unwrap_or(&Value::None).is_type(k))
+				}
+				_ => false,
+			},
+			Kind::Option(k) => matches!(self, Value::None | Value::Null) || self.is_type(k),
+			Kind::Either(k) => k.iter().any(|k| self.is_type(k)),
+			Kind::Literal(v) => self == v,
+		}
+	}
+
 	pub fn is_type_geometry(&self, types: &[String]) -> bool {
 		match self {
 			Value::Geometry(Geometry::Point(_)) => {
@@ -707,6 +743,7 @@ impl Value {
 				Function::Future(_) => "fn::future".to_string().into(),
 				Function::Script(_, _) => "fn::script".to_string().into(),
 				Function::Normal(f, _) => f.to_string().into(),
+				Function::Custom(f, _) => format!("fn::{}", f).into(),
 				Function::Cast(_, v) => v.to_idiom(),
 			},
 			_ => self.to_string().into(),
@@ -822,6 +859,24 @@ impl Value {
 				Value::Array(_) => self,
 				_ => Value::None,
 			},
+			Kind::ArrayOf(k) => match self {
+				Value::Array(v) => {
+					let mut out = Array::with_capacity(v.len());
+					for v in v {
+						// Elements which can't be converted invalidate the array
+						match (v.is_none(), v.convert_to(k)) {
+							(false, Value::None) => return Value::None,
+							(_, v) => out.push(v),
+						}
+					}
+					Value::Array(out)
+				}
+				_ => Value::None,
+			},
+			Kind::Set(k) => match self.convert_to(&Kind::ArrayOf(k.to_owned())) {
+				Value::Array(v) => Value::Array(v.uniq()),
+				v => v,
+			},
 			Kind::Object => match self {
 				Value::Object(_) => self,
 				_ => Value::None,
@@ -834,6 +889,42 @@ impl Value {
 				true => self,
 				_ => Value::None,
 			},
+			Kind::Option(k) => match self {
+				Value::None | Value::Null => self,
+				_ => self.convert_to(k),
+			},
+			Kind::Either(k) => match self.is_type(kind) {
+				true => self,
+				_ => k
+					.iter()
+					.map(|k| self.clone().convert_to(k))
+					.find(|v| !v.is_none())
+					.unwrap_or(Value::None),
+			},
+			Kind::Literal(v) => match self == *v {
+				true => self,
+				_ => Value::None,
+			},
+			Kind::Schema(k) => match self {
+				Value::Object(mut v) => {
+					let mut out = Object::default();
+					for (f, k) in k.iter() {
+						if let Some(v) = v.remove(f) {
+							// Fields which can't be converted invalidate the object
+							match (v.is_none(), v.convert_to(k)) {
+								(false, Value::None) => return Value::None,
+								(_, Value::None) => (),
+								(_, v) => {
+									out.insert(f.to_owned(), v);
+								}
+							}
+						}
+					}
+					// Fields which are not in the schema are removed
+					Value::Object(out)
+				}
+				_ => Value::None,
+			},
 		}
 	}
 
@@ -1094,7 +1185,7 @@ impl Value {
 		match self {
 			Value::Array(v) => v.iter().any(|v| v.writeable()),
 			Value::Object(v) => v.iter().any(|(_, v)| v.writeable()),
-			Value::Function(v) => v.args().iter().any(|v| v.writeable()),
+			Value::Function(v) => v.is_custom() || v.args().iter().any(|v| v.writeable()),
 			Value::Subquery(v) => v.writeable(),
 			Value::Expression(v) => v.l.writeable() || v.r.writeable(),
 			_ => false,
@@ -3,6 +3,9 @@ use crate::sql::cond::{cond, Cond};
 use crate::sql::error::IResult;
 use crate::sql::field::{fields, Fields};
 use crate::sql::group::{group, Groups};
+use crate::sql::limit::{limit, Limit};
+use crate::sql::order::{order, Orders};
+use crate::sql::start::{start, Start};
 use crate::sql::table::{tables, Tables};
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
@@ -10,12 +13,32 @@ use nom::sequence::preceded;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+// A view is maintained one source record at a time, so ORDER BY,
+// LIMIT, and START clauses are parsed only for a view definition to
+// be rejected with a clear error, as removing a record from an ordered
+// or limited view could require any other source record to be processed.
+// Fields which are read through record links are tracked for each source
+// record, so that the source records are processed again when a linked
+// record changes.
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct View {
 	pub expr: Fields,
 	pub what: Tables,
 	pub cond: Option<Cond>,
 	pub group: Option<Groups>,
+	#[serde(default)]
+	pub order: Option<Orders>,
+	#[serde(default)]
+	pub limit: Option<Limit>,
+	#[serde(default)]
+	pub start: Option<Start>,
+}
+
+impl View {
+	// Check if the view is ordered or limited
+	pub(crate) fn is_limited(&self) -> bool {
+		self.order.is_some() || self.limit.is_some() || self.start.is_some()
+	}
 }
 
 impl fmt::Display for View {
@@ -27,6 +50,15 @@ impl fmt::Display for View {
 		if let Some(ref v) = self.group {
 			write!(f, " {}", v)?
 		}
+		if let Some(ref v) = self.order {
+			write!(f, " {}", v)?
+		}
+		if let Some(ref v) = self.limit {
+			write!(f, " {}", v)?
+		}
+		if let Some(ref v) = self.start {
+			write!(f, " {}", v)?
+		}
 		Ok(())
 	}
 }
@@ -44,6 +76,9 @@ pub fn view(i: &str) -> IResult<&str, View> {
 	let (i, what) = tables(i)?;
 	let (i, cond) = opt(preceded(shouldbespace, cond))(i)?;
 	let (i, group) = opt(preceded(shouldbespace, group))(i)?;
+	let (i, order) = opt(preceded(shouldbespace, order))(i)?;
+	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
+	let (i, start) = opt(preceded(shouldbespace, start))(i)?;
 	let (i, _) = opt(tag_no_case(")"))(i)?;
 	Ok((
 		i,
@@ -52,6 +87,9 @@ pub fn view(i: &str) -> IResult<&str, View> {
 			what,
 			cond,
 			group,
+			order,
+			limit,
+			start,
 		},
 	))
 }
@@ -96,4 +134,14 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("AS SELECT temp FROM test WHERE temp != NONE GROUP BY temp", format!("{}", out))
 	}
+
+	#[test]
+	fn view_order_limit() {
+		let sql = "AS SELECT temp FROM test GROUP BY temp ORDER BY temp LIMIT 10 START 5";
+		let res = view(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert!(out.is_limited());
+		assert_eq!(sql, format!("{}", out))
+	}
 }
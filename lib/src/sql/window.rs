@@ -0,0 +1,455 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::array::Array;
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::{commas, take_usize};
+use crate::sql::error::IResult;
+use crate::sql::function::{function, Function};
+use crate::sql::idiom::{basic, Idiom, Idioms};
+use crate::sql::number::Number;
+use crate::sql::order::{order, Orders};
+use crate::sql::value::{value, Value};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::char;
+use nom::combinator::{map, opt, verify};
+use nom::multi::{separated_list0, separated_list1};
+use nom::sequence::{terminated, tuple};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Window {
+	pub partition: Option<Idioms>,
+	pub order: Option<Orders>,
+	pub frame: Option<Frame>,
+}
+
+impl fmt::Display for Window {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut v = vec![];
+		if let Some(ref p) = self.partition {
+			v.push(format!("PARTITION BY {}", p));
+		}
+		if let Some(ref o) = self.order {
+			v.push(format!("{}", o));
+		}
+		if let Some(ref r) = self.frame {
+			v.push(format!("{}", r));
+		}
+		write!(f, "OVER ({})", v.join(" "))
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Frame {
+	pub start: Bound,
+	pub end: Bound,
+}
+
+impl fmt::Display for Frame {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ROWS BETWEEN {} AND {}", self.start, self.end)
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum Bound {
+	UnboundedPreceding,
+	Preceding(usize),
+	CurrentRow,
+	Following(usize),
+	UnboundedFollowing,
+}
+
+impl Bound {
+	// Get the position of this bound relative to the current row
+	fn offset(&self) -> i64 {
+		match self {
+			Bound::UnboundedPreceding => i64::MIN,
+			Bound::Preceding(v) => -(*v as i64),
+			Bound::CurrentRow => 0,
+			Bound::Following(v) => *v as i64,
+			Bound::UnboundedFollowing => i64::MAX,
+		}
+	}
+	// Get the index of this bound within a partition
+	fn index(&self, pos: usize, len: usize) -> i64 {
+		match self {
+			Bound::UnboundedPreceding => 0,
+			Bound::UnboundedFollowing => len as i64 - 1,
+			v => pos as i64 + v.offset(),
+		}
+	}
+}
+
+impl fmt::Display for Bound {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Bound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+			Bound::Preceding(v) => write!(f, "{} PRECEDING", v),
+			Bound::CurrentRow => write!(f, "CURRENT ROW"),
+			Bound::Following(v) => write!(f, "{} FOLLOWING", v),
+			Bound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+		}
+	}
+}
+
+impl Window {
+	// Calculate the window function for each record
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		func: &Function,
+		path: &Idiom,
+		res: &mut [Value],
+	) -> Result<(), Error> {
+		// Check the function arguments
+		let (name, args) = match func {
+			Function::Normal(n, a) => (n.as_str(), a),
+			_ => unreachable!(),
+		};
+		match name {
+			"row_number" | "rank" if !args.is_empty() => {
+				return Err(Error::InvalidArguments {
+					name: name.to_owned(),
+					message: String::from("The function expects 0 arguments."),
+				})
+			}
+			"lag" | "lead" if args.is_empty() || args.len() > 3 => {
+				return Err(Error::InvalidArguments {
+					name: name.to_owned(),
+					message: String::from("The function expects 1 to 3 arguments."),
+				})
+			}
+			_ => (),
+		}
+		// Split the records into partitions
+		let mut parts: BTreeMap<Array, Vec<usize>> = BTreeMap::new();
+		for (i, v) in res.iter().enumerate() {
+			let key = match self.partition {
+				Some(ref p) => p.iter().map(|p| v.pick(p)).collect::<Vec<_>>().into(),
+				None => Array::new(),
+			};
+			parts.entry(key).or_default().push(i);
+		}
+		// Process each of the partitions
+		for (_, mut idx) in parts {
+			// Sort the partition records
+			if let Some(ref o) = self.order {
+				idx.sort_by(|a, b| o.compare(&res[*a], &res[*b]));
+			}
+			// Fetch the function input for each record
+			let vals: Vec<Value> = idx.iter().map(|i| res[*i].pick(path)).collect();
+			// Calculate the output for each record
+			let out = match name {
+				"row_number" | "rank" | "lag" | "lead" => {
+					let mut out = Vec::with_capacity(idx.len());
+					let mut rank = 1;
+					for (pos, i) in idx.iter().enumerate() {
+						let doc = &res[*i];
+						let v = match name {
+							"row_number" => Value::from(pos as i64 + 1),
+							"rank" => {
+								// Records with equal ordering share a rank
+								if let Some(ref o) = self.order {
+									if pos > 0
+										&& o.compare(&res[idx[pos - 1]], doc) != Ordering::Equal
+									{
+										rank = pos as i64 + 1;
+									}
+								}
+								Value::from(rank)
+							}
+							_ => {
+								// Get the number of records to move
+								let num = match args.get(1) {
+									Some(v) => v.compute(ctx, opt, txn, Some(doc)).await?.as_int(),
+									None => 1,
+								};
+								if num < 0 {
+									return Err(Error::InvalidArguments {
+										name: name.to_owned(),
+										message: String::from(
+											"The offset must be a positive number.",
+										),
+									});
+								}
+								// Get the record at the offset
+								let at = match name {
+									"lag" => pos as i64 - num,
+									_ => pos as i64 + num,
+								};
+								match at >= 0 && at < vals.len() as i64 {
+									true => vals[at as usize].clone(),
+									false => match args.get(2) {
+										Some(v) => v.compute(ctx, opt, txn, Some(doc)).await?,
+										None => Value::None,
+									},
+								}
+							}
+						};
+						out.push(v);
+					}
+					out
+				}
+				_ => self.frames(ctx, opt, txn, func, vals).await?,
+			};
+			// Store the output on each record
+			for (i, v) in idx.into_iter().zip(out) {
+				res[i].set(ctx, opt, txn, path, v).await?;
+			}
+		}
+		Ok(())
+	}
+	// Calculate an aggregate function over the frame of each record
+	async fn frames(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		func: &Function,
+		vals: Vec<Value>,
+	) -> Result<Vec<Value>, Error> {
+		// Without ordering the frame is the whole partition
+		let (beg, end) = match (&self.frame, &self.order) {
+			(Some(f), _) => (&f.start, &f.end),
+			(None, Some(_)) => (&Bound::UnboundedPreceding, &Bound::CurrentRow),
+			(None, None) => (&Bound::UnboundedPreceding, &Bound::UnboundedFollowing),
+		};
+		let len = vals.len();
+		// The whole partition is aggregated only once
+		if beg == &Bound::UnboundedPreceding && end == &Bound::UnboundedFollowing {
+			let v = func.aggregate(vals.into()).compute(ctx, opt, txn, None).await?;
+			return Ok(vec![v; len]);
+		}
+		let name = match func {
+			Function::Normal(n, _) => n.as_str(),
+			_ => unreachable!(),
+		};
+		let mut out = Vec::with_capacity(len);
+		match name {
+			// These aggregates are kept as running totals
+			"count" | "math::sum" | "math::mean" => {
+				let mut sum = Number::from(0);
+				let mut count = 0;
+				// The frame records which are in the totals
+				let (mut lo, mut hi) = (0, 0);
+				for pos in 0..len {
+					let b = beg.index(pos, len).max(0) as usize;
+					let e = (end.index(pos, len) + 1).clamp(0, len as i64) as usize;
+					// Add the records which enter the frame
+					while hi < e {
+						match name {
+							"count" => count += vals[hi].is_truthy() as i64,
+							_ => sum = sum + vals[hi].clone().as_number(),
+						}
+						hi += 1;
+					}
+					// Remove the records which leave the frame
+					while lo < b && lo < hi {
+						match name {
+							"count" => count -= vals[lo].is_truthy() as i64,
+							_ => sum = sum - vals[lo].clone().as_number(),
+						}
+						lo += 1;
+					}
+					let v = match name {
+						"count" => Value::from(count),
+						"math::sum" => Value::from(sum.clone()),
+						// An empty frame has no mean
+						_ if lo == hi => Value::None,
+						_ => Value::from(sum.clone() / Number::from(hi - lo)),
+					};
+					out.push(v);
+				}
+			}
+			// Other aggregates are calculated for each frame
+			_ => {
+				for pos in 0..len {
+					let b = beg.index(pos, len).max(0);
+					let e = end.index(pos, len).min(len as i64 - 1);
+					let x = match b <= e {
+						true => vals[b as usize..=e as usize].to_vec(),
+						false => vec![],
+					};
+					out.push(func.aggregate(x.into()).compute(ctx, opt, txn, None).await?);
+				}
+			}
+		}
+		Ok(out)
+	}
+}
+
+pub fn windowed(i: &str) -> IResult<&str, Function> {
+	alt((analytic, verify(function, |f| f.is_aggregate())))(i)
+}
+
+fn analytic(i: &str) -> IResult<&str, Function> {
+	let (i, s) = alt((tag("row_number"), tag("rank"), tag("lag"), tag("lead")))(i)?;
+	let (i, _) = char('(')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, a) = separated_list0(commas, value)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char(')')(i)?;
+	Ok((i, Function::Normal(s.to_string(), a)))
+}
+
+pub fn window(i: &str) -> IResult<&str, Window> {
+	let (i, _) = tag_no_case("OVER")(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = char('(')(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, partition) = opt(terminated(partition, mightbespace))(i)?;
+	let (i, order) = opt(terminated(order, mightbespace))(i)?;
+	let (i, frame) = opt(terminated(frame, mightbespace))(i)?;
+	let (i, _) = char(')')(i)?;
+	Ok((
+		i,
+		Window {
+			partition,
+			order,
+			frame,
+		},
+	))
+}
+
+fn partition(i: &str) -> IResult<&str, Idioms> {
+	let (i, _) = tag_no_case("PARTITION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("BY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = separated_list1(commas, basic)(i)?;
+	Ok((i, Idioms(v)))
+}
+
+fn frame(i: &str) -> IResult<&str, Frame> {
+	let (i, _) = tag_no_case("ROWS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	verify(alt((frame_between, frame_start)), |f: &Frame| f.start.offset() <= f.end.offset())(i)
+}
+
+fn frame_between(i: &str) -> IResult<&str, Frame> {
+	let (i, _) = tag_no_case("BETWEEN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, start) = bound(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("AND")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, end) = bound(i)?;
+	Ok((
+		i,
+		Frame {
+			start,
+			end,
+		},
+	))
+}
+
+fn frame_start(i: &str) -> IResult<&str, Frame> {
+	let (i, start) = bound(i)?;
+	Ok((
+		i,
+		Frame {
+			start,
+			end: Bound::CurrentRow,
+		},
+	))
+}
+
+fn bound(i: &str) -> IResult<&str, Bound> {
+	alt((
+		map(tuple((tag_no_case("UNBOUNDED"), shouldbespace, tag_no_case("PRECEDING"))), |_| {
+			Bound::UnboundedPreceding
+		}),
+		map(tuple((tag_no_case("UNBOUNDED"), shouldbespace, tag_no_case("FOLLOWING"))), |_| {
+			Bound::UnboundedFollowing
+		}),
+		map(tuple((tag_no_case("CURRENT"), shouldbespace, tag_no_case("ROW"))), |_| {
+			Bound::CurrentRow
+		}),
+		map(tuple((take_usize, shouldbespace, tag_no_case("PRECEDING"))), |(v, _, _)| {
+			Bound::Preceding(v)
+		}),
+		map(tuple((take_usize, shouldbespace, tag_no_case("FOLLOWING"))), |(v, _, _)| {
+			Bound::Following(v)
+		}),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn window_empty() {
+		let sql = "OVER ()";
+		let res = window(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("OVER ()", format!("{}", out));
+		assert_eq!(out, Window::default());
+	}
+
+	#[test]
+	fn window_partition_order() {
+		let sql = "OVER (PARTITION BY sensor, room ORDER BY time DESC)";
+		let res = window(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("OVER (PARTITION BY sensor, room ORDER BY time DESC)", format!("{}", out));
+	}
+
+	#[test]
+	fn window_frame() {
+		let sql = "OVER (ORDER BY time ROWS BETWEEN 2 PRECEDING AND 1 FOLLOWING)";
+		let res = window(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"OVER (ORDER BY time ROWS BETWEEN 2 PRECEDING AND 1 FOLLOWING)",
+			format!("{}", out)
+		);
+		assert_eq!(
+			out.frame,
+			Some(Frame {
+				start: Bound::Preceding(2),
+				end: Bound::Following(1),
+			})
+		);
+	}
+
+	#[test]
+	fn window_frame_start() {
+		let sql = "OVER (ORDER BY time ROWS UNBOUNDED PRECEDING)";
+		let res = window(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(
+			"OVER (ORDER BY time ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)",
+			format!("{}", out)
+		);
+	}
+
+	#[test]
+	fn window_frame_invalid() {
+		let sql = "OVER (ORDER BY time ROWS BETWEEN 1 FOLLOWING AND 1 PRECEDING)";
+		let res = window(sql);
+		assert!(res.is_err());
+	}
+
+	#[test]
+	fn window_functions() {
+		assert!(windowed("row_number()").is_ok());
+		assert!(windowed("lag(value, 2, 0)").is_ok());
+		assert!(windowed("math::sum(value)").is_ok());
+		assert!(windowed("string::lowercase(value)").is_err());
+	}
+}
@@ -0,0 +1,230 @@
+mod parse;
+use chrono::{DateTime, Utc};
+use parse::Parse;
+use std::io::Cursor;
+use std::time::Duration;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn backup(dbs: &Datastore, since: Option<DateTime<Utc>>) -> Result<Vec<u8>, Error> {
+	let (snd, rcv) = surrealdb::channel::new(1);
+	let (res, out) = futures::join!(dbs.backup(since, snd), async move {
+		let mut out = vec![];
+		while let Ok(v) = rcv.recv().await {
+			out.extend(v);
+		}
+		out
+	});
+	res.map(|_| out)
+}
+
+#[tokio::test]
+async fn backup_and_restore() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Take a backup of the datastore
+	let data = backup(&dbs, None).await?;
+	assert!(data.starts_with(surrealdb::backup::MAGIC));
+	let info = surrealdb::backup::verify(&data[..])?;
+	assert_eq!(info.version, surrealdb::backup::VERSION);
+	assert!(info.total > 0);
+	// Restore the backup into a datastore with more data than a block
+	let new = Datastore::new("memory").await?;
+	let sql: String =
+		(0..1500).map(|i| format!("CREATE other:{} SET name = 'Other';", i)).collect();
+	let res = &mut new.execute(&sql, &ses, None, false).await?;
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	let res = new.restore(Cursor::new(&data), None).await?;
+	assert_eq!(res.total, info.total);
+	//
+	let sql = "
+		SELECT name FROM person ORDER BY name;
+		SELECT count() FROM other GROUP BY ALL;
+		CREATE person:copy SET name = 'Tobie';
+	";
+	let res = &mut new.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Jaime' }, { name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IndexExists { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn backup_restore_rejects_invalid_data() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res =
+		&mut dbs.execute("CREATE person:tobie SET name = 'Tobie';", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	// Truncate a valid backup
+	let data = backup(&dbs, None).await?;
+	let tmp = dbs.restore(Cursor::new(&data[..data.len() - 10]), None).await;
+	assert!(matches!(tmp, Err(Error::InvalidBackup { .. })));
+	// Restore something which is not a backup
+	let tmp = dbs.restore(Cursor::new(b"CREATE person:jaime;"), None).await;
+	assert!(matches!(tmp, Err(Error::InvalidBackup { .. })));
+	// Corrupt the last block of a backup with more data than a block
+	let sql: String =
+		(0..1500).map(|i| format!("CREATE other:{} SET name = 'Other';", i)).collect();
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	let mut data = backup(&dbs, None).await?;
+	let pos = data.len() - 100;
+	data[pos] ^= 0xff;
+	let tmp = dbs.restore(Cursor::new(&data), None).await;
+	assert!(matches!(tmp, Err(Error::InvalidBackup { .. })));
+	// The existing data is left untouched
+	let res = &mut dbs.execute("SELECT name FROM person;", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn backup_incremental_point_in_time() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_change_log(Some(Duration::from_secs(3600)));
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	// Take a full backup of the datastore
+	let full = backup(&dbs, None).await?;
+	let info = surrealdb::backup::verify(&full[..])?;
+	assert_eq!(info.kind, surrealdb::backup::Kind::Full);
+	// Make some changes, followed by a bad deploy
+	let res = &mut dbs.execute("UPDATE person:jaime SET age = 30;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	let good = Utc::now();
+	let res = &mut dbs.execute("DELETE person; CREATE person:bad;", &ses, None, false).await?;
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	// Take an incremental backup of the changes
+	let incr = backup(&dbs, Some(info.time)).await?;
+	let tmp = surrealdb::backup::verify(&incr[..])?;
+	assert_eq!(tmp.kind, surrealdb::backup::Kind::Incremental);
+	assert_eq!(tmp.since, Some(info.time));
+	assert_eq!(tmp.total, 3);
+	// Restore to just before the bad deploy
+	let new = Datastore::new("memory").await?;
+	new.restore(Cursor::new(&full), None).await?;
+	new.restore(Cursor::new(&incr), Some(good)).await?;
+	let res = &mut new.execute("SELECT * FROM person;", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, name: 'Jaime', age: 30 },
+			{ id: person:tobie, name: 'Tobie' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Restore all of the changes
+	let new = Datastore::new("memory").await?;
+	new.restore(Cursor::new(&full), None).await?;
+	new.restore(Cursor::new(&incr), None).await?;
+	let res = &mut new.execute("SELECT * FROM person;", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:bad }]");
+	assert_eq!(tmp, val);
+	// A full backup can not be restored to an earlier time
+	let tmp = new.restore(Cursor::new(&full), Some(info.time - chrono::Duration::seconds(1))).await;
+	assert!(matches!(tmp, Err(Error::InvalidBackup { .. })));
+	// Pruned changes are no longer backed up
+	dbs.prune(Utc::now()).await?;
+	let incr = backup(&dbs, Some(info.time)).await?;
+	let tmp = surrealdb::backup::verify(&incr[..])?;
+	assert_eq!(tmp.total, 0);
+	// Restored entries are not recorded in the change log
+	let new = Datastore::new("memory").await?.with_change_log(Some(Duration::from_secs(3600)));
+	let info = surrealdb::backup::verify(&backup(&new, None).await?[..])?;
+	new.restore(Cursor::new(&full), None).await?;
+	let incr = backup(&new, Some(info.time)).await?;
+	let tmp = surrealdb::backup::verify(&incr[..])?;
+	assert_eq!(tmp.total, 0);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn backup_incremental_change_log() -> Result<(), Error> {
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	// The change log is not recorded by default
+	let dbs = Datastore::new("memory").await?;
+	let full = backup(&dbs, None).await?;
+	let info = surrealdb::backup::verify(&full[..])?;
+	let tmp = backup(&dbs, Some(info.time)).await;
+	assert!(matches!(tmp, Err(Error::ChangeLogDisabled)));
+	// The changes are removed after the retention
+	let dbs = Datastore::new("memory").await?.with_change_log(Some(Duration::from_millis(20)));
+	let full = backup(&dbs, None).await?;
+	let info = surrealdb::backup::verify(&full[..])?;
+	let res = &mut dbs.execute("CREATE person:tobie;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	dbs.tick().await?;
+	let incr = backup(&dbs, Some(info.time)).await?;
+	let tmp = surrealdb::backup::verify(&incr[..])?;
+	assert_eq!(tmp.total, 1);
+	std::thread::sleep(Duration::from_millis(50));
+	dbs.tick().await?;
+	let incr = backup(&dbs, Some(info.time)).await?;
+	let tmp = surrealdb::backup::verify(&incr[..])?;
+	assert_eq!(tmp.total, 0);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn backup_incremental_large_transaction() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?.with_change_log(Some(Duration::from_secs(3600)));
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let full = backup(&dbs, None).await?;
+	let info = surrealdb::backup::verify(&full[..])?;
+	// Make a large number of changes in a single transaction
+	let sql: String = (0..200)
+		.map(|i| format!("CREATE person:{} SET text = string::repeat('x', 2000);", i))
+		.collect();
+	let sql = format!("BEGIN; {} COMMIT;", sql);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	// The changes are stored in more than one change log entry
+	let incr = backup(&dbs, Some(info.time)).await?;
+	let tmp = surrealdb::backup::verify(&incr[..])?;
+	assert!(tmp.total > 1);
+	// All of the changes are restored
+	let new = Datastore::new("memory").await?;
+	new.restore(Cursor::new(&full), None).await?;
+	new.restore(Cursor::new(&incr), None).await?;
+	let sql = "SELECT count() FROM person WHERE string::length(text) = 2000 GROUP BY ALL;";
+	let res = &mut new.execute(sql, &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 200 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
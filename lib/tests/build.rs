@@ -0,0 +1,225 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn users(dbs: Datastore) -> Result<(Datastore, Session), Error> {
+	let sql = "
+		CREATE |user:1..2500| SET email = rand::uuid(), age = 20;
+		UPDATE user:1500, user:2500 SET age = 50;
+	";
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	Ok((dbs, ses))
+}
+
+#[tokio::test]
+async fn build_index_in_batches() -> Result<(), Error> {
+	let (dbs, ses) = users(Datastore::new("memory").await?.with_index_builds()).await?;
+	let sql = "
+		DEFINE INDEX age ON user FIELDS age;
+		INFO FOR TABLE user;
+		EXPLAIN SELECT id FROM user WHERE age = 50;
+		UPDATE user:2000 SET age = 50;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	assert!(res.remove(0).result.is_ok());
+	// The index is building until every record is indexed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ age: { status: 'building', records: 1000 } }");
+	assert_eq!(tmp.pick(&["ib".into()]), val);
+	// The planner ignores the index while it is building
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ type: 'Table', detail: 'user' }]");
+	assert_eq!(tmp.pick(&["iterables".into()]), val);
+	assert!(res.remove(0).result.is_ok());
+	// Continue the build in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		EXPLAIN SELECT id FROM user WHERE age = 50;
+		SELECT id FROM user WHERE age = 50 ORDER BY id;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["ib".into()]), Value::parse("{}"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ type: 'Index', detail: 'INDEX age ON user WHERE age = 50' }]");
+	assert_eq!(tmp.pick(&["iterables".into()]), val);
+	// Records written during the build are also indexed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1500 }, { id: user:2000 }, { id: user:2500 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn build_index_failure() -> Result<(), Error> {
+	let (dbs, ses) = users(Datastore::new("memory").await?.with_index_builds()).await?;
+	let sql = "
+		UPDATE user:1501, user:1502 SET email = 'tobie@surrealdb.com';
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// The build stops at the batch with the duplicate value
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		EXPLAIN SELECT id FROM user WHERE email = 'tobie@surrealdb.com';
+		REMOVE INDEX email ON user;
+		INFO FOR TABLE user;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			email: {
+				status: 'failed',
+				records: 1000,
+				error: 'Database index `email` already contains \"tobie@surrealdb.com\", with record `user:1502`'
+			}
+		}",
+	);
+	assert_eq!(tmp.pick(&["ib".into()]), val);
+	// The planner ignores the failed index
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ type: 'Table', detail: 'user' }]");
+	assert_eq!(tmp.pick(&["iterables".into()]), val);
+	assert!(res.remove(0).result.is_ok());
+	// Removing the index removes the failed build
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["ib".into()]), Value::parse("{}"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_index() -> Result<(), Error> {
+	let (dbs, ses) = users(Datastore::new("memory").await?.with_index_builds()).await?;
+	let sql = "
+		UPDATE user:1501, user:1502 SET email = 'tobie@surrealdb.com';
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// The build stops at the batch with the duplicate value
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		UPDATE user:1502 SET email = 'jaime@surrealdb.com';
+		REBUILD INDEX email ON user;
+		INFO FOR TABLE user;
+		REBUILD INDEX other ON user;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// The index is built again from the first record
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ email: { status: 'building', records: 1000 } }");
+	assert_eq!(tmp.pick(&["ib".into()]), val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IxNotFound)));
+	// Continue the build in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		SELECT id FROM user WHERE email = 'tobie@surrealdb.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["ib".into()]), Value::parse("{}"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1501 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn build_index_without_background_builds() -> Result<(), Error> {
+	let (dbs, ses) = users(Datastore::new("memory").await?).await?;
+	let sql = "
+		DEFINE INDEX age ON user FIELDS age;
+		INFO FOR TABLE user;
+		EXPLAIN SELECT id FROM user WHERE age = 50;
+		SELECT id FROM user WHERE age = 50 ORDER BY id;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	assert!(res.remove(0).result.is_ok());
+	// The index is built by the statement which defines it
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["ib".into()]), Value::parse("{}"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ type: 'Index', detail: 'INDEX age ON user WHERE age = 50' }]");
+	assert_eq!(tmp.pick(&["iterables".into()]), val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1500 }, { id: user:2500 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn build_foreign_table_in_batches() -> Result<(), Error> {
+	let (dbs, ses) = users(Datastore::new("memory").await?.with_index_builds()).await?;
+	let sql = "
+		DEFINE TABLE user_by_age AS SELECT count(), age FROM user GROUP BY age;
+		INFO FOR TABLE user_by_age;
+		UPDATE user:500, user:2000 SET age = 50;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.remove(0).result.is_ok());
+	// The view is building until every record is processed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ status: 'building', records: 1000 }");
+	assert_eq!(tmp.pick(&["vb".into()]), val);
+	assert!(res.remove(0).result.is_ok());
+	// Continue the build in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user_by_age;
+		SELECT count, age FROM user_by_age ORDER BY age;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["vb".into()]), Value::None);
+	// Records written during the build are counted once
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2496, age: 20 }, { count: 4, age: 50 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,222 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+// Remove the change versionstamps and timestamps, checking they are present
+fn changes(val: Value) -> (Vec<u64>, Value) {
+	let mut vs = vec![];
+	match val {
+		Value::Array(mut v) => {
+			for v in v.0.iter_mut() {
+				match v {
+					Value::Object(v) => {
+						assert!(matches!(v.remove("timestamp"), Some(Value::Datetime(_))));
+						match v.remove("versionstamp") {
+							Some(Value::Number(v)) => vs.push(v.as_int() as u64),
+							v => panic!("Unexpected versionstamp: {:?}", v),
+						}
+					}
+					v => panic!("Unexpected change: {}", v),
+				}
+			}
+			(vs, Value::Array(v))
+		}
+		v => panic!("Unexpected changes: {}", v),
+	}
+}
+
+#[tokio::test]
+async fn changefeed_show_changes() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:tobie SET name = 'Tobie';
+		UPDATE person:tobie SET name = 'Tobie Morgan';
+		CREATE person:jaime SET name = 'Jaime';
+		DELETE person:tobie;
+		CREATE other:test SET name = 'Test';
+		SHOW CHANGES FOR TABLE person SINCE 0;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let (vs, tmp) = changes(res.remove(0).result?);
+	let val = Value::parse(
+		"[
+			{ update: { id: person:tobie, name: 'Tobie' } },
+			{ update: { id: person:tobie, name: 'Tobie Morgan' } },
+			{ update: { id: person:jaime, name: 'Jaime' } },
+			{ delete: { id: person:tobie } },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Each transaction has a greater versionstamp
+	assert!(vs.windows(2).all(|v| v[0] < v[1]));
+	//
+	let sql = format!(
+		"
+		SHOW CHANGES FOR TABLE person SINCE {} LIMIT 1;
+		SHOW CHANGES FOR TABLE person SINCE {};
+		SHOW CHANGES FOR TABLE other SINCE 0;
+		INFO FOR DB;
+		",
+		vs[2],
+		vs[3] + 1,
+	);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let (tmp, val) = changes(res.remove(0).result?);
+	assert_eq!(tmp, vec![vs[2]]);
+	assert_eq!(val, Value::parse("[{ update: { id: person:jaime, name: 'Jaime' } }]"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			dl: {},
+			dt: {},
+			fc: {},
+			sc: {},
+			tb: {
+				other: 'DEFINE TABLE other SCHEMALESS PERMISSIONS NONE',
+				person: 'DEFINE TABLE person SCHEMALESS CHANGEFEED 1h',
+			},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn changefeed_ignores_failed_transactions() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:tobie SET name = 'Tobie';
+		BEGIN TRANSACTION;
+		CREATE person:jaime SET name = 'Jaime';
+		CANCEL TRANSACTION;
+		CREATE person:jaime SET name = 'Jaime';
+		SHOW CHANGES FOR TABLE person SINCE 0;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryCancelled)));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let (_, tmp) = changes(res.remove(0).result?);
+	let val = Value::parse(
+		"[
+			{ update: { id: person:tobie, name: 'Tobie' } },
+			{ update: { id: person:jaime, name: 'Jaime' } },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn changefeed_orders_changes_within_transactions() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		BEGIN TRANSACTION;
+		CREATE person:tobie SET name = 'Tobie';
+		UPDATE person:tobie SET name = 'Tobie Morgan';
+		CREATE person:jaime SET name = 'Jaime';
+		COMMIT TRANSACTION;
+		DELETE person:tobie;
+		SHOW CHANGES FOR TABLE person SINCE 0;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let (vs, tmp) = changes(res.remove(0).result?);
+	let val = Value::parse(
+		"[
+			{ update: { id: person:tobie, name: 'Tobie' } },
+			{ update: { id: person:tobie, name: 'Tobie Morgan' } },
+			{ update: { id: person:jaime, name: 'Jaime' } },
+			{ delete: { id: person:tobie } },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The changes of a transaction share its versionstamp
+	assert_eq!(vs[0], vs[1]);
+	assert_eq!(vs[1], vs[2]);
+	assert!(vs[2] < vs[3]);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn changefeed_removes_expired_changes() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 100ms;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	// Wait for the changes to expire
+	std::thread::sleep(std::time::Duration::from_millis(150));
+	//
+	let sql = "
+		UPDATE person:jaime SET age = 30;
+		SHOW CHANGES FOR TABLE person SINCE 0;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// Expired changes are only removed by the datastore maintenance
+	let (vs, _) = changes(res.remove(0).result?);
+	assert_eq!(vs.len(), 3);
+	//
+	dbs.tick().await?;
+	let sql = "SHOW CHANGES FOR TABLE person SINCE 0;";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	let (_, tmp) = changes(res.remove(0).result?);
+	let val = Value::parse("[{ update: { id: person:jaime, name: 'Jaime', age: 30 } }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
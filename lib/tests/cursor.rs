@@ -0,0 +1,197 @@
+mod parse;
+use parse::Parse;
+use std::collections::BTreeMap;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn page(
+	dbs: &Datastore,
+	sql: &str,
+	ses: &Session,
+	after: Option<String>,
+) -> Result<(Vec<Value>, Option<String>), Error> {
+	let vars = after.map(|v| {
+		let mut vars = BTreeMap::new();
+		vars.insert(String::from("cursor"), Value::from(v));
+		vars
+	});
+	let res = &mut dbs.execute(sql, ses, vars, false).await?;
+	assert_eq!(res.len(), 1);
+	let res = res.remove(0);
+	match res.result? {
+		Value::Array(v) => Ok((v.0, res.next)),
+		v => panic!("unexpected result: {}", v),
+	}
+}
+
+#[tokio::test]
+async fn cursor_table_scan() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE |person:1..25|;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let sql = "SELECT * FROM person LIMIT 10 AFTER $cursor";
+	let (out, next) = page(&dbs, sql, &ses, None).await?;
+	assert_eq!(out.len(), 10);
+	assert_eq!(out[0], Value::parse("{ id: person:1 }"));
+	assert_eq!(out[9], Value::parse("{ id: person:10 }"));
+	assert!(next.is_some());
+	//
+	let (out, next) = page(&dbs, sql, &ses, next).await?;
+	assert_eq!(out.len(), 10);
+	assert_eq!(out[0], Value::parse("{ id: person:11 }"));
+	assert_eq!(out[9], Value::parse("{ id: person:20 }"));
+	assert!(next.is_some());
+	// Records created between pages are picked up
+	let res = &mut dbs.execute("CREATE person:30;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let (out, next) = page(&dbs, sql, &ses, next).await?;
+	assert_eq!(out.len(), 6);
+	assert_eq!(out[0], Value::parse("{ id: person:21 }"));
+	assert_eq!(out[5], Value::parse("{ id: person:30 }"));
+	assert!(next.is_none());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn cursor_filtered_scans() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE INDEX age ON person FIELDS age;
+		CREATE |person:1..20| SET age = rand::enum(10, 20, 30, 40);
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// Page through a record range, an index scan, and a table scan
+	for (sql, total) in [
+		("SELECT id FROM person:5..15 LIMIT 3 AFTER $cursor", 10),
+		("SELECT id, age FROM person WHERE age >= 30 LIMIT 3 AFTER $cursor", 0),
+		("SELECT id, age FROM person WHERE age + 0 >= 30 LIMIT 3 AFTER $cursor", 0),
+	] {
+		let mut all = vec![];
+		let mut next = None;
+		loop {
+			let (out, nxt) = page(&dbs, sql, &ses, next).await?;
+			assert!(out.len() <= 3);
+			all.extend(out);
+			match nxt {
+				Some(v) => next = Some(v),
+				None => break,
+			}
+		}
+		// Compare with the query results without pagination
+		let sql = sql.replace(" LIMIT 3 AFTER $cursor", "");
+		let (out, _) = page(&dbs, &sql, &ses, None).await?;
+		let mut sorted = out.clone();
+		sorted.sort();
+		sorted.dedup();
+		assert_eq!(sorted.len(), out.len());
+		assert_eq!(all, out);
+		if total > 0 {
+			assert_eq!(all.len(), total);
+		}
+	}
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn cursor_overlapping_index_ranges() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE INDEX age ON person FIELDS age;
+		CREATE |person:1..15| SET age = <decimal> 10;
+		UPDATE person:11, person:12, person:13, person:14, person:15 SET age = 20;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// Decimal values are scanned within the ranges of both values
+	let sql = "SELECT id FROM person WHERE age INSIDE [10, 20] LIMIT 3 AFTER $cursor";
+	let mut all = vec![];
+	let mut next = None;
+	loop {
+		let (out, nxt) = page(&dbs, sql, &ses, next).await?;
+		all.extend(out);
+		match nxt {
+			Some(v) => next = Some(v),
+			None => break,
+		}
+	}
+	// Each record is output once across all pages
+	assert_eq!(all.len(), 15);
+	all.sort();
+	all.dedup();
+	assert_eq!(all.len(), 15);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn cursor_multiple_targets() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE |person:1..3|, |animal:1..3|;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let sql = "SELECT * FROM person:tobie, person, [1, 2], animal LIMIT 4 AFTER $cursor";
+	let (out, next) = page(&dbs, sql, &ses, None).await?;
+	assert_eq!(
+		Value::from(out),
+		Value::parse("[{ id: person:1 }, { id: person:2 }, { id: person:3 }, 1]")
+	);
+	let (out, next) = page(&dbs, sql, &ses, next).await?;
+	assert_eq!(
+		Value::from(out),
+		Value::parse("[2, { id: animal:1 }, { id: animal:2 }, { id: animal:3 }]")
+	);
+	let (out, next) = page(&dbs, sql, &ses, next).await?;
+	assert_eq!(Value::from(out), Value::parse("[]"));
+	assert!(next.is_none());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn cursor_invalid() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE |person:1..5|, |animal:1..5|;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let (_, next) = page(&dbs, "SELECT * FROM animal LIMIT 2", &ses, None).await?;
+	assert!(next.is_some());
+	// A cursor from another table is rejected
+	let sql = "SELECT * FROM person LIMIT 2 AFTER $cursor";
+	let res = page(&dbs, sql, &ses, next).await;
+	assert!(matches!(res, Err(Error::InvalidCursor { .. })));
+	// A cursor from another query on the same table is rejected
+	let (_, next) = page(&dbs, "SELECT * FROM person LIMIT 2", &ses, None).await?;
+	let sql = "SELECT * FROM person WHERE id != person:1 LIMIT 2 AFTER $cursor";
+	let res = page(&dbs, sql, &ses, next).await;
+	assert!(matches!(res, Err(Error::InvalidCursor { .. })));
+	// A malformed cursor is rejected
+	let sql = "SELECT * FROM person LIMIT 2 AFTER $cursor";
+	let res = page(&dbs, sql, &ses, Some(String::from("cursor"))).await;
+	assert!(matches!(res, Err(Error::InvalidCursor { .. })));
+	// Sorted results can not be resumed
+	let sql = "SELECT * FROM person ORDER BY id DESC LIMIT 2 AFTER $cursor";
+	let res = page(&dbs, sql, &ses, None).await;
+	assert!(matches!(res, Err(Error::InvalidCursor { .. })));
+	// Sorted results have no cursor
+	let sql = "SELECT * FROM person ORDER BY id DESC LIMIT 2";
+	let (out, next) = page(&dbs, sql, &ses, None).await?;
+	assert_eq!(out.len(), 2);
+	assert!(next.is_none());
+	//
+	Ok(())
+}
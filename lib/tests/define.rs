@@ -76,6 +76,7 @@ async fn define_statement_table_drop() -> Result<(), Error> {
 		"{
 			dl: {},
 			dt: {},
+			fc: {},
 			sc: {},
 			tb: { test: 'DEFINE TABLE test DROP SCHEMALESS' },
 		}",
@@ -104,6 +105,7 @@ async fn define_statement_table_schemaless() -> Result<(), Error> {
 		"{
 			dl: {},
 			dt: {},
+			fc: {},
 			sc: {},
 			tb: { test: 'DEFINE TABLE test SCHEMALESS' },
 		}",
@@ -132,6 +134,7 @@ async fn define_statement_table_schemafull() -> Result<(), Error> {
 		"{
 			dl: {},
 			dt: {},
+			fc: {},
 			sc: {},
 			tb: { test: 'DEFINE TABLE test SCHEMAFULL' },
 		}",
@@ -173,6 +176,7 @@ async fn define_statement_event() -> Result<(), Error> {
 			ev: { test: 'DEFINE EVENT test ON user WHEN true THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -230,6 +234,7 @@ async fn define_statement_event_when_event() -> Result<(), Error> {
 			ev: { test: 'DEFINE EVENT test ON user WHEN $event = \"CREATE\" THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -287,6 +292,7 @@ async fn define_statement_event_when_logic() -> Result<(), Error> {
 			ev: { test: 'DEFINE EVENT test ON user WHEN $before.email != $after.email THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -336,6 +342,7 @@ async fn define_statement_field() -> Result<(), Error> {
 			ev: {},
 			fd: { test: 'DEFINE FIELD test ON user' },
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -368,6 +375,7 @@ async fn define_statement_field_type() -> Result<(), Error> {
 			ev: {},
 			fd: { test: 'DEFINE FIELD test ON user TYPE string' },
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -400,6 +408,7 @@ async fn define_statement_field_value() -> Result<(), Error> {
 			ev: {},
 			fd: { test: 'DEFINE FIELD test ON user VALUE $value OR \"GBR\"' },
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -432,6 +441,36 @@ async fn define_statement_field_assert() -> Result<(), Error> {
 			ev: {},
 			fd: { test: 'DEFINE FIELD test ON user ASSERT $value != NONE AND $value = /[A-Z]{3}/' },
 			ft: {},
+			ib: {},
+			ix: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_field_default_readonly() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD test ON user TYPE datetime READONLY DEFAULT time::now();
+		INFO FOR TABLE user;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			ev: {},
+			fd: { test: 'DEFINE FIELD test ON user TYPE datetime DEFAULT time::now() READONLY' },
+			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -464,6 +503,7 @@ async fn define_statement_field_type_value_assert() -> Result<(), Error> {
 			ev: {},
 			fd: { test: 'DEFINE FIELD test ON user TYPE string VALUE $value OR \"GBR\" ASSERT $value != NONE AND $value = /[A-Z]{3}/' },
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -506,6 +546,7 @@ async fn define_statement_index_single_simple() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: { test: 'DEFINE INDEX test ON user FIELDS age' },
 		}",
 	);
@@ -548,6 +589,7 @@ async fn define_statement_index_single() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: { test: 'DEFINE INDEX test ON user FIELDS email' },
 		}",
 	);
@@ -592,6 +634,7 @@ async fn define_statement_index_multiple() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: { test: 'DEFINE INDEX test ON user FIELDS account, email' },
 		}",
 	);
@@ -642,6 +685,7 @@ async fn define_statement_index_single_unique() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE' },
 		}",
 	);
@@ -688,6 +732,7 @@ async fn define_statement_index_multiple_unique() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: { test: 'DEFINE INDEX test ON user FIELDS account, email UNIQUE' },
 		}",
 	);
@@ -758,6 +803,7 @@ async fn define_statement_index_single_unique_existing() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -812,6 +858,7 @@ async fn define_statement_index_multiple_unique_existing() -> Result<(), Error>
 			ev: {},
 			fd: {},
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
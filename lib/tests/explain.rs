@@ -0,0 +1,207 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn explain_select_statement() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_age ON person FIELDS age;
+		EXPLAIN SELECT * FROM person WHERE age > 20 ORDER BY name LIMIT 5;
+		EXPLAIN SELECT * FROM person:tobie, person WHERE name = 'Tobie' FETCH friends PARALLEL;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Index', detail: 'INDEX idx_age ON person WHERE age > 20' },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+				{ stage: 'ORDER', detail: 'ORDER BY name' },
+				{ stage: 'LIMIT', detail: 'LIMIT 5' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: true,
+			iterables: [
+				{ type: 'Thing', detail: person:tobie },
+				{ type: 'Table', detail: 'person' },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+				{ stage: 'FETCH', detail: 'FETCH friends' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn explain_does_not_run_statement() -> Result<(), Error> {
+	let sql = "
+		EXPLAIN CREATE person:tobie SET name = 'Tobie';
+		SELECT * FROM person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Thing', detail: person:tobie },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn explain_analyze_statement() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', age = 30;
+		CREATE person:jaime SET name = 'Jaime', age = 18;
+		CREATE person:simon SET name = 'Simon', age = 41;
+		EXPLAIN ANALYZE SELECT * FROM person WHERE age > 20 ORDER BY name LIMIT 1;
+		EXPLAIN ANALYZE UPDATE person SET adult = true WHERE age > 20;
+		SELECT id FROM person WHERE adult = true ORDER BY id;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = match res.remove(0).result? {
+		Value::Object(v) => v,
+		v => panic!("expected an object, got {}", v),
+	};
+	assert_eq!(tmp.get("analyze"), Some(&Value::True));
+	assert_eq!(tmp.get("records"), Some(&Value::from(1)));
+	assert!(matches!(tmp.get("time"), Some(Value::Duration(_))));
+	let stages = match tmp.get("stages") {
+		Some(Value::Array(v)) => v.clone(),
+		_ => panic!("expected an array of stages"),
+	};
+	assert_eq!(stages.len(), 3);
+	let records: Vec<Value> = stages
+		.iter()
+		.map(|v| match v {
+			Value::Object(v) => {
+				assert!(matches!(v.get("time"), Some(Value::Duration(_))));
+				v.get("records").cloned().unwrap()
+			}
+			_ => panic!("expected a stage object"),
+		})
+		.collect();
+	assert_eq!(records, vec![Value::from(2), Value::from(2), Value::from(1)]);
+	//
+	let tmp = match res.remove(0).result? {
+		Value::Object(v) => v,
+		v => panic!("expected an object, got {}", v),
+	};
+	assert_eq!(tmp.get("records"), Some(&Value::from(2)));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:simon }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn explain_select_with_subqueries() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', age = 30;
+		CREATE person:jaime SET name = 'Jaime', age = 18;
+		DEFINE FUNCTION fn::adults() { SELECT id FROM person WHERE age > 20 };
+		EXPLAIN SELECT * FROM (SELECT id FROM person WHERE age > 20);
+		EXPLAIN SELECT * FROM fn::adults();
+		EXPLAIN ANALYZE SELECT * FROM person WHERE array::len((SELECT id FROM person WHERE age < 20)) = 1;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Subqueries in the targets return their records
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Thing', detail: person:tobie },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	// Functions in the targets return their records
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Value', detail: { id: person:tobie } },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	// Subqueries in the condition return their records
+	let tmp = match res.remove(0).result? {
+		Value::Object(v) => v,
+		v => panic!("expected an object, got {}", v),
+	};
+	assert_eq!(tmp.get("records"), Some(&Value::from(2)));
+	//
+	Ok(())
+}
@@ -95,3 +95,195 @@ async fn field_definition_value_assert_success() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn field_definition_nested_types() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMAFULL;
+		DEFINE FIELD name ON person TYPE string;
+		DEFINE FIELD nickname ON person TYPE option<string>;
+		DEFINE FIELD friends ON person TYPE array<record(person)>;
+		DEFINE FIELD tags ON person TYPE set<string>;
+		DEFINE FIELD status ON person TYPE 'active' | 'inactive';
+		DEFINE FIELD code ON person TYPE either<int, string>;
+		DEFINE FIELD address ON person TYPE { city: string, zip: option<int> };
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	for _ in 0..8 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	//
+	let sql = "
+		CREATE person:tobie CONTENT {
+			name: 'Tobie',
+			friends: [person:jaime],
+			tags: ['admin', 'owner', 'admin'],
+			status: 'active',
+			code: 'GBR',
+			address: { city: 'London', zip: '12345', other: true },
+		};
+		CREATE person:jaime CONTENT {
+			name: 'Jaime',
+			nickname: NULL,
+			friends: [],
+			tags: [],
+			status: 'inactive',
+			code: 10,
+			address: { city: 'London' },
+		};
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	// Values are converted and checked recursively
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:tobie,
+				name: 'Tobie',
+				friends: [person:jaime],
+				tags: ['admin', 'owner'],
+				status: 'active',
+				code: 'GBR',
+				address: { city: 'London', zip: 12345 },
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Optional fields can be empty
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:jaime,
+				name: 'Jaime',
+				nickname: NULL,
+				friends: [],
+				tags: [],
+				status: 'inactive',
+				code: 10,
+				address: { city: 'London' },
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_nested_types_failure() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMAFULL;
+		DEFINE FIELD friends ON person TYPE array<record(person)>;
+		DEFINE FIELD status ON person TYPE 'active' | 'inactive';
+		DEFINE FIELD address ON person TYPE option<{ city: string, zip: option<int> }>;
+		CREATE person:test SET friends = [person:tobie, user:jaime], status = 'active';
+		CREATE person:test SET friends = [], status = 'deleted';
+		CREATE person:test SET friends = [], status = 'active', address = { zip: 12345 };
+		CREATE person:test SET friends = [], status = 'active';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	for _ in 0..4 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found [person:tobie, user:jaime] for field `friends`, with record `person:test`, but field must be of type array<record(person)>"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found \"deleted\" for field `status`, with record `person:test`, but field must be of type \"active\" | \"inactive\""
+	));
+	// Nested fields of an object schema are checked
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found { zip: 12345 } for field `address`, with record `person:test`, but field must be of type option<{ city: string, zip: option<int> }>"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_default_readonly() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD owner ON post TYPE string DEFAULT 'tobie' READONLY;
+		DEFINE FIELD status ON post DEFAULT 'draft';
+		CREATE post:1 SET title = 'First';
+		CREATE post:2 SET title = 'Second', owner = 'jaime', status = 'published';
+		UPDATE post:1 SET title = 'Updated', status = NONE;
+		UPDATE post:2 SET owner = 'tobie';
+		UPDATE post:2 SET owner = 'jaime', status = 'draft';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// Default values are set when a field is absent
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:1, title: 'First', owner: 'tobie', status: 'draft' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val =
+		Value::parse("[{ id: post:2, title: 'Second', owner: 'jaime', status: 'published' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:1, title: 'Updated', owner: 'tobie', status: 'draft' }]");
+	assert_eq!(tmp, val);
+	// Readonly fields can not be changed once created
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found \"tobie\" for field `owner`, with record `post:2`, but field is readonly"
+	));
+	// Readonly fields can be set to the same value
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:2, title: 'Second', owner: 'jaime', status: 'draft' }]");
+	assert_eq!(tmp, val);
+	// Field definitions round-trip through an export
+	let (snd, rcv) = surrealdb::channel::new(1);
+	let (res, out) = futures::join!(dbs.export("test".into(), "test".into(), snd), async move {
+		let mut out = vec![];
+		while let Ok(v) = rcv.recv().await {
+			out.extend(v);
+		}
+		out
+	});
+	res?;
+	let sql = String::from_utf8(out).unwrap();
+	let dbs = Datastore::new("memory").await?;
+	dbs.execute(&sql, &ses, None, false).await?;
+	let res = &mut dbs.execute("INFO FOR TABLE post", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			owner: 'DEFINE FIELD owner ON post TYPE string DEFAULT \"tobie\" READONLY',
+			status: 'DEFINE FIELD status ON post DEFAULT \"draft\"',
+		}",
+	);
+	assert_eq!(tmp.pick(&["fd".into()]), val);
+	//
+	Ok(())
+}
@@ -0,0 +1,162 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn function_custom_definition() -> Result<(), Error> {
+	let sql = "
+		DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello, ' + $name + '!'; };
+		DEFINE FUNCTION fn::math::double($num: number) { $num * 2 };
+		CREATE person:tobie SET name = 'Tobie', age = '21';
+		RETURN fn::greet('Jaime');
+		SELECT fn::greet(name) AS greeting, fn::math::double(age) AS double FROM person;
+		INFO FOR DB;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from("Hello, Jaime!");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ greeting: 'Hello, Tobie!', double: 42 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			dl: {},
+			dt: {},
+			fc: {
+				'fn::greet': 'DEFINE FUNCTION fn::greet($name: string) { RETURN \"Hello, \" + $name + \"!\"; }',
+				'fn::math::double': 'DEFINE FUNCTION fn::math::double($num: number) { $num * 2; }',
+			},
+			sc: {},
+			tb: { person: 'DEFINE TABLE person SCHEMALESS PERMISSIONS NONE' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_custom_statements() -> Result<(), Error> {
+	let sql = "
+		DEFINE FUNCTION fn::visit($page: string) {
+			LET $id = type::thing('page', $page);
+			UPDATE $id SET visits += 1;
+			RETURN $id.visits;
+		};
+		RETURN fn::visit('home');
+		RETURN fn::visit('home');
+		SELECT * FROM page;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(1);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(2);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: page:home, visits: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_custom_errors() -> Result<(), Error> {
+	let sql = "
+		DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello, ' + $name + '!'; };
+		RETURN fn::greet('Tobie', 'Jaime');
+		RETURN fn::unknown();
+		REMOVE FUNCTION fn::greet;
+		RETURN fn::greet('Tobie');
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidArguments { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::FcNotFound { value }) if value == "unknown"));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::FcNotFound { value }) if value == "greet"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_custom_permissions() -> Result<(), Error> {
+	let sql = "
+		DEFINE FUNCTION fn::open() { true } PERMISSIONS FULL;
+		DEFINE FUNCTION fn::closed() { true } PERMISSIONS NONE;
+		DEFINE FUNCTION fn::admin() { true } PERMISSIONS WHERE $scope = 'admin';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	//
+	let sql = "
+		RETURN fn::open();
+		RETURN fn::closed();
+		RETURN fn::admin();
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.iter().all(|v| v.result.is_ok()));
+	//
+	let ses = Session::for_sc("test", "test", "user");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::True);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::FunctionPermissions { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::FunctionPermissions { .. })));
+	//
+	let ses = Session::for_sc("test", "test", "admin");
+	let res = &mut dbs.execute("RETURN fn::admin();", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::True);
+	//
+	Ok(())
+}
@@ -0,0 +1,380 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn network() -> Result<(Datastore, Session), Error> {
+	let sql = "
+		CREATE person:a, person:b, person:c, person:d, person:e;
+		RELATE person:a->knows->person:b;
+		RELATE person:b->knows->person:c;
+		RELATE person:c->knows->person:d;
+		RELATE person:d->knows->person:a;
+		RELATE person:a->knows->person:c;
+		RELATE person:d->likes->person:e;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 7);
+	for _ in 0..7 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	Ok((dbs, ses))
+}
+
+// Sorts the traversal output, as edges have random ids
+fn sorted(v: Value) -> Value {
+	match v {
+		Value::Array(mut v) => {
+			v.0.sort();
+			Value::from(v)
+		}
+		v => v,
+	}
+}
+
+// Replaces the edge placeholders with the generated edge ids
+async fn paths(dbs: &Datastore, ses: &Session, val: &str) -> Result<Value, Error> {
+	let sql = "SELECT id, in.id AS from, out.id AS with FROM knows;";
+	let res = &mut dbs.execute(sql, ses, None, false).await?;
+	let mut val = val.to_owned();
+	if let Value::Array(v) = res.remove(0).result? {
+		for v in v.iter() {
+			let id = v.pick(&["id".into()]).to_string();
+			let from = v.pick(&["from".into()]).to_string();
+			let with = v.pick(&["with".into()]).to_string();
+			let tag = format!("knows:{}{}", &from[7..], &with[7..]);
+			val = val.replace(&tag, &id);
+		}
+	}
+	Ok(Value::parse(&val))
+}
+
+#[tokio::test]
+async fn graph_recursive_traversal() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		RETURN person:a->knows{1..5}->person;
+		RETURN person:a->knows{2..3}->person;
+		RETURN person:a->knows{1}->person;
+		RETURN person:a->knows->person;
+		SELECT id, ->knows{..2}->person.id AS reach FROM person:b;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 5);
+	// The starting record is never returned, even through a cycle
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:b, person:c, person:d]");
+	assert_eq!(tmp, val);
+	// Only records first reached within the bounds are returned
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:d]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:b, person:c]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:b, person:c]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[{ id: person:b, reach: [person:c, person:d] }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_recursive_shortest_path() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		RETURN person:a->knows{1..5 SHORTEST PATH}->person;
+		RETURN person:b<-knows{1..5 SHORTEST PATH}<-person;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = sorted(
+		paths(
+			&dbs,
+			&ses,
+			"[
+			[person:a, knows:ab, person:b],
+			[person:a, knows:ac, person:c],
+			[person:a, knows:ac, person:c, knows:cd, person:d]
+		]",
+		)
+		.await?,
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = sorted(
+		paths(
+			&dbs,
+			&ses,
+			"[
+			[person:b, knows:ab, person:a],
+			[person:b, knows:ab, person:a, knows:da, person:d],
+			[person:b, knows:ab, person:a, knows:da, person:d, knows:cd, person:c]
+		]",
+		)
+		.await?,
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_recursive_all_paths() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		RETURN person:a->knows{1..5 ALL PATHS}->person;
+		RETURN person:a->knows{3 ALL PATHS}->person;
+		RETURN person:a->(knows, likes){2..5 ALL PATHS}->(person WHERE id != person:c);
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	// Paths never visit the same record twice
+	let tmp = sorted(res.remove(0).result?);
+	let val = sorted(
+		paths(
+			&dbs,
+			&ses,
+			"[
+			[person:a, knows:ab, person:b],
+			[person:a, knows:ab, person:b, knows:bc, person:c],
+			[person:a, knows:ab, person:b, knows:bc, person:c, knows:cd, person:d],
+			[person:a, knows:ac, person:c],
+			[person:a, knows:ac, person:c, knows:cd, person:d]
+		]",
+		)
+		.await?,
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = "[[person:a, knows:ab, person:b, knows:bc, person:c, knows:cd, person:d]]";
+	let val = sorted(paths(&dbs, &ses, val).await?);
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_recursive_all_paths_limit() -> Result<(), Error> {
+	// Every person knows every other person
+	let mut sql = String::new();
+	for a in 0..8 {
+		sql.push_str(&format!("CREATE person:{a};"));
+	}
+	for a in 0..8 {
+		for b in 0..8 {
+			if a != b {
+				sql.push_str(&format!("RELATE person:{a}->knows->person:{b};"));
+			}
+		}
+	}
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 64);
+	for _ in 0..64 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	let sql = "
+		RETURN array::len(person:0->knows{1..3 ALL PATHS}->person);
+		RETURN person:0->knows{1..7 ALL PATHS}->person;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(259);
+	assert_eq!(tmp, val);
+	// The number of simple paths grows too quickly
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(Error::TooManyGraphPaths {
+			limit: 10000
+		})
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_recursive_edge_conditions() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		UPDATE knows SET blocked = true WHERE out = person:b OR out = person:d;
+		RETURN person:a->(knows WHERE blocked != true){1..5}->person;
+		RETURN person:a->knows{1..5}->person.id;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.remove(0).result.is_ok());
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:c]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:b, person:c, person:d]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_function_neighbours_and_degree() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		RETURN graph::neighbours(person:a);
+		RETURN graph::neighbours(person:a, 'knows', 'out');
+		RETURN graph::neighbours(person:d, ['likes']);
+		RETURN graph::degree(person:a);
+		RETURN graph::degree(person:a, NONE, 'in');
+		RETURN graph::degree(person:d, 'likes', 'out');
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:b, person:c, person:d]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = sorted(res.remove(0).result?);
+	let val = Value::parse("[person:b, person:c]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:e]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("3");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_function_shortest_path() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		RETURN graph::shortest_path(person:a, person:d);
+		RETURN graph::shortest_path(person:d, person:b, 'knows');
+		RETURN graph::shortest_path(person:e, person:a);
+		RETURN graph::shortest_path(person:e, person:a, NONE, 'both');
+		RETURN graph::shortest_path(person:a, person:a);
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = "[person:a, knows:ac, person:c, knows:cd, person:d]";
+	assert_eq!(tmp, paths(&dbs, &ses, val).await?);
+	//
+	let tmp = res.remove(0).result?;
+	let val = "[person:d, knows:da, person:a, knows:ab, person:b]";
+	assert_eq!(tmp, paths(&dbs, &ses, val).await?);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[0.into()]), Value::parse("person:e"));
+	assert_eq!(tmp.pick(&[2.into()]), Value::parse("person:d"));
+	assert_eq!(tmp.pick(&[4.into()]), Value::parse("person:a"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:a]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_function_components_and_pagerank() -> Result<(), Error> {
+	let (dbs, ses) = network().await?;
+	let sql = "
+		CREATE person:f, person:g;
+		RELATE person:f->knows->person:g;
+		RETURN graph::connected_components('person', 'knows');
+		RETURN graph::connected_components([person:e, person:d]);
+		RETURN graph::pagerank('person', 'knows');
+		LET $rank = graph::pagerank('person');
+		RETURN math::sum($rank.rank);
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 7);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[person:a, person:b, person:c, person:d],
+			[person:e],
+			[person:f, person:g]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[person:d, person:e]]");
+	assert_eq!(tmp, val);
+	// The most linked record ranks highest
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[0.into(), "id".into()]), Value::parse("person:c"));
+	assert_eq!(tmp.pick(&[6.into(), "id".into()]), Value::parse("person:f"));
+	// The ranks are a probability distribution
+	assert!(res.remove(0).result.is_ok());
+	let tmp = res.remove(0).result?;
+	assert!((tmp.as_float() - 1.0).abs() < 1e-6);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_function_permissions() -> Result<(), Error> {
+	let (dbs, _) = network().await?;
+	let sql = "
+		RETURN graph::neighbours(person:a);
+		RETURN graph::degree(1);
+	";
+	let ses = Session::for_sc("test", "test", "test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(matches!(res.remove(0).result, Err(Error::QueryPermissions)));
+	assert!(matches!(res.remove(0).result, Err(Error::QueryPermissions)));
+	//
+	let ses = Session::for_db("test", "test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_ok());
+	assert!(matches!(res.remove(0).result, Err(Error::InvalidArguments { .. })));
+	//
+	Ok(())
+}
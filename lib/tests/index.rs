@@ -0,0 +1,252 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn setup(dbs: &Datastore, ses: &Session) -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX uniq_email ON person FIELDS email UNIQUE;
+		DEFINE INDEX idx_age ON person FIELDS age;
+		DEFINE INDEX idx_name ON person FIELDS country, city, name;
+		CREATE person:tobie SET email = 'tobie@surrealdb.com', age = 30, country = 'GB', city = 'London', name = 'Tobie';
+		CREATE person:jaime SET email = 'jaime@surrealdb.com', age = 25.5, country = 'GB', city = 'Bristol', name = 'Jaime';
+		CREATE person:simon SET email = 'simon@surrealdb.com', age = 41, country = 'GB', city = 'London', name = 'Simon';
+		CREATE person:marta SET email = 'marta@surrealdb.com', age = 18, country = 'ES', city = 'Madrid', name = 'Marta';
+		CREATE person:lucas SET email = 'lucas@surrealdb.com', country = 'ES', city = 'Madrid', name = 'Lucas';
+	";
+	let res = dbs.execute(&sql, ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	for v in res {
+		assert!(v.result.is_ok());
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_index_equality() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	setup(&dbs, &ses).await?;
+	let sql = "
+		SELECT id FROM person WHERE email = 'jaime@surrealdb.com';
+		SELECT id FROM person WHERE age = 30;
+		SELECT id FROM person WHERE 25.5 = age;
+		SELECT id FROM person WHERE email = 'nobody@surrealdb.com';
+	";
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_index_inside() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	setup(&dbs, &ses).await?;
+	let sql = "
+		LET $emails = ['marta@surrealdb.com', 'tobie@surrealdb.com', 'nobody@surrealdb.com'];
+		SELECT id FROM person WHERE email INSIDE $emails ORDER BY id;
+		SELECT id FROM person WHERE age INSIDE [18, 41, 25] ORDER BY id;
+	";
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:marta }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:marta }, { id: person:simon }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_index_range() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	setup(&dbs, &ses).await?;
+	let sql = "
+		SELECT id FROM person WHERE age > 25 AND age <= 41 ORDER BY id;
+		SELECT id FROM person WHERE age < 26 ORDER BY id;
+		SELECT id FROM person WHERE 30 < age ORDER BY id;
+		SELECT id FROM person WHERE email >= 'm' AND email < 't' ORDER BY id;
+	";
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }, { id: person:simon }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }, { id: person:lucas }, { id: person:marta }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:simon }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:marta }, { id: person:simon }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_index_compound_prefix() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	setup(&dbs, &ses).await?;
+	let sql = "
+		SELECT id FROM person WHERE country = 'GB' ORDER BY id;
+		SELECT id FROM person WHERE city = 'London' AND country = 'GB' ORDER BY id;
+		SELECT id FROM person WHERE country = 'GB' AND city = 'London' AND name > 'Z';
+		SELECT id FROM person WHERE country INSIDE ['ES', 'GB'] AND city = 'Madrid' ORDER BY id;
+		SELECT id FROM person WHERE country = 'GB' AND name = 'Jaime';
+	";
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }, { id: person:simon }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:simon }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:lucas }, { id: person:marta }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn update_and_delete_with_index() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	setup(&dbs, &ses).await?;
+	let sql = "
+		UPDATE person SET age = age + 100 WHERE age >= 30 RETURN NONE;
+		SELECT id, age FROM person WHERE age > 100 ORDER BY id;
+		DELETE person WHERE country = 'ES' AND city = 'Madrid';
+		SELECT id FROM person ORDER BY id;
+	";
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:simon, age: 141 }, { id: person:tobie, age: 130 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }, { id: person:simon }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_index_matches_unindexed_equality() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		LET $data = [
+			{ num: 1, val: 30 },
+			{ num: 2, val: '30' },
+			{ num: 3, val: 30.0 },
+			{ num: 4, val: 1h },
+			{ num: 5, val: '1h' },
+			{ num: 6, val: person:tobie },
+			{ num: 7, val: 'person:tobie' },
+			{ num: 8, val: true },
+			{ num: 9, val: 'true' },
+			{ num: 10, val: 'abc' },
+			{ num: 11, val: null },
+			{ num: 12 },
+		];
+		DEFINE INDEX idx_val ON indexed FIELDS val;
+		INSERT INTO indexed $data;
+		INSERT INTO plain $data;
+	";
+	let res = dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	for v in res {
+		assert!(v.result.is_ok());
+	}
+	for cond in [
+		"val = '30'",
+		"val = 30",
+		"'30' = val",
+		"30 = val",
+		"val == 30",
+		"val = '1h'",
+		"val = 1h",
+		"val = person:tobie",
+		"val = 'person:tobie'",
+		"val = true",
+		"true = val",
+		"val = 'true'",
+		"val = 'abc'",
+		"val = null",
+		"null = val",
+		"val INSIDE ['30', 30, person:tobie, true]",
+	] {
+		let sql = format!(
+			"
+			SELECT num FROM indexed WHERE {cond} ORDER BY num;
+			SELECT num FROM plain WHERE {cond} ORDER BY num;
+		"
+		);
+		let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+		assert_eq!(res.len(), 2);
+		//
+		let tmp = res.remove(0).result?;
+		let val = res.remove(0).result?;
+		assert_eq!(tmp, val, "{cond}");
+		assert_ne!(tmp, Value::parse("[]"), "{cond}");
+	}
+	Ok(())
+}
@@ -0,0 +1,129 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn orders() -> Result<(Datastore, Session), Error> {
+	let sql = "
+		CREATE customer:1 SET email = 'tobie@surrealdb.com', name = 'Tobie';
+		CREATE customer:2 SET email = 'jaime@surrealdb.com', name = 'Jaime';
+		CREATE order:1 SET email = 'tobie@surrealdb.com', total = 10;
+		CREATE order:2 SET email = 'tobie@surrealdb.com', total = 20;
+		CREATE order:3 SET email = 'nobody@surrealdb.com', total = 30;
+		CREATE order:4 SET email = NULL, total = 40;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	for _ in 0..6 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	Ok((dbs, ses))
+}
+
+async fn check(dbs: &Datastore, ses: &Session) -> Result<(), Error> {
+	let sql = "
+		SELECT id, email, total FROM order JOIN customer ON email = email AS buyer ORDER BY id;
+		SELECT name, email FROM customer JOIN order ON email = email ORDER BY name;
+	";
+	let res = &mut dbs.execute(sql, ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	// Records without a matching value have no linked records
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: order:1,
+				email: 'tobie@surrealdb.com',
+				total: 10,
+				buyer: [{ id: customer:1, email: 'tobie@surrealdb.com', name: 'Tobie' }]
+			},
+			{
+				id: order:2,
+				email: 'tobie@surrealdb.com',
+				total: 20,
+				buyer: [{ id: customer:1, email: 'tobie@surrealdb.com', name: 'Tobie' }]
+			},
+			{ id: order:3, email: 'nobody@surrealdb.com', total: 30, buyer: [] },
+			{ id: order:4, email: NULL, total: 40, buyer: [] }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Each of the matching records are linked
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Jaime', email: 'jaime@surrealdb.com', order: [] },
+			{
+				name: 'Tobie',
+				email: 'tobie@surrealdb.com',
+				order: [
+					{ id: order:1, email: 'tobie@surrealdb.com', total: 10 },
+					{ id: order:2, email: 'tobie@surrealdb.com', total: 20 }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn join_hash_lookup() -> Result<(), Error> {
+	let (dbs, ses) = orders().await?;
+	check(&dbs, &ses).await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn join_index_lookup() -> Result<(), Error> {
+	let (dbs, ses) = orders().await?;
+	let sql = "
+		DEFINE INDEX email ON customer FIELDS email UNIQUE;
+		DEFINE INDEX email ON order FIELDS email;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	check(&dbs, &ses).await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn join_explain_and_permissions() -> Result<(), Error> {
+	let (dbs, ses) = orders().await?;
+	let sql = "
+		DEFINE TABLE customer SCHEMALESS PERMISSIONS FOR select WHERE name = 'Jaime';
+		DEFINE TABLE order SCHEMALESS PERMISSIONS FOR select FULL;
+		EXPLAIN SELECT * FROM order JOIN customer ON email = email;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ stage: 'ITERATE' },
+			{ stage: 'JOIN', detail: 'JOIN customer ON email = email' }
+		]",
+	);
+	assert_eq!(tmp.pick(&["stages".into()]), val);
+	// Linked records are checked against the table permissions
+	let sql = "SELECT id, email FROM order JOIN customer ON email = email WHERE id = order:1;";
+	let ses = Session::for_sc("test", "test", "test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: order:1, email: 'tobie@surrealdb.com', customer: [] }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
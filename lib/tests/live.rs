@@ -0,0 +1,136 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Action;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn live_select_notifications() -> Result<(), Error> {
+	let sql = "
+		LIVE SELECT * FROM person WHERE age > 18;
+		CREATE person:tobie SET age = 30;
+		CREATE person:jaime SET age = 10;
+		UPDATE person:tobie SET age = 31;
+		DELETE person:tobie;
+	";
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let ses = Session {
+		rt: true,
+		..Session::for_kv().with_ns("test").with_db("test")
+	};
+	let rcv = dbs.notifications().unwrap();
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let id = match tmp {
+		Value::Uuid(id) => id,
+		v => panic!("expected a live query id, got {}", v),
+	};
+	//
+	let tmp = rcv.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Create);
+	assert_eq!(tmp.result, Value::parse("{ id: person:tobie, age: 30 }"));
+	//
+	let tmp = rcv.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Update);
+	assert_eq!(tmp.result, Value::parse("{ id: person:tobie, age: 31 }"));
+	//
+	let tmp = rcv.try_recv().unwrap();
+	assert_eq!(tmp.id, id);
+	assert_eq!(tmp.action, Action::Delete);
+	assert_eq!(tmp.result, Value::parse("person:tobie"));
+	//
+	assert!(rcv.try_recv().is_err());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_cancelled_transaction() -> Result<(), Error> {
+	let sql = "
+		LIVE SELECT * FROM person;
+		BEGIN;
+		CREATE person:tobie SET age = 30;
+		CANCEL;
+		CREATE person:jaime SET age = 10;
+	";
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let ses = Session {
+		rt: true,
+		..Session::for_kv().with_ns("test").with_db("test")
+	};
+	let rcv = dbs.notifications().unwrap();
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = rcv.try_recv().unwrap();
+	assert_eq!(tmp.action, Action::Create);
+	assert_eq!(tmp.result, Value::parse("{ id: person:jaime, age: 10 }"));
+	//
+	assert!(rcv.try_recv().is_err());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_permissions() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS PERMISSIONS FOR select WHERE public = true;
+	";
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let sql = "LIVE SELECT name FROM person";
+	let ses = Session {
+		rt: true,
+		..Session::for_sc("test", "test", "test")
+	};
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	assert!(res.remove(0).result.is_ok());
+	//
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', public = false;
+		CREATE person:jaime SET name = 'Jaime', public = true;
+	";
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let rcv = dbs.notifications().unwrap();
+	let tmp = rcv.try_recv().unwrap();
+	assert_eq!(tmp.action, Action::Create);
+	assert_eq!(tmp.result, Value::parse("{ name: 'Jaime' }"));
+	//
+	assert!(rcv.try_recv().is_err());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn live_select_without_realtime() -> Result<(), Error> {
+	let sql = "
+		LIVE SELECT * FROM person;
+		CREATE person:tobie SET age = 30;
+	";
+	let dbs = Datastore::new("memory").await?.with_notifications();
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let rcv = dbs.notifications().unwrap();
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	// Sessions which can not receive notifications can not start live queries
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::RealtimeDisabled)));
+	//
+	assert!(res.remove(0).result.is_ok());
+	assert!(rcv.try_recv().is_err());
+	//
+	Ok(())
+}
@@ -0,0 +1,41 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn prepare_and_execute() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let qry = dbs.prepare("CREATE type::thing('person', $name) SET age = $age RETURN age;")?;
+	assert_eq!(qry.query().len(), 1);
+	//
+	for (name, age) in [("tobie", 21), ("jaime", 30)] {
+		let var = Some([("name".to_owned(), name.into()), ("age".to_owned(), age.into())].into());
+		let res = &mut dbs.execute_prepared(&qry, &ses, var, false).await?;
+		assert_eq!(res.len(), 1);
+		let tmp = res.remove(0).result?;
+		let val = Value::parse(&format!("[{{ age: {} }}]", age));
+		assert_eq!(tmp, val);
+	}
+	//
+	let qry = dbs.prepare("SELECT * FROM person WHERE age > $age;")?;
+	let var = Some([("age".to_owned(), 25.into())].into());
+	let res = &mut dbs.execute_prepared(&qry, &ses, var, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime, age: 30 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn prepare_invalid_query() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let tmp = dbs.prepare("SELECT * FROM");
+	assert!(matches!(tmp, Err(Error::InvalidQuery { .. })));
+	//
+	Ok(())
+}
@@ -0,0 +1,108 @@
+mod parse;
+use parse::Parse;
+use std::sync::Arc;
+use std::time::Duration;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn running_default_query_timeout() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE |person:1..100|;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let dbs = dbs.with_query_timeout(Some(Duration::from_nanos(1)));
+	let sql = "
+		SELECT * FROM person;
+		SELECT * FROM person TIMEOUT 1m;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryTimedout)));
+	//
+	let tmp = res.remove(0).result?;
+	assert!(matches!(tmp, Value::Array(v) if v.len() == 100));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn running_kill_query() -> Result<(), Error> {
+	let dbs = Arc::new(Datastore::new("memory").await?);
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE |person:1..1000|;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	// Run a slow query on another thread
+	let run = {
+		let dbs = dbs.clone();
+		let ses = ses.clone();
+		let sql = "
+			SELECT * FROM person WHERE count((SELECT * FROM person)) > 0;
+			CREATE person:tobie;
+		";
+		std::thread::spawn(move || futures::executor::block_on(dbs.execute(sql, &ses, None, false)))
+	};
+	// Find the slow query in the running queries
+	let mut id = None;
+	for _ in 0..1000 {
+		let res = &mut dbs.execute("INFO FOR RUNNING;", &ses, None, false).await?;
+		let tmp = res.remove(0).result?;
+		id = match tmp {
+			Value::Object(v) => v
+				.iter()
+				.find(|(_, v)| v.pick(&["statement".into()]).as_string().starts_with("SELECT"))
+				.map(|(k, v)| {
+					assert_eq!(v.pick(&["ns".into()]), Value::from("test"));
+					assert_eq!(v.pick(&["db".into()]), Value::from("test"));
+					k.clone()
+				}),
+			v => panic!("unexpected result: {}", v),
+		};
+		if id.is_some() {
+			break;
+		}
+		futures_timer::Delay::new(Duration::from_millis(5)).await;
+	}
+	let id = id.expect("the slow query was not found within 5 seconds");
+	// Kill the slow query
+	let sql = format!("KILL QUERY '{}'", id);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let res = &mut run.join().unwrap()?;
+	assert_eq!(res.len(), 2);
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryKilled)));
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryKilled)));
+	// The killed query is no longer running
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert!(matches!(res.remove(0).result, Err(Error::KillStatement { .. })));
+	let res = &mut dbs.execute("SELECT * FROM person:tobie;", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn running_requires_root() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_db("test", "test");
+	let sql = "
+		INFO FOR RUNNING;
+		KILL QUERY 'e72bee20-f49b-11ec-b939-0242ac120002';
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(matches!(res.remove(0).result, Err(Error::QueryPermissions)));
+	assert!(matches!(res.remove(0).result, Err(Error::QueryPermissions)));
+	//
+	Ok(())
+}
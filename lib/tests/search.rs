@@ -0,0 +1,181 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn search_index_matches() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX ft_description ON product FIELDS description SEARCH FILTERS ascii,lowercase,stem;
+		CREATE product:1 SET description = 'Red running shoes with a rubber sole';
+		CREATE product:2 SET description = 'Blue shoes, suitable for running and hiking';
+		CREATE product:3 SET description = 'A red crème brûlée dish';
+		CREATE product:4 SET description = 'Red shoes for walking. Red laces. Red sole.';
+		SELECT id FROM product WHERE description @@ 'shoe' ORDER BY id;
+		SELECT id FROM product WHERE description @@ 'RED shoes' ORDER BY id;
+		SELECT id FROM product WHERE description @@ 'creme brulee';
+		SELECT id FROM product WHERE description @@ 'run' AND description @@ 'blue';
+		SELECT id FROM product WHERE description @@ 'green';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1 }, { id: product:2 }, { id: product:4 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:1 }, { id: product:4 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:3 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: product:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn search_index_score_and_highlight() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX ft_description ON product FIELDS description SEARCH FILTERS lowercase;
+		CREATE product:1 SET description = 'Red shoes and a red hat';
+		CREATE product:2 SET description = 'Shoes with red laces, available in many different colours and sizes';
+		CREATE product:3 SET description = 'A blue hat';
+		SELECT id, search::score() > 0 AS positive FROM product WHERE description @@ 'red' ORDER BY id;
+		SELECT id, search::score() AS score FROM product WHERE description @@ 'red shoes' ORDER BY score DESC;
+		SELECT search::highlight('<b>', '</b>', description) AS text FROM product WHERE description @@ 'red hat';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: product:1, positive: true },
+			{ id: product:2, positive: true },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = match res.remove(0).result? {
+		Value::Array(v) => v,
+		v => panic!("expected an array, got {}", v),
+	};
+	let (ids, scores): (Vec<Value>, Vec<Value>) = tmp
+		.iter()
+		.map(|v| match v {
+			Value::Object(v) => (v.get("id").cloned().unwrap(), v.get("score").cloned().unwrap()),
+			_ => panic!("expected an object"),
+		})
+		.unzip();
+	assert_eq!(Value::from(ids), Value::parse("[product:1, product:2]"));
+	assert!(scores[0] > scores[1]);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ text: '<b>Red</b> shoes and a <b>red</b> <b>hat</b>' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn search_index_update_and_delete() -> Result<(), Error> {
+	let sql = "
+		CREATE article:1 SET title = 'Rust database internals';
+		CREATE article:2 SET title = 'Cooking with cast iron';
+		DEFINE INDEX ft_title ON article FIELDS title SEARCH;
+		UPDATE article:1 SET title = 'Cooking for programmers';
+		DELETE article:2;
+		SELECT id FROM article WHERE title @@ 'cooking';
+		SELECT id FROM article WHERE title @@ 'database';
+		EXPLAIN SELECT id FROM article WHERE title @@ 'cooking';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: article:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Index', detail: 'INDEX ft_title ON article WHERE title @@ \"cooking\"' },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn search_without_index() -> Result<(), Error> {
+	let sql = "
+		CREATE note:1 SET text = 'Buy milk and eggs';
+		CREATE note:2 SET text = 'Call the plumber';
+		SELECT id, search::score() AS score FROM note WHERE text @@ 'MILK';
+		RETURN 'The quick brown fox' @@ 'quick fox';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: note:1, score: NONE }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("true");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
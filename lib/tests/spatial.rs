@@ -0,0 +1,170 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn spatial_index_delivery_zones() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX zone_area ON zone FIELDS area SPATIAL;
+		CREATE zone:central SET area = {
+			type: 'Polygon',
+			coordinates: [[[-0.15, 51.49], [-0.10, 51.49], [-0.10, 51.52], [-0.15, 51.52], [-0.15, 51.49]]]
+		};
+		CREATE zone:greater SET area = {
+			type: 'Polygon',
+			coordinates: [[[-0.55, 51.25], [0.35, 51.25], [0.35, 51.70], [-0.55, 51.70], [-0.55, 51.25]]]
+		};
+		CREATE zone:paris SET area = {
+			type: 'Polygon',
+			coordinates: [[[2.25, 48.81], [2.42, 48.81], [2.42, 48.90], [2.25, 48.90], [2.25, 48.81]]]
+		};
+		SELECT id FROM zone WHERE area CONTAINS (-0.1278, 51.5074) ORDER BY id;
+		SELECT id FROM zone WHERE (0.1, 51.4) INSIDE area;
+		SELECT id FROM zone WHERE area CONTAINS (-3.1883, 55.9533);
+		EXPLAIN SELECT id FROM zone WHERE area CONTAINS (-0.1278, 51.5074);
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: zone:central }, { id: zone:greater }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: zone:greater }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Index', detail: 'INDEX zone_area ON zone WHERE area CONTAINS (-0.1278, 51.5074)' },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn spatial_index_bounding_box() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX shop_location ON shop FIELDS location SPATIAL;
+		CREATE shop:1 SET location = (-0.1278, 51.5074);
+		CREATE shop:2 SET location = (-0.0877, 51.5045);
+		CREATE shop:3 SET location = (2.3522, 48.8566);
+		CREATE shop:4 SET location = (-0.1419, 51.5014);
+		SELECT id FROM shop WHERE location INSIDE {
+			type: 'Polygon',
+			coordinates: [[[-0.13, 51.50], [-0.08, 51.50], [-0.08, 51.51], [-0.13, 51.51], [-0.13, 51.50]]]
+		} ORDER BY id;
+		UPDATE shop:2 SET location = (2.2945, 48.8584);
+		DELETE shop:1;
+		SELECT id FROM shop WHERE location INTERSECTS {
+			type: 'Polygon',
+			coordinates: [[[-0.13, 51.50], [-0.08, 51.50], [-0.08, 51.51], [-0.13, 51.51], [-0.13, 51.50]]]
+		};
+		SELECT id FROM shop WHERE location INSIDE {
+			type: 'Polygon',
+			coordinates: [[[2.0, 48.0], [3.0, 48.0], [3.0, 49.0], [2.0, 49.0], [2.0, 48.0]]]
+		} ORDER BY id;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: shop:1 }, { id: shop:2 }]");
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: shop:2 }, { id: shop:3 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn spatial_index_nearest_neighbours() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX shop_location ON shop FIELDS location SPATIAL;
+		CREATE shop:1 SET location = (-0.1278, 51.5074);
+		CREATE shop:2 SET location = (-0.0877, 51.5045);
+		CREATE shop:3 SET location = (2.3522, 48.8566);
+		CREATE shop:4 SET location = (-0.1419, 51.5014);
+		LET $here = (-0.1337, 51.5050);
+		SELECT id, math::round(geo::distance(location, $here)) AS distance FROM shop
+			WHERE geo::distance(location, $here) < 5000 ORDER BY distance LIMIT 2;
+		EXPLAIN SELECT id FROM shop WHERE geo::distance($here, location) <= 5000;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: shop:1, distance: 488.0 },
+			{ id: shop:4, distance: 695.0 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyze: false,
+			parallel: false,
+			iterables: [
+				{ type: 'Index', detail: 'INDEX shop_location ON shop WHERE geo::distance($here, location) <= 5000' },
+			],
+			stages: [
+				{ stage: 'ITERATE' },
+			],
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
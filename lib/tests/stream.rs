@@ -0,0 +1,88 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Chunk;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn stream(dbs: &Datastore, sql: &str, ses: &Session) -> Result<Vec<Chunk>, Error> {
+	let (snd, rcv) = surrealdb::channel::new(1);
+	let (res, out) = futures::join!(dbs.execute_stream(sql, ses, None, false, snd), async move {
+		let mut out = vec![];
+		while let Ok(v) = rcv.recv().await {
+			out.push(v);
+		}
+		out
+	});
+	res.map(|_| out)
+}
+
+#[tokio::test]
+async fn stream_select_batches() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE |log:1..250|;", &ses, None, false).await?;
+	assert!(res.remove(0).result.is_ok());
+	//
+	let mut res = stream(&dbs, "SELECT * FROM log;", &ses).await?;
+	assert_eq!(res.len(), 4);
+	for len in [100, 100, 50] {
+		assert!(matches!(res.remove(0), Chunk::Values(v) if v.len() == len));
+	}
+	let tmp = match res.remove(0) {
+		Chunk::Response(v) => v.result?,
+		v => panic!("unexpected chunk: {:?}", v),
+	};
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	let mut res = stream(&dbs, "SELECT * FROM log LIMIT 60 START 150;", &ses).await?;
+	let mut out = vec![];
+	while let Chunk::Values(v) = res.remove(0) {
+		out.extend(v);
+	}
+	assert!(res.is_empty());
+	assert_eq!(out.len(), 60);
+	assert_eq!(out[0], Value::parse("{ id: log:151 }"));
+	assert_eq!(out[59], Value::parse("{ id: log:210 }"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn stream_buffered_statements() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:tobie SET age = 21;
+		CREATE person:jaime SET age = 30;
+		SELECT * FROM person ORDER BY age DESC;
+		BEGIN;
+		SELECT * FROM person;
+		COMMIT;
+		SELECT age FROM person WHERE age > 25;
+	";
+	let mut res = stream(&dbs, sql, &ses).await?;
+	assert_eq!(res.len(), 6);
+	// Writes and sorted results are not streamed
+	for _ in 0..2 {
+		assert!(matches!(res.remove(0), Chunk::Response(v) if v.result.is_ok()));
+	}
+	let tmp = match res.remove(0) {
+		Chunk::Response(v) => v.result?,
+		v => panic!("unexpected chunk: {:?}", v),
+	};
+	let val = Value::parse("[{ id: person:jaime, age: 30 }, { id: person:tobie, age: 21 }]");
+	assert_eq!(tmp, val);
+	// Results within a transaction are not streamed
+	let tmp = match res.remove(0) {
+		Chunk::Response(v) => v.result?,
+		v => panic!("unexpected chunk: {:?}", v),
+	};
+	assert!(matches!(tmp, Value::Array(v) if v.len() == 2));
+	// Other results are streamed
+	assert!(matches!(res.remove(0), Chunk::Values(v) if v == vec![Value::parse("{ age: 30 }")]));
+	assert!(matches!(res.remove(0), Chunk::Response(v) if v.result.is_ok()));
+	//
+	Ok(())
+}
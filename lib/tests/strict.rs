@@ -192,6 +192,7 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 		"{
 			dl: {},
 			dt: {},
+			fc: {},
 			sc: {},
 			tb: { test: 'DEFINE TABLE test SCHEMALESS PERMISSIONS NONE' },
 		}",
@@ -204,6 +205,7 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 			ev: {},
 			fd: { extra: 'DEFINE FIELD extra ON test VALUE true' },
 			ft: {},
+			ib: {},
 			ix: {},
 		}",
 	);
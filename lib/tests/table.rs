@@ -43,6 +43,7 @@ async fn define_foreign_table() -> Result<(), Error> {
 			ev: {},
 			fd: {},
 			ft: { person_by_age: 'DEFINE TABLE person_by_age SCHEMALESS AS SELECT count(), age, math::sum(age) AS total, math::mean(score) AS average FROM person GROUP BY age' },
+			ib: {},
 			ix: {},
 		}",
 	);
@@ -104,3 +105,310 @@ async fn define_foreign_table() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn define_foreign_table_aggregates() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE reading_by_sensor AS
+			SELECT
+				count(),
+				sensor,
+				math::min(value) AS min,
+				math::max(value) AS max,
+				math::variance(value) AS variance,
+				math::stddev(value) AS stddev,
+				array::distinct(unit) AS units,
+				array::group(tags) AS tags
+			FROM reading
+			GROUP BY sensor
+		;
+		CREATE reading:1 SET sensor = 'a', value = 10, unit = 'c', tags = ['x'];
+		CREATE reading:2 SET sensor = 'a', value = 20, unit = 'c', tags = ['y'];
+		CREATE reading:3 SET sensor = 'a', value = 30, unit = 'f', tags = ['x', 'z'];
+		SELECT count, min, max, variance, stddev, units, tags FROM reading_by_sensor;
+		DELETE reading:3;
+		UPDATE reading:1 SET value = 40;
+		SELECT count, min, max, variance, math::fixed(stddev, 4) AS stddev, units, tags FROM reading_by_sensor;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 8);
+	for _ in 0..4 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				count: 3,
+				min: 10,
+				max: 30,
+				variance: 100,
+				stddev: 10,
+				units: ['c', 'f'],
+				tags: ['x', 'z', 'y'],
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// The aggregates are maintained when records are removed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				count: 2,
+				min: 20,
+				max: 40,
+				variance: 200,
+				stddev: 14.1421,
+				units: ['c'],
+				tags: ['x', 'y'],
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_min_and_max() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE reading_range AS
+			SELECT count(), math::min(value) AS min, math::max(value) AS max FROM reading GROUP BY sensor
+		;
+		CREATE reading:1 SET sensor = 'a', value = -5;
+		CREATE reading:2 SET sensor = 'a', value = 2.5;
+		CREATE reading:3 SET sensor = 'a', value = 7;
+		CREATE reading:4 SET sensor = 'a', value = 7;
+		SELECT count, min, max FROM reading_range;
+		DELETE reading:1;
+		DELETE reading:3;
+		SELECT count, min, max FROM reading_range;
+		UPDATE reading:4 SET value = -1.5;
+		SELECT count, min, max FROM reading_range;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 11);
+	for _ in 0..5 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 4, min: -5, max: 7 }]");
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// Removing one of two equal values keeps the value
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2, min: 2.5, max: 7 }]");
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	// The next value is found when the lowest or highest is removed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2, min: -1.5, max: 2.5 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_where_and_links() -> Result<(), Error> {
+	let sql = "
+		CREATE customer:1 SET country = 'uk';
+		CREATE customer:2 SET country = 'us';
+		DEFINE TABLE sales_by_country AS
+			SELECT
+				count(),
+				customer.country AS country,
+				math::sum(total) AS total
+			FROM order
+			WHERE total > 5
+			GROUP BY customer.country
+		;
+		CREATE order:1 SET customer = customer:1, total = 10;
+		CREATE order:2 SET customer = customer:1, total = 20;
+		CREATE order:3 SET customer = customer:2, total = 30;
+		CREATE order:4 SET customer = customer:2, total = 1;
+		SELECT count, country, total FROM sales_by_country ORDER BY country;
+		DELETE order:1;
+		UPDATE order:3 SET total = 2;
+		SELECT count, country, total FROM sales_by_country ORDER BY country;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 11);
+	for _ in 0..7 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	// Fields are fetched through record links
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ count: 2, country: 'uk', total: 30 },
+			{ count: 1, country: 'us', total: 30 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// Records are removed when they no longer match the view
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ count: 1, country: 'uk', total: 20 },
+			{ count: 0, country: 'us', total: 0 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_linked_record_changes() -> Result<(), Error> {
+	let sql = "
+		CREATE customer:1 SET country = 'uk';
+		CREATE customer:2 SET country = 'us';
+		DEFINE TABLE sales_by_country AS
+			SELECT
+				count(),
+				customer.country AS country,
+				math::sum(total) AS total
+			FROM order
+			GROUP BY customer.country
+		;
+		CREATE order:1 SET customer = customer:1, total = 10;
+		CREATE order:2 SET customer = customer:2, total = 20;
+		UPDATE customer:1 SET country = 'us';
+		SELECT count, country, total FROM sales_by_country ORDER BY country;
+		UPDATE order:1 SET customer = customer:3;
+		CREATE customer:3 SET country = 'fr';
+		UPDATE customer:1 SET country = 'uk';
+		SELECT count, country, total FROM sales_by_country ORDER BY country;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 11);
+	for _ in 0..6 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	// Records are moved when a linked record changes
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ count: 0, country: 'uk', total: 0 },
+			{ count: 2, country: 'us', total: 30 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..3 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	// Records only follow the records they currently link to
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ count: 0, country: NONE, total: 0 },
+			{ count: 1, country: 'fr', total: 10 },
+			{ count: 0, country: 'uk', total: 0 },
+			{ count: 1, country: 'us', total: 20 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_order_and_limit() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM person GROUP BY age ORDER BY age;
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM person GROUP BY age LIMIT 10;
+		DEFINE TABLE person_by_age AS SELECT age FROM person START 10;
+		INFO FOR DB;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	// Views can not be ordered or limited
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(matches!(tmp, Err(Error::TableViewLimited { .. })));
+	}
+	// The table is not defined
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["tb".into(), "person_by_age".into()]), Value::None);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_foreign_table() -> Result<(), Error> {
+	let sql = "
+		CREATE person:one SET age = 39;
+		CREATE person:two SET age = 39;
+		CREATE person:six SET age = 18;
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM person GROUP BY age;
+		SELECT count, age FROM person_by_age ORDER BY age;
+		DELETE person_by_age;
+		REBUILD TABLE person_by_age;
+		SELECT count, age FROM person_by_age ORDER BY age;
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM person WHERE age > 30 GROUP BY age;
+		SELECT count, age FROM person_by_age ORDER BY age;
+		DEFINE TABLE person_by_age AS SELECT count(), age FROM user GROUP BY age;
+		CREATE person:ten SET age = 39;
+		SELECT count, age FROM person_by_age ORDER BY age;
+		REBUILD TABLE animal;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 14);
+	for _ in 0..4 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	// The view is computed from the existing records
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 1, age: 18 }, { count: 2, age: 39 }]");
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// The view is recomputed when it is rebuilt
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 1, age: 18 }, { count: 2, age: 39 }]");
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	// The view is recomputed when its definition changes
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2, age: 39 }]");
+	assert_eq!(tmp, val);
+	//
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	// The previous source table no longer updates the view
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TbNotFound)));
+	//
+	Ok(())
+}
@@ -0,0 +1,54 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn transaction_concurrent_counters() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test").with_retries(10);
+	let sql = "UPDATE counter:one SET count += 1;";
+	let res = futures::future::join_all((0..50).map(|_| dbs.execute(sql, &ses, None, false))).await;
+	for res in res {
+		let tmp = res?.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let res = &mut dbs.execute("SELECT * FROM counter;", &ses, None, false).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: counter:one, count: 50 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_explicit_commit() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		CREATE person:tobie;
+		CREATE person:tobie;
+		COMMIT;
+		BEGIN;
+		CREATE person:jaime;
+		COMMIT;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test").with_retries(0);
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryNotExecuted)));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::RecordExists { .. })));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,219 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+#[tokio::test]
+async fn upsert_statement_set() -> Result<(), Error> {
+	let sql = "
+		UPSERT person:tobie SET name = 'Tobie', visits += 1;
+		UPSERT person:tobie SET name = 'Tobie', visits += 1;
+		SELECT * FROM person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie', visits: 1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie', visits: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie', visits: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn upsert_statement_content_and_merge() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', age = 30;
+		UPSERT person:tobie, person:jaime MERGE { company: 'SurrealDB' };
+		UPSERT person:tobie CONTENT { name: 'Tobie Morgan Hitchcock' } RETURN BEFORE;
+		SELECT * FROM person;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:tobie, name: 'Tobie', age: 30, company: 'SurrealDB' },
+			{ id: person:jaime, company: 'SurrealDB' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie', age: 30, company: 'SurrealDB' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, company: 'SurrealDB' },
+			{ id: person:tobie, name: 'Tobie Morgan Hitchcock' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn upsert_statement_permissions() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person PERMISSIONS
+			FOR select FULL
+			FOR create WHERE name = 'Tobie'
+			FOR update NONE;
+		DEFINE FIELD created ON person VALUE $before.created OR 'now';
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	//
+	let sql = "
+		UPSERT person:tobie SET name = 'Tobie';
+		UPSERT person:tobie SET name = 'Changed';
+		UPSERT person:jaime SET name = 'Jaime';
+	";
+	let ses = Session::for_sc("test", "test", "test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie', created: 'now' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn upsert_statement_invalid() -> Result<(), Error> {
+	let sql = "UPSERT person:1..5 SET name = 'Tobie';";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::UpsertStatement { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_statement_unique() -> Result<(), Error> {
+	let sql = "
+		RELATE person:tobie->knows->person:jaime UNIQUE SET count += 1;
+		RELATE person:tobie->knows->person:jaime UNIQUE SET count += 1;
+		RELATE person:tobie->knows->person:jack UNIQUE SET count += 1;
+		RELATE person:jaime->knows->person:tobie UNIQUE SET count += 1;
+		SELECT count, in, out FROM knows ORDER BY in, out;
+		SELECT count() AS total FROM person:tobie->knows GROUP ALL;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let first = res.remove(0).result?;
+	let again = res.remove(0).result?;
+	assert_eq!(first.pick(&["id".into()]), again.pick(&["id".into()]));
+	assert_eq!(again.pick(&["count".into()]), Value::parse("[2]"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["count".into()]), Value::parse("[1]"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["count".into()]), Value::parse("[1]"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ count: 1, in: person:jaime, out: person:tobie },
+			{ count: 1, in: person:tobie, out: person:jack },
+			{ count: 2, in: person:tobie, out: person:jaime }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ total: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_statement_unique_duplicate_records() -> Result<(), Error> {
+	let sql = "
+		RELATE [person:tobie, person:tobie]->knows->[person:jaime, person:jaime] UNIQUE SET count += 1;
+		DELETE knows;
+		RELATE person:tobie->knows->person:jaime UNIQUE SET count += 1;
+		SELECT count() AS total FROM knows GROUP ALL;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	// Each pair of records is related once
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["count".into()]), Value::parse("[1]"));
+	assert!(res.remove(0).result.is_ok());
+	// Deleted edges are not reused
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&["count".into()]), Value::parse("[1]"));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ total: 1 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_statement_without_unique() -> Result<(), Error> {
+	let sql = "
+		RELATE person:tobie->knows->person:jaime;
+		RELATE person:tobie->knows->person:jaime;
+		SELECT count() AS total FROM knows GROUP ALL;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ total: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
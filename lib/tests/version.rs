@@ -0,0 +1,228 @@
+mod parse;
+use chrono::{SecondsFormat, Utc};
+use parse::Parse;
+use std::thread::sleep;
+use std::time::Duration;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+fn now() -> String {
+	let now = Utc::now().to_rfc3339_opts(SecondsFormat::Nanos, true);
+	sleep(Duration::from_millis(5));
+	now
+}
+
+#[tokio::test]
+async fn select_version_table() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	//
+	let sql = "DEFINE TABLE person HISTORY 1d;";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let before = now();
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let during = now();
+	let sql = "
+		UPDATE person:tobie SET name = 'Tobias';
+		DELETE person:jaime;
+		CREATE person:simon SET name = 'Simon';
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let sql = format!(
+		"
+		SELECT * FROM person VERSION '{before}';
+		SELECT * FROM person VERSION '{during}';
+		SELECT * FROM person VERSION '{after}';
+		SELECT * FROM person;
+		",
+		before = before,
+		during = during,
+		after = now(),
+	);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, name: 'Jaime' },
+			{ id: person:tobie, name: 'Tobie' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:simon, name: 'Simon' },
+			{ id: person:tobie, name: 'Tobias' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:simon, name: 'Simon' },
+			{ id: person:tobie, name: 'Tobias' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_version_record() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	//
+	let sql = "
+		DEFINE TABLE person HISTORY 1d;
+		DEFINE INDEX idx_name ON person FIELDS name;
+		CREATE person:tobie SET name = 'Tobie', age = 30;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let first = now();
+	let sql = "UPDATE person:tobie SET name = 'Tobias', age = 31;";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let second = now();
+	let sql = "DELETE person:tobie;";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let sql = format!(
+		"
+		SELECT name, age FROM person:tobie VERSION '{first}';
+		SELECT name, age FROM person:tobie VERSION '{second}';
+		SELECT name, age FROM person:tobie VERSION '{third}';
+		SELECT name FROM person WHERE name = 'Tobie' VERSION '{first}';
+		",
+		first = first,
+		second = second,
+		third = now(),
+	);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Tobie', age: 30 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Tobias', age: 31 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_version_without_history() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	//
+	let sql = "CREATE person:tobie SET name = 'Tobie';";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let sql = format!(
+		"
+		SELECT * FROM person VERSION '{now}';
+		SELECT * FROM person:tobie VERSION '{now}';
+		",
+		now = now(),
+	);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_version_history_retention() -> Result<(), Error> {
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	//
+	let sql = "
+		DEFINE TABLE person HISTORY 20ms;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let first = now();
+	let sql = "
+		UPDATE person:tobie SET name = 'Tobias';
+		DELETE person:jaime;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let second = now();
+	sleep(Duration::from_millis(50));
+	dbs.tick().await?;
+	//
+	let sql = format!(
+		"
+		SELECT * FROM person VERSION '{first}';
+		SELECT * FROM person VERSION '{second}';
+		SELECT * FROM person VERSION '{third}';
+		",
+		first = first,
+		second = second,
+		third = now(),
+	);
+	let res = &mut dbs.execute(&sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobias' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobias' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
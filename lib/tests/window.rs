@@ -0,0 +1,191 @@
+mod parse;
+use parse::Parse;
+use surrealdb::sql::Value;
+use surrealdb::Datastore;
+use surrealdb::Error;
+use surrealdb::Session;
+
+async fn readings() -> Result<(Datastore, Session), Error> {
+	let sql = "
+		CREATE reading:1 SET sensor = 'a', time = 1, value = 10;
+		CREATE reading:2 SET sensor = 'a', time = 2, value = 20;
+		CREATE reading:3 SET sensor = 'a', time = 3, value = 30;
+		CREATE reading:4 SET sensor = 'a', time = 4, value = 40;
+		CREATE reading:5 SET sensor = 'b', time = 1, value = 5;
+		CREATE reading:6 SET sensor = 'b', time = 2, value = 5;
+		CREATE reading:7 SET sensor = 'b', time = 3, value = 15;
+	";
+	let dbs = Datastore::new("memory").await?;
+	let ses = Session::for_kv().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 7);
+	for _ in 0..7 {
+		assert!(res.remove(0).result.is_ok());
+	}
+	Ok((dbs, ses))
+}
+
+#[tokio::test]
+async fn window_row_number_lag_lead() -> Result<(), Error> {
+	let (dbs, ses) = readings().await?;
+	let sql = "
+		SELECT
+			sensor,
+			time,
+			row_number() OVER (PARTITION BY sensor ORDER BY time) AS num,
+			lag(value, 1, null) OVER (PARTITION BY sensor ORDER BY time) AS prev,
+			lead(value, 2, 0) OVER (PARTITION BY sensor ORDER BY time) AS next
+		FROM reading ORDER BY sensor, time;
+		SELECT time, lag(value) OVER (ORDER BY time) AS prev FROM reading WHERE sensor = 'b' ORDER BY time;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ sensor: 'a', time: 1, num: 1, prev: null, next: 30 },
+			{ sensor: 'a', time: 2, num: 2, prev: 10, next: 40 },
+			{ sensor: 'a', time: 3, num: 3, prev: 20, next: 0 },
+			{ sensor: 'a', time: 4, num: 4, prev: 30, next: 0 },
+			{ sensor: 'b', time: 1, num: 1, prev: null, next: 15 },
+			{ sensor: 'b', time: 2, num: 2, prev: 5, next: 0 },
+			{ sensor: 'b', time: 3, num: 3, prev: 5, next: 0 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Records without a previous value have no output
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[0.into(), "prev".into()]), Value::None);
+	assert_eq!(tmp.pick(&[1.into(), "prev".into()]), Value::parse("5"));
+	assert_eq!(tmp.pick(&[2.into(), "prev".into()]), Value::parse("5"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn window_running_aggregates() -> Result<(), Error> {
+	let (dbs, ses) = readings().await?;
+	let sql = "
+		SELECT
+			time,
+			math::sum(value) OVER (ORDER BY time) AS running,
+			math::max(value) OVER (ORDER BY time ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) AS peak,
+			math::mean(value) OVER (ORDER BY time ROWS 1 PRECEDING) AS average,
+			math::sum(value) OVER (ORDER BY time ROWS BETWEEN CURRENT ROW AND 1 FOLLOWING) AS pair,
+			count(value) OVER (ORDER BY time ROWS BETWEEN 1 FOLLOWING AND 2 FOLLOWING) AS ahead,
+			count() OVER () AS total
+		FROM reading WHERE sensor = 'a' ORDER BY time;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ time: 1, running: 10, peak: 20, average: 10, pair: 30, ahead: 2, total: 4 },
+			{ time: 2, running: 30, peak: 30, average: 15, pair: 50, ahead: 2, total: 4 },
+			{ time: 3, running: 60, peak: 40, average: 25, pair: 70, ahead: 1, total: 4 },
+			{ time: 4, running: 100, peak: 40, average: 35, pair: 40, ahead: 0, total: 4 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn window_rank_over_groups() -> Result<(), Error> {
+	let (dbs, ses) = readings().await?;
+	let sql = "
+		SELECT value, rank() OVER (ORDER BY value DESC) AS rank FROM reading WHERE sensor = 'b' ORDER BY rank;
+		SELECT sensor, math::sum(value) AS total, rank() OVER (ORDER BY total DESC) AS pos FROM reading GROUP BY sensor ORDER BY pos;
+		SELECT sensor, math::sum(math::sum(value)) OVER (ORDER BY sensor) AS running, lag(sensor, 1, null) OVER (ORDER BY sensor) AS prev FROM reading GROUP BY sensor ORDER BY sensor;
+		SELECT sensor, lag(value) OVER (ORDER BY sensor) AS prev FROM reading GROUP BY sensor;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 4);
+	// Records with equal values share the same rank
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ value: 15, rank: 1 },
+			{ value: 5, rank: 2 },
+			{ value: 5, rank: 2 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Window functions run after grouping
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ sensor: 'a', total: 100, pos: 1 },
+			{ sensor: 'b', total: 25, pos: 2 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Window function inputs can be grouped or aggregated
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ sensor: 'a', running: 100, prev: null },
+			{ sensor: 'b', running: 125, prev: 'a' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Window function inputs which differ within a group are rejected
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidWindow { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn window_with_limit_and_start() -> Result<(), Error> {
+	let (dbs, ses) = readings().await?;
+	let sql = "
+		SELECT time, value, count() OVER () AS total, row_number() OVER (ORDER BY value DESC) AS num FROM reading WHERE sensor = 'a' LIMIT 2;
+		SELECT time, value, count() OVER () AS total, row_number() OVER (ORDER BY value DESC) AS num FROM reading WHERE sensor = 'a' LIMIT 2 START 1;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	// Window functions see every record before the limit
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ time: 1, value: 10, total: 4, num: 4 },
+			{ time: 2, value: 20, total: 4, num: 3 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ time: 2, value: 20, total: 4, num: 3 },
+			{ time: 3, value: 30, total: 4, num: 2 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn window_invalid() -> Result<(), Error> {
+	let (dbs, ses) = readings().await?;
+	let sql = "
+		SELECT row_number(time) OVER () FROM reading;
+		SELECT lag(value, -1) OVER (ORDER BY time) FROM reading;
+	";
+	let res = &mut dbs.execute(sql, &ses, None, false).await?;
+	assert_eq!(res.len(), 2);
+	assert!(matches!(res.remove(0).result, Err(Error::InvalidArguments { .. })));
+	assert!(matches!(res.remove(0).result, Err(Error::InvalidArguments { .. })));
+	//
+	let sql = "SELECT string::lowercase(sensor) OVER () FROM reading;";
+	let res = dbs.execute(sql, &ses, None, false).await;
+	assert!(matches!(res, Err(Error::InvalidQuery { .. })));
+	//
+	Ok(())
+}
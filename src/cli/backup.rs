@@ -1,13 +1,17 @@
+use crate::cli::LOG;
 use crate::err::Error;
 use reqwest::blocking::Body;
 use reqwest::blocking::Client;
 use reqwest::header::CONTENT_TYPE;
 use std::fs::OpenOptions;
 use std::io::copy;
+use std::io::BufReader;
 
 const TYPE: &str = "application/octet-stream";
 
 pub fn init(matches: &clap::ArgMatches) -> Result<(), Error> {
+	// Set the default logging level
+	crate::cli::log::init(3);
 	// Try to parse the specified source file
 	let from = matches.value_of("from").unwrap();
 	// Try to parse the specified output file
@@ -25,6 +29,8 @@ pub fn init(matches: &clap::ArgMatches) -> Result<(), Error> {
 }
 
 fn backup_file_to_file(_: &clap::ArgMatches, from: &str, into: &str) -> Result<(), Error> {
+	// Check that the source file is a valid backup
+	verify(from)?;
 	// Try to open the source file
 	let mut from = OpenOptions::new().read(true).open(from)?;
 	// Try to open the output file
@@ -45,14 +51,20 @@ fn backup_http_to_file(matches: &clap::ArgMatches, from: &str, into: &str) -> Re
 	// Try to open the source http
 	let mut from = Client::new()
 		.get(&from)
+		.query(&since(matches))
 		.basic_auth(user, Some(pass))
 		.header(CONTENT_TYPE, TYPE)
 		.send()?
 		.error_for_status()?;
 	// Try to open the output file
-	let mut into = OpenOptions::new().write(true).create(true).truncate(true).open(into)?;
+	let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(into)?;
 	// Copy the data to the destination
-	copy(&mut from, &mut into)?;
+	copy(&mut from, &mut file)?;
+	// Check that the downloaded backup is complete
+	if let Err(e) = verify(into) {
+		std::fs::remove_file(into)?;
+		return Err(e);
+	}
 	// Everything OK
 	Ok(())
 }
@@ -62,6 +74,8 @@ fn backup_file_to_http(matches: &clap::ArgMatches, from: &str, into: &str) -> Re
 	let user = matches.value_of("user").unwrap();
 	// Parse the specified password
 	let pass = matches.value_of("pass").unwrap();
+	// Check that the source file is a valid backup
+	verify(from)?;
 	// Try to open the source file
 	let from = OpenOptions::new().read(true).open(from)?;
 	// Set the correct output URL
@@ -69,6 +83,7 @@ fn backup_file_to_http(matches: &clap::ArgMatches, from: &str, into: &str) -> Re
 	// Copy the data to the destination
 	Client::new()
 		.post(&into)
+		.query(&until(matches))
 		.basic_auth(user, Some(pass))
 		.header(CONTENT_TYPE, TYPE)
 		.body(from)
@@ -90,6 +105,7 @@ fn backup_http_to_http(matches: &clap::ArgMatches, from: &str, into: &str) -> Re
 	// Try to open the source file
 	let from = Client::new()
 		.get(&from)
+		.query(&since(matches))
 		.basic_auth(user, Some(pass))
 		.header(CONTENT_TYPE, TYPE)
 		.send()?
@@ -97,6 +113,7 @@ fn backup_http_to_http(matches: &clap::ArgMatches, from: &str, into: &str) -> Re
 	// Copy the data to the destination
 	Client::new()
 		.post(&into)
+		.query(&until(matches))
 		.basic_auth(user, Some(pass))
 		.header(CONTENT_TYPE, TYPE)
 		.body(Body::new(from))
@@ -105,3 +122,30 @@ fn backup_http_to_http(matches: &clap::ArgMatches, from: &str, into: &str) -> Re
 	// Everything OK
 	Ok(())
 }
+
+fn since(matches: &clap::ArgMatches) -> Vec<(&str, &str)> {
+	matches.value_of("since").map(|v| ("since", v)).into_iter().collect()
+}
+
+fn until(matches: &clap::ArgMatches) -> Vec<(&str, &str)> {
+	matches.value_of("until").map(|v| ("until", v)).into_iter().collect()
+}
+
+fn verify(file: &str) -> Result<(), Error> {
+	// Try to open the backup file
+	let data = OpenOptions::new().read(true).open(file)?;
+	// Check the backup structure and checksums
+	let info = surrealdb::backup::verify(BufReader::new(data))?;
+	// Output the backup details
+	info!(
+		target: LOG,
+		"Verified {:?} backup {} taken at {} (format version {}, {} entries)",
+		info.kind,
+		file,
+		info.time,
+		info.version,
+		info.total
+	);
+	// Everything OK
+	Ok(())
+}
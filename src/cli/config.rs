@@ -1,11 +1,14 @@
 use once_cell::sync::OnceCell;
 use std::net::SocketAddr;
+use std::time::Duration;
 
 pub static CF: OnceCell<Config> = OnceCell::new();
 
 #[derive(Clone, Debug)]
 pub struct Config {
 	pub strict: bool,
+	pub timeout: Option<Duration>,
+	pub changelog: Option<Duration>,
 	pub bind: SocketAddr,
 	pub path: String,
 	pub user: String,
@@ -32,9 +35,15 @@ pub fn init(matches: &clap::ArgMatches) {
 	let key = matches.value_of("web-key").map(|v| v.to_owned());
 	// Check if database strict mode is enabled
 	let strict = matches.is_present("strict");
+	// Parse any default query timeout
+	let timeout = matches.value_of("query-timeout").map(|v| *surrealdb::sql::Duration::from(v));
+	// Parse any change log retention
+	let changelog = matches.value_of("change-log").map(|v| *surrealdb::sql::Duration::from(v));
 	// Store the new config object
 	let _ = CF.set(Config {
 		strict,
+		timeout,
+		changelog,
 		bind,
 		path,
 		user,
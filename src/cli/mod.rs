@@ -93,6 +93,28 @@ fn into_valid(v: &str) -> Result<(), String> {
 	}
 }
 
+fn time_valid(v: &str) -> Result<(), String> {
+	match chrono::DateTime::parse_from_rfc3339(v) {
+		Ok(_) => Ok(()),
+		_ => Err(String::from(
+			"\
+			Provide a valid RFC 3339 datetime, such as 2022-09-01T12:00:00Z\
+		",
+		)),
+	}
+}
+
+fn duration_valid(v: &str) -> Result<(), String> {
+	match surrealdb::sql::Duration::from(v).is_zero() {
+		false => Ok(()),
+		true => Err(String::from(
+			"\
+			Provide a valid duration, such as 30s or 5m\
+		",
+		)),
+	}
+}
+
 fn key_valid(v: &str) -> Result<(), String> {
 	match v.len() {
 		16 => Ok(()),
@@ -228,6 +250,26 @@ pub fn init() {
 					.takes_value(false)
 					.help("Whether strict mode is enabled on this database instance"),
 			)
+			.arg(
+				Arg::new("query-timeout")
+					.env("QUERY_TIMEOUT")
+					.long("query-timeout")
+					.takes_value(true)
+					.forbid_empty_values(true)
+					.validator(duration_valid)
+					.help(
+						"The default timeout for statements which do not specify a TIMEOUT clause",
+					),
+			)
+			.arg(
+				Arg::new("change-log")
+					.env("CHANGE_LOG")
+					.long("change-log")
+					.takes_value(true)
+					.forbid_empty_values(true)
+					.validator(duration_valid)
+					.help("Record a change log for incremental backups, kept for the specified duration"),
+			)
 			.arg(
 				Arg::new("log")
 					.short('l')
@@ -259,6 +301,22 @@ pub fn init() {
 					.validator(into_valid)
 					.help("Path to the remote database or file into which to import"),
 			)
+			.arg(
+				Arg::new("since")
+					.long("since")
+					.takes_value(true)
+					.forbid_empty_values(true)
+					.validator(time_valid)
+					.help("Only back up the changes committed after this time"),
+			)
+			.arg(
+				Arg::new("until")
+					.long("until")
+					.takes_value(true)
+					.forbid_empty_values(true)
+					.validator(time_valid)
+					.help("Only restore the changes committed before this time"),
+			)
 			.arg(
 				Arg::new("user")
 					.short('u')
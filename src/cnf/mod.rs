@@ -22,3 +22,12 @@ pub const APP_ENDPOINT: &str = "https://app.surrealdb.com";
 
 // Specifies how many concurrent jobs can be buffered in the worker channel.
 pub const MAX_CONCURRENT_CALLS: usize = 24;
+
+// Specifies how many prepared queries each RPC connection can store.
+pub const MAX_PREPARED_QUERIES: usize = 100;
+
+// Specifies how often in seconds the background index and table builds are continued.
+pub const INDEX_BUILD_INTERVAL: u64 = 1;
+
+// Specifies how often in seconds the datastore maintenance is run.
+pub const TICK_INTERVAL: u64 = 10;
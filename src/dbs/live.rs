@@ -0,0 +1,32 @@
+use crate::dbs::DB;
+use crate::rpc::res::Response;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use surrealdb::channel::Sender;
+use surrealdb::sql::Uuid;
+use tokio::sync::RwLock;
+use warp::ws::Message;
+
+// The currently connected WebSockets
+pub static WEBSOCKETS: Lazy<RwLock<HashMap<Uuid, Sender<Message>>>> = Lazy::new(Default::default);
+
+// The WebSocket connection which owns each live query
+pub static LIVE_QUERIES: Lazy<RwLock<HashMap<Uuid, Uuid>>> = Lazy::new(Default::default);
+
+pub async fn notifications() {
+	// Check if notifications are enabled
+	if let Some(rcv) = DB.get().unwrap().notifications() {
+		// Forward each notification to the subscriber
+		while let Ok(ntf) = rcv.recv().await {
+			// Find the WebSocket which owns the live query
+			let chn = match LIVE_QUERIES.read().await.get(&ntf.id) {
+				Some(ws) => WEBSOCKETS.read().await.get(ws).cloned(),
+				None => None,
+			};
+			// Send the notification to the WebSocket
+			if let Some(chn) = chn {
+				Response::success(None, ntf.into()).send(chn).await;
+			}
+		}
+	}
+}
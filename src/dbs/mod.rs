@@ -1,6 +1,11 @@
+pub mod live;
+
 use crate::cli::CF;
+use crate::cnf::INDEX_BUILD_INTERVAL;
+use crate::cnf::TICK_INTERVAL;
 use crate::err::Error;
 use once_cell::sync::OnceCell;
+use std::time::Duration;
 use surrealdb::Datastore;
 
 pub static DB: OnceCell<Datastore> = OnceCell::new();
@@ -15,10 +20,47 @@ pub async fn init() -> Result<(), Error> {
 		true => info!(target: LOG, "Database strict mode is enabled"),
 		false => info!(target: LOG, "Database strict mode is disabled"),
 	};
+	// Log the default query timeout
+	if let Some(v) = opt.timeout {
+		info!(target: LOG, "Default query timeout is {:?}", v);
+	}
+	// Log the change log retention
+	if let Some(v) = opt.changelog {
+		info!(target: LOG, "Change log is enabled, with a retention of {:?}", v);
+	}
 	// Parse and setup the desired kv datastore
-	let dbs = Datastore::new(&opt.path).await?;
+	let dbs = Datastore::new(&opt.path)
+		.await?
+		.with_notifications()
+		.with_query_timeout(opt.timeout)
+		.with_change_log(opt.changelog)
+		.with_index_builds();
 	// Store database instance
 	let _ = DB.set(dbs);
 	// All ok
 	Ok(())
 }
+
+pub async fn builds() {
+	// Get the database instance
+	let db = DB.get().unwrap();
+	// Continue the index builds periodically
+	loop {
+		if let Err(e) = db.build_indexes().await {
+			warn!(target: LOG, "Index build failed: {}", e);
+		}
+		tokio::time::sleep(Duration::from_secs(INDEX_BUILD_INTERVAL)).await;
+	}
+}
+
+pub async fn ticks() {
+	// Get the database instance
+	let db = DB.get().unwrap();
+	// Run the datastore maintenance periodically
+	loop {
+		if let Err(e) = db.tick().await {
+			warn!(target: LOG, "Datastore maintenance failed: {}", e);
+		}
+		tokio::time::sleep(Duration::from_secs(TICK_INTERVAL)).await;
+	}
+}
@@ -26,6 +26,12 @@ pub enum Error {
 	#[error("There was a problem connecting with the storage engine")]
 	InvalidStorage,
 
+	#[error("The prepared query does not exist")]
+	PreparedQueryNotFound,
+
+	#[error("The maximum number of prepared queries has been reached")]
+	PreparedQueryLimit,
+
 	#[error("There was a problem with the database: {0}")]
 	Db(#[from] DbError),
 
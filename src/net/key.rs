@@ -3,6 +3,7 @@ use crate::dbs::DB;
 use crate::err::Error;
 use crate::net::output;
 use crate::net::session;
+use crate::net::stream;
 use bytes::Bytes;
 use serde::Deserialize;
 use std::str;
@@ -17,6 +18,7 @@ const MAX: u64 = 1024 * 16; // 16 KiB
 struct Query {
 	pub limit: Option<String>,
 	pub start: Option<String>,
+	pub stream: Option<bool>,
 }
 
 pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
@@ -139,6 +141,13 @@ async fn select_all(
 	let vars = map! {
 		String::from("table") => Value::from(table),
 	};
+	// Stream the results as JSON if requested
+	if output == "application/json" && query.stream == Some(true) {
+		return match db.prepare(&sql) {
+			Ok(qry) => Ok(stream::json(qry, session, Some(vars), opt.strict)),
+			Err(err) => Err(warp::reject::custom(Error::from(err))),
+		};
+	}
 	// Execute the query and return the result
 	match db.execute(sql.as_str(), &session, Some(vars), opt.strict).await {
 		Ok(ref res) => match output.as_ref() {
@@ -13,6 +13,7 @@ mod signin;
 mod signup;
 mod sql;
 mod status;
+mod stream;
 mod sync;
 mod version;
 
@@ -63,6 +64,15 @@ pub async fn init() -> Result<(), Error> {
 	// Get local copy of options
 	let opt = CF.get().unwrap();
 
+	// Deliver live query notifications
+	tokio::spawn(crate::dbs::live::notifications());
+
+	// Build indexes in the background
+	tokio::spawn(crate::dbs::builds());
+
+	// Run the datastore maintenance in the background
+	tokio::spawn(crate::dbs::ticks());
+
 	info!(target: LOG, "Starting web server on {}", &opt.bind);
 
 	if let (Some(c), Some(k)) = (&opt.crt, &opt.key) {
@@ -1,5 +1,6 @@
 use http::header::{HeaderValue, CONTENT_TYPE};
 use http::StatusCode;
+use hyper::body::Body;
 use serde::Serialize;
 
 pub enum Output {
@@ -8,6 +9,7 @@ pub enum Output {
 	Json(Vec<u8>), // JSON
 	Cbor(Vec<u8>), // CBOR
 	Pack(Vec<u8>), // MessagePack
+	Stream(Body),  // Chunked JSON
 }
 
 pub fn none() -> Output {
@@ -65,6 +67,12 @@ impl warp::Reply for Output {
 				res.headers_mut().insert(CONTENT_TYPE, con);
 				res
 			}
+			Output::Stream(v) => {
+				let mut res = warp::reply::Response::new(v);
+				let con = HeaderValue::from_static("application/json");
+				res.headers_mut().insert(CONTENT_TYPE, con);
+				res
+			}
 			Output::None => StatusCode::OK.into_response(),
 			Output::Fail => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
 		}
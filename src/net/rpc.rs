@@ -1,5 +1,7 @@
 use crate::cli::CF;
 use crate::cnf::MAX_CONCURRENT_CALLS;
+use crate::cnf::MAX_PREPARED_QUERIES;
+use crate::dbs::live::{LIVE_QUERIES, WEBSOCKETS};
 use crate::dbs::DB;
 use crate::err::Error;
 use crate::net::session;
@@ -9,12 +11,17 @@ use crate::rpc::res::Failure;
 use crate::rpc::res::Response;
 use futures::{SinkExt, StreamExt};
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::sync::Arc;
 use surrealdb::channel;
 use surrealdb::channel::Sender;
 use surrealdb::sql::Object;
 use surrealdb::sql::Strand;
+use surrealdb::sql::Uuid;
 use surrealdb::sql::Value;
+use surrealdb::Chunk;
+use surrealdb::PreparedQuery;
+use surrealdb::QueryType;
 use surrealdb::Session;
 use tokio::sync::RwLock;
 use warp::ws::{Message, WebSocket, Ws};
@@ -34,8 +41,10 @@ async fn socket(ws: WebSocket, session: Session) {
 }
 
 pub struct Rpc {
+	uuid: Uuid,
 	session: Session,
 	vars: BTreeMap<String, Value>,
+	prepared: HashMap<Uuid, PreparedQuery>,
 }
 
 impl Rpc {
@@ -43,12 +52,16 @@ impl Rpc {
 	pub fn new(mut session: Session) -> Arc<RwLock<Rpc>> {
 		// Create a new RPC variables store
 		let vars = BTreeMap::new();
+		// Create a new RPC prepared query store
+		let prepared = HashMap::new();
 		// Enable real-time live queries
 		session.rt = true;
 		// Create and store the Rpc connection
 		Arc::new(RwLock::new(Rpc {
+			uuid: Uuid::new(),
 			session,
 			vars,
+			prepared,
 		}))
 	}
 
@@ -56,6 +69,9 @@ impl Rpc {
 	pub async fn serve(rpc: Arc<RwLock<Rpc>>, ws: WebSocket) {
 		// Create a channel for sending messages
 		let (chn, mut rcv) = channel::new(MAX_CONCURRENT_CALLS);
+		// Register the WebSocket for notifications
+		let id = rpc.read().await.uuid.clone();
+		WEBSOCKETS.write().await.insert(id.clone(), chn.clone());
 		// Split the socket into send and recv
 		let (mut wtx, mut wrx) = ws.split();
 		// Send messages to the client
@@ -72,6 +88,10 @@ impl Rpc {
 				}
 			}
 		}
+		// Remove the WebSocket from notifications
+		WEBSOCKETS.write().await.remove(&id);
+		// Kill any remaining live queries
+		rpc.read().await.cleanup().await;
 	}
 
 	// Call RPC methods from the WebSocket
@@ -153,6 +173,28 @@ impl Rpc {
 				(Value::Strand(s), Value::Object(o)) => rpc.read().await.query_with(s, o).await,
 				_ => return Response::failure(id, Failure::INVALID_PARAMS).send(chn).await,
 			},
+			"stream" => match params.take_two() {
+				(Value::Strand(s), o) if o.is_none() => {
+					rpc.read().await.stream(s, None, id.clone(), chn.clone()).await
+				}
+				(Value::Strand(s), Value::Object(o)) => {
+					rpc.read().await.stream(s, o, id.clone(), chn.clone()).await
+				}
+				_ => return Response::failure(id, Failure::INVALID_PARAMS).send(chn).await,
+			},
+			"prepare" => match params.take_one() {
+				Value::Strand(s) => rpc.write().await.prepare(s).await,
+				_ => return Response::failure(id, Failure::INVALID_PARAMS).send(chn).await,
+			},
+			"deallocate" => match params.take_one() {
+				v if v.is_uuid() => rpc.write().await.deallocate(v).await,
+				_ => return Response::failure(id, Failure::INVALID_PARAMS).send(chn).await,
+			},
+			"execute" => match params.take_two() {
+				(v, o) if v.is_uuid() && o.is_none() => rpc.read().await.execute(v, None).await,
+				(v, Value::Object(o)) if v.is_uuid() => rpc.read().await.execute(v, o).await,
+				_ => return Response::failure(id, Failure::INVALID_PARAMS).send(chn).await,
+			},
 			"select" => match params.take_one() {
 				v if v.is_thing() => rpc.read().await.select(v).await,
 				v if v.is_strand() => rpc.read().await.select(v).await,
@@ -274,6 +316,41 @@ impl Rpc {
 	// Methods for live queries
 	// ------------------------------
 
+	async fn register(&self, res: &[surrealdb::Response]) {
+		for v in res {
+			if let (QueryType::Live, Ok(Value::Uuid(lv))) = (v.query_type, &v.result) {
+				LIVE_QUERIES.write().await.insert(lv.clone(), self.uuid.clone());
+			}
+		}
+	}
+
+	async fn cleanup(&self) {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Get local copy of options
+		let opt = CF.get().unwrap();
+		// Remove the live queries of this WebSocket
+		let mut lives = vec![];
+		LIVE_QUERIES.write().await.retain(|lv, ws| match *ws == self.uuid {
+			true => {
+				lives.push(lv.clone());
+				false
+			}
+			false => true,
+		});
+		// Kill each of the live queries
+		for lv in lives {
+			// Specify the SQL query string
+			let sql = "KILL $id";
+			// Specify the query parameters
+			let var = Some(map! {
+				String::from("id") => lv.into(),
+			});
+			// Execute the query on the database
+			let _ = kvs.execute(sql, &self.session, var, opt.strict).await;
+		}
+	}
+
 	async fn kill(&self, id: Value) -> Result<Value, Error> {
 		// Get a database reference
 		let kvs = DB.get().unwrap();
@@ -283,13 +360,17 @@ impl Rpc {
 		let sql = "KILL $id";
 		// Specify the query parameters
 		let var = Some(map! {
-			String::from("id") => id,
+			String::from("id") => id.clone(),
 			=> &self.vars
 		});
 		// Execute the query on the database
 		let mut res = kvs.execute(sql, &self.session, var, opt.strict).await?;
 		// Extract the first query result
 		let res = res.remove(0).result?;
+		// Unregister the live query
+		if let Value::Uuid(lv) = id {
+			LIVE_QUERIES.write().await.remove(&lv);
+		}
 		// Return the result to the client
 		Ok(res)
 	}
@@ -308,6 +389,8 @@ impl Rpc {
 		});
 		// Execute the query on the database
 		let mut res = kvs.execute(sql, &self.session, var, opt.strict).await?;
+		// Register the live query
+		self.register(&res).await;
 		// Extract the first query result
 		let res = res.remove(0).result?;
 		// Return the result to the client
@@ -327,6 +410,8 @@ impl Rpc {
 		let var = Some(self.vars.clone());
 		// Execute the query on the database
 		let res = kvs.execute(&sql, &self.session, var, opt.strict).await?;
+		// Register any live queries
+		self.register(&res).await;
 		// Extract the first query result
 		let res = res.into_iter().collect::<Vec<Value>>().into();
 		// Return the result to the client
@@ -342,6 +427,114 @@ impl Rpc {
 		let var = Some(mrg! { vars.0, &self.vars });
 		// Execute the query on the database
 		let res = kvs.execute(&sql, &self.session, var, opt.strict).await?;
+		// Register any live queries
+		self.register(&res).await;
+		// Extract the first query result
+		let res = res.into_iter().collect::<Vec<Value>>().into();
+		// Return the result to the client
+		Ok(res)
+	}
+
+	// Send the records of each read-only statement in batches, as
+	// `{ query, result }` responses with the same request id, followed
+	// by the responses of the statements once the query is complete
+	async fn stream(
+		&self,
+		sql: Strand,
+		vars: impl Into<Option<Object>>,
+		id: Option<String>,
+		chn: Sender<Message>,
+	) -> Result<Value, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Get local copy of options
+		let opt = CF.get().unwrap();
+		// Specify the query parameters
+		let var = match vars.into() {
+			Some(mut vars) => Some(mrg! { vars.0, &self.vars }),
+			None => Some(self.vars.clone()),
+		};
+		// Create a new bounded channel
+		let (snd, rcv) = channel::new(1);
+		// Execute the query on the database
+		let qry = kvs.execute_stream(&sql, &self.session, var, opt.strict, snd);
+		// Send each batch of records to the client
+		let out = async {
+			let mut out = vec![];
+			while let Ok(v) = rcv.recv().await {
+				match v {
+					Chunk::Values(v) => {
+						let res = Value::from(map! {
+							String::from("query") => Value::from(out.len()),
+							String::from("result") => Value::from(v),
+						});
+						Response::success(id.clone(), res).send(chn.clone()).await;
+					}
+					Chunk::Response(v) => out.push(v),
+				}
+			}
+			out
+		};
+		// Wait for the query to complete
+		let (res, out) = futures::join!(qry, out);
+		res?;
+		// Register any live queries
+		self.register(&out).await;
+		// Return the statement responses to the client
+		let res = out.into_iter().collect::<Vec<Value>>().into();
+		Ok(res)
+	}
+
+	// ------------------------------
+	// Methods for prepared queries
+	// ------------------------------
+
+	async fn prepare(&mut self, sql: Strand) -> Result<Value, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Check the number of stored prepared queries
+		if self.prepared.len() >= MAX_PREPARED_QUERIES {
+			return Err(Error::PreparedQueryLimit);
+		}
+		// Parse the query on the database
+		let qry = kvs.prepare(&sql)?;
+		// Store the prepared query
+		let id = Uuid::new();
+		self.prepared.insert(id.clone(), qry);
+		// Return the prepared query id to the client
+		Ok(id.into())
+	}
+
+	async fn deallocate(&mut self, id: Value) -> Result<Value, Error> {
+		// Remove the prepared query
+		match id {
+			Value::Uuid(id) => match self.prepared.remove(&id) {
+				Some(_) => Ok(Value::None),
+				None => Err(Error::PreparedQueryNotFound),
+			},
+			_ => Err(Error::PreparedQueryNotFound),
+		}
+	}
+
+	async fn execute(&self, id: Value, vars: impl Into<Option<Object>>) -> Result<Value, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Get local copy of options
+		let opt = CF.get().unwrap();
+		// Fetch the prepared query
+		let qry = match id {
+			Value::Uuid(id) => self.prepared.get(&id).ok_or(Error::PreparedQueryNotFound)?,
+			_ => return Err(Error::PreparedQueryNotFound),
+		};
+		// Specify the query parameters
+		let var = match vars.into() {
+			Some(mut vars) => Some(mrg! { vars.0, &self.vars }),
+			None => Some(self.vars.clone()),
+		};
+		// Execute the query on the database
+		let res = kvs.execute_prepared(qry, &self.session, var, opt.strict).await?;
+		// Register any live queries
+		self.register(&res).await;
 		// Extract the first query result
 		let res = res.into_iter().collect::<Vec<Value>>().into();
 		// Return the result to the client
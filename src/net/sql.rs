@@ -3,14 +3,21 @@ use crate::dbs::DB;
 use crate::err::Error;
 use crate::net::output;
 use crate::net::session;
+use crate::net::stream;
 use bytes::Bytes;
 use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
 use surrealdb::Session;
 use warp::ws::{Message, WebSocket, Ws};
 use warp::Filter;
 
 const MAX: u64 = 1024 * 1024; // 1 MiB
 
+#[derive(Default, Deserialize, Debug, Clone)]
+struct Query {
+	pub stream: Option<bool>,
+}
+
 pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
 	// Set base path
 	let base = warp::path("sql").and(warp::path::end());
@@ -21,6 +28,7 @@ pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejecti
 		.and(warp::post())
 		.and(session::build())
 		.and(warp::header::<String>(http::header::CONTENT_TYPE.as_str()))
+		.and(warp::query())
 		.and(warp::body::content_length_limit(MAX))
 		.and(warp::body::bytes())
 		.and_then(handler);
@@ -36,6 +44,7 @@ pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejecti
 async fn handler(
 	session: Session,
 	output: String,
+	query: Query,
 	sql: Bytes,
 ) -> Result<impl warp::Reply, warp::Rejection> {
 	// Get a database reference
@@ -44,9 +53,18 @@ async fn handler(
 	let opt = CF.get().unwrap();
 	// Convert the received sql query
 	let sql = std::str::from_utf8(&sql).unwrap();
+	// Stream the results as JSON if requested
+	if output == "application/json" && query.stream == Some(true) {
+		return match db.prepare(sql) {
+			// Execute the received sql query
+			Ok(qry) => Ok(stream::json(qry, session, None, opt.strict)),
+			// There was an error when parsing the query
+			Err(err) => Err(warp::reject::custom(Error::from(err))),
+		};
+	}
 	// Execute the received sql query
 	match db.execute(sql, &session, None, opt.strict).await {
-		// Convert the response to JSON
+		// Convert the response to the output format
 		Ok(res) => match output.as_ref() {
 			"application/json" => Ok(output::json(&res)),
 			"application/cbor" => Ok(output::cbor(&res)),
@@ -0,0 +1,155 @@
+use crate::dbs::DB;
+use crate::net::output::Output;
+use bytes::Bytes;
+use hyper::body::{Body, Sender};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::mem;
+use surrealdb::channel::Receiver;
+use surrealdb::sql::Value;
+use surrealdb::Chunk;
+use surrealdb::PreparedQuery;
+use surrealdb::Session;
+
+// Execute a query, and stream the responses as a chunked JSON body
+pub fn json(
+	qry: PreparedQuery,
+	session: Session,
+	vars: Option<BTreeMap<String, Value>>,
+	strict: bool,
+) -> Output {
+	// Create a chunked response
+	let (chn, bdy) = Body::channel();
+	// Create a new bounded channel
+	let (snd, rcv) = surrealdb::channel::new(1);
+	// Spawn the query and the response
+	tokio::spawn(async move {
+		// Get the datastore reference
+		let db = DB.get().unwrap();
+		// Process the query and the results together
+		let qry = db.execute_prepared_stream(&qry, &session, vars, strict, snd);
+		let (res, (mut chn, mut out)) = futures::join!(qry, write(rcv, chn));
+		// Write the query error as the last response
+		if let Err(e) = res {
+			out.fail(&e.to_string());
+		}
+		// Finish the array of responses
+		out.buf.push(b']');
+		let _ = chn.send_data(Bytes::from(out.buf)).await;
+	});
+	// Return the chunked body
+	Output::Stream(bdy)
+}
+
+// The state of the JSON output of a streamed query
+#[derive(Default)]
+struct State {
+	// The output which has not been sent yet
+	buf: Vec<u8>,
+	// Whether a response has been written
+	next: bool,
+	// Whether a streamed result is being written
+	open: bool,
+	// Whether the streamed result has any records
+	empty: bool,
+}
+
+impl State {
+	// Write a record to the streamed result
+	fn value<T: Serialize>(&mut self, v: &T) {
+		if !self.empty {
+			self.buf.push(b',');
+		}
+		append(&mut self.buf, v);
+		self.empty = false;
+	}
+	// Start a new response
+	fn start(&mut self) {
+		if self.next {
+			self.buf.push(b',');
+		}
+		self.next = true;
+	}
+	// Write an error response, closing any streamed result
+	// which was interrupted, so that the output remains valid
+	fn fail(&mut self, err: &str) {
+		if self.open {
+			self.buf.extend_from_slice(b"],\"status\":\"ERR\",\"detail\":");
+			append(&mut self.buf, &err);
+			self.buf.push(b'}');
+			self.open = false;
+		} else {
+			self.start();
+			append(&mut self.buf, &map! { "status" => "ERR", "detail" => err });
+		}
+	}
+}
+
+// Write the streamed query results as a JSON array of responses. The
+// records of a streamed statement are written to the `result` field
+// as they are received, followed by the remaining response fields.
+// The array is left open, so that the caller can write the outcome.
+async fn write(rcv: Receiver<Chunk>, mut chn: Sender) -> (Sender, State) {
+	let mut out = State {
+		buf: vec![b'['],
+		..Default::default()
+	};
+	// Write each chunk as it is received
+	while let Ok(v) = rcv.recv().await {
+		match v {
+			Chunk::Values(v) => {
+				// Start a new streamed response
+				if !out.open {
+					out.start();
+					out.buf.extend_from_slice(b"{\"result\":[");
+					out.open = true;
+					out.empty = true;
+				}
+				// Write each of the records
+				for v in v {
+					out.value(&v);
+				}
+			}
+			Chunk::Response(v) if out.open => {
+				// Separate the result from the other fields
+				let mut v = match serde_json::to_value(&v) {
+					Ok(serde_json::Value::Object(v)) => v,
+					_ => Default::default(),
+				};
+				// Write any remaining records
+				if let Some(serde_json::Value::Array(v)) = v.remove("result") {
+					for v in v {
+						out.value(&v);
+					}
+				}
+				out.buf.push(b']');
+				// Write the other response fields
+				for (k, v) in v {
+					out.buf.push(b',');
+					append(&mut out.buf, &k);
+					out.buf.push(b':');
+					append(&mut out.buf, &v);
+				}
+				out.buf.push(b'}');
+				out.open = false;
+			}
+			Chunk::Response(v) => {
+				out.start();
+				append(&mut out.buf, &v);
+			}
+		}
+		// Send the output to the client
+		if chn.send_data(Bytes::from(mem::take(&mut out.buf))).await.is_err() {
+			break;
+		}
+	}
+	(chn, out)
+}
+
+// Append a value to the output as JSON
+fn append<T>(buf: &mut Vec<u8>, val: &T)
+where
+	T: Serialize,
+{
+	let _ = serde_json::to_writer(buf, val);
+}
@@ -1,21 +1,178 @@
-use warp::http;
+use crate::dbs::DB;
+use crate::err::Error;
+use crate::net::output;
+use crate::net::session;
+use bytes::{Buf, Bytes};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use hyper::body::Body;
+use serde::Deserialize;
+use std::io::BufReader;
+use std::path::Path;
+use surrealdb::Session;
+use tokio::fs::{remove_file, File, OpenOptions};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use warp::Filter;
 
+#[derive(Default, Deserialize, Debug, Clone)]
+struct Query {
+	pub since: Option<String>,
+	pub until: Option<String>,
+	pub before: Option<String>,
+}
+
 pub fn config() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
 	// Set base path
 	let base = warp::path("sync").and(warp::path::end());
 	// Set save method
-	let save = base.and(warp::get()).and_then(save);
+	let save = base.and(warp::get()).and(session::build()).and(warp::query()).and_then(save);
 	// Set load method
-	let load = base.and(warp::post()).and_then(load);
+	let load = base
+		.and(warp::post())
+		.and(session::build())
+		.and(warp::query())
+		.and(warp::body::stream())
+		.and_then(load);
+	// Set prune method
+	let prune = base.and(warp::delete()).and(session::build()).and(warp::query()).and_then(prune);
 	// Specify route
-	save.or(load)
+	save.or(load).or(prune)
+}
+
+async fn load<S, B>(
+	session: Session,
+	query: Query,
+	body: S,
+) -> Result<impl warp::Reply, warp::Rejection>
+where
+	S: Stream<Item = Result<B, warp::Error>> + Unpin,
+	B: Buf,
+{
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Parse the recovery time
+			let until = time(query.until)?;
+			// Write the backup to a temporary file, so that the
+			// whole backup is verified before it is restored
+			let path = std::env::temp_dir().join(format!("surreal-{}.db", rand::random::<u64>()));
+			let res = match receive(&path, body).await {
+				// Restore the backup into the datastore
+				Ok(file) => {
+					let file = file.into_std().await;
+					db.restore(BufReader::new(file), until).await.map_err(Error::from)
+				}
+				Err(err) => Err(err),
+			};
+			// Remove the temporary file
+			let _ = remove_file(&path).await;
+			// Return the restore result
+			match res {
+				Ok(_) => Ok(output::none()),
+				Err(err) => Err(warp::reject::custom(err)),
+			}
+		}
+		// There was an error with permissions
+		_ => Err(warp::reject::custom(Error::InvalidAuth)),
+	}
+}
+
+async fn save(session: Session, query: Query) -> Result<impl warp::Reply, warp::Rejection> {
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Parse the incremental backup time
+			let since = time(query.since)?;
+			// Create a chunked response
+			let (mut chn, bdy) = Body::channel();
+			// Create a new bounded channel
+			let (snd, rcv) = surrealdb::channel::new(1);
+			// Spawn a new datastore backup
+			let task = tokio::spawn(db.backup(since, snd));
+			// Wait for the backup header, so that a backup
+			// which can not be started is returned as an error
+			let head = match rcv.recv().await {
+				Ok(v) => v,
+				Err(_) => {
+					return match task.await {
+						Ok(Err(err)) => Err(warp::reject::custom(Error::from(err))),
+						_ => Err(warp::reject::custom(Error::Request)),
+					}
+				}
+			};
+			// Process all processed values
+			tokio::spawn(async move {
+				let _ = chn.send_data(Bytes::from(head)).await;
+				while let Ok(v) = rcv.recv().await {
+					let _ = chn.send_data(Bytes::from(v)).await;
+				}
+				// Abort the response if the backup failed, so
+				// that a truncated backup is not a successful one
+				if !matches!(task.await, Ok(Ok(_))) {
+					chn.abort();
+				}
+			});
+			// Return the chunked body
+			Ok(warp::reply::Response::new(bdy))
+		}
+		// There was an error with permissions
+		_ => Err(warp::reject::custom(Error::InvalidAuth)),
+	}
+}
+
+async fn prune(session: Session, query: Query) -> Result<impl warp::Reply, warp::Rejection> {
+	// Check the permissions
+	match session.au.is_kv() {
+		true => {
+			// Get the datastore reference
+			let db = DB.get().unwrap();
+			// Parse the pruning time
+			let before = match time(query.before)? {
+				Some(v) => v,
+				None => return Err(warp::reject::custom(Error::Request)),
+			};
+			// Remove the older change log entries
+			match db.prune(before).await {
+				Ok(_) => Ok(output::none()),
+				Err(err) => Err(warp::reject::custom(Error::from(err))),
+			}
+		}
+		// There was an error with permissions
+		_ => Err(warp::reject::custom(Error::InvalidAuth)),
+	}
 }
 
-pub async fn load() -> Result<impl warp::Reply, warp::Rejection> {
-	Ok(warp::reply::with_status("Load", http::StatusCode::OK))
+async fn receive<S, B>(path: &Path, mut body: S) -> Result<File, Error>
+where
+	S: Stream<Item = Result<B, warp::Error>> + Unpin,
+	B: Buf,
+{
+	let mut file = OpenOptions::new().read(true).write(true).create_new(true).open(path).await?;
+	// Write each chunk of the request body
+	while let Some(chunk) = body.next().await {
+		let mut chunk = chunk.map_err(|_| Error::Request)?;
+		while chunk.has_remaining() {
+			let len = chunk.chunk().len();
+			file.write_all(chunk.chunk()).await?;
+			chunk.advance(len);
+		}
+	}
+	// Read the file from the start
+	file.flush().await?;
+	file.rewind().await?;
+	Ok(file)
 }
 
-pub async fn save() -> Result<impl warp::Reply, warp::Rejection> {
-	Ok(warp::reply::with_status("Save", http::StatusCode::OK))
+fn time(v: Option<String>) -> Result<Option<DateTime<Utc>>, warp::Rejection> {
+	match v {
+		Some(v) => match DateTime::parse_from_rfc3339(&v) {
+			Ok(v) => Ok(Some(v.with_timezone(&Utc))),
+			Err(_) => Err(warp::reject::custom(Error::Request)),
+		},
+		None => Ok(None),
+	}
 }